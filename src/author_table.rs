@@ -0,0 +1,348 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use rfd::FileDialog;
+use std::{
+    fs, io,
+    path::Path,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use crate::authors::{
+    ages_by_year, ages_markdown, authors_markdown, ownership_markdown, AgeStat, AuthorReport,
+    AuthorStat, ModuleAuthors,
+};
+use crate::charts;
+use crate::csv;
+use crate::format;
+use crate::git;
+use crate::i18n::Texts;
+use crate::project::dir_display_name;
+use crate::stat::{ScanProgress, StatResult};
+
+/* ---------------- 按作者归属 ---------------- */
+// blame 比统计慢得多，只在点击后于后台线程进行；换上新的统计结果后需要重新归属
+#[derive(Default)]
+pub struct AuthorView {
+    report: Option<AuthorReport>,
+    pending: Option<Receiver<Result<AuthorReport, String>>>,
+    error: Option<String>,
+    // 选中的模块在 report.modules 中的下标，None 为整个工程
+    module: Option<usize>,
+    mode: AuthorMode,
+    // 代码年龄按季度而不是按年分段
+    by_quarter: bool,
+}
+
+// 同一份 blame 结果的三种看法
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum AuthorMode {
+    #[default]
+    Authors,
+    // 各模块的主要作者
+    Ownership,
+    // 按最后修改时间分段的代码年龄
+    Age,
+}
+
+impl AuthorView {
+    fn start(&mut self, root: &Path, result: &StatResult, workers: usize, ctx: &egui::Context) {
+        let (tx, rx) = mpsc::channel();
+        let root = root.to_path_buf();
+        let (files, modules) = (result.files.clone(), result.modules.clone());
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let progress = ScanProgress::default();
+            let report = git::blame_authors(&root, &files, &modules, workers, &progress)
+                .map_err(|e| e.message().to_owned());
+            let _ = tx.send(report);
+            ctx.request_repaint();
+        });
+        self.pending = Some(rx);
+        self.error = None;
+    }
+
+    fn poll(&mut self) {
+        let Some(rx) = &self.pending else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(report)) => {
+                self.pending = None;
+                self.module = None;
+                self.report = Some(report);
+            }
+            Ok(Err(e)) => {
+                self.pending = None;
+                self.error = Some(e);
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.pending = None,
+        }
+    }
+
+    // 当前选中范围（整个工程或某个模块）的作者
+    fn selected(&self) -> &[AuthorStat] {
+        let Some(report) = &self.report else {
+            return &[];
+        };
+        match self.module.and_then(|i| report.modules.get(i)) {
+            Some(m) => &m.authors,
+            None => &report.authors,
+        }
+    }
+
+    // 当前选中范围的代码年龄，按所选粒度分段
+    fn selected_ages(&self) -> Vec<AgeStat> {
+        let Some(report) = &self.report else {
+            return Vec::new();
+        };
+        let ages = match self.module.and_then(|i| report.modules.get(i)) {
+            Some(m) => &m.ages,
+            None => &report.ages,
+        };
+        if self.by_quarter {
+            ages.clone()
+        } else {
+            ages_by_year(ages)
+        }
+    }
+
+    // 代码年龄图的响应，供调用方提供保存图片
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: &Path,
+        result: &StatResult,
+        workers: usize,
+        t: &Texts,
+    ) -> Option<egui::Response> {
+        self.poll();
+        let running = self.pending.is_some();
+        ui.horizontal(|ui| {
+            let label = if self.report.is_some() {
+                t.blame_rerun
+            } else {
+                t.blame_run
+            };
+            if ui
+                .add_enabled(!running, egui::Button::new(label))
+                .on_hover_text(t.blame_hint)
+                .clicked()
+            {
+                self.start(root, result, workers, ui.ctx());
+            }
+            if running {
+                ui.spinner();
+                ui.weak(t.blame_running);
+            }
+            let Some(report) = &self.report else {
+                return;
+            };
+            ui.separator();
+            ui.selectable_value(&mut self.mode, AuthorMode::Authors, t.by_author);
+            ui.selectable_value(&mut self.mode, AuthorMode::Ownership, t.ownership)
+                .on_hover_text(t.ownership_hint);
+            ui.selectable_value(&mut self.mode, AuthorMode::Age, t.code_age)
+                .on_hover_text(t.code_age_hint);
+            ui.separator();
+            let selected = match self.module.and_then(|i| report.modules.get(i)) {
+                Some(m) => charts::module_name(m.name.as_deref(), m.plugin.as_deref(), t),
+                None => t.all_modules.to_owned(),
+            };
+            ui.add_enabled_ui(self.mode != AuthorMode::Ownership, |ui| {
+                egui::ComboBox::from_id_source("author_module")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.module, None, t.all_modules);
+                        for (i, m) in report.modules.iter().enumerate() {
+                            if !m.authors.is_empty() {
+                                let name =
+                                    charts::module_name(m.name.as_deref(), m.plugin.as_deref(), t);
+                                ui.selectable_value(&mut self.module, Some(i), name);
+                            }
+                        }
+                    });
+            });
+            if self.mode == AuthorMode::Age {
+                ui.selectable_value(&mut self.by_quarter, false, t.age_by_year);
+                ui.selectable_value(&mut self.by_quarter, true, t.age_by_quarter);
+            }
+            let mut exported = None;
+            ui.menu_button(t.export, |ui| {
+                for (name, ext) in [("CSV", "csv"), ("Markdown", "md")] {
+                    if ui.button(name).clicked() {
+                        ui.close_menu();
+                        exported = Some((name, ext));
+                    }
+                }
+            });
+            if let Some((name, ext)) = exported {
+                if let Err(e) = self.export(root, name, ext) {
+                    self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e));
+                }
+            }
+        });
+
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        let Some(report) = &self.report else {
+            if !running {
+                ui.weak(t.blame_empty);
+            }
+            return None;
+        };
+        if report.untracked > 0 {
+            ui.weak(format!(
+                "{}{}{}",
+                t.blame_untracked,
+                t.colon,
+                format::count(report.untracked, t)
+            ));
+        }
+        ui.add_space(4.0);
+        match self.mode {
+            AuthorMode::Authors => author_table(ui, self.selected(), t),
+            AuthorMode::Ownership => ownership_table(ui, &report.modules, t),
+            AuthorMode::Age => return charts::age_chart(ui, &self.selected_ages(), t),
+        }
+        None
+    }
+
+    fn export(&self, root: &Path, name: &str, ext: &str) -> io::Result<()> {
+        let Some(path) = FileDialog::new()
+            .add_filter(name, &[ext])
+            .set_file_name(format!(
+                "{}-{}.{}",
+                dir_display_name(root),
+                match self.mode {
+                    AuthorMode::Authors => "authors",
+                    AuthorMode::Ownership => "owners",
+                    AuthorMode::Age => "code-age",
+                },
+                ext
+            ))
+            .save_file()
+        else {
+            return Ok(());
+        };
+        let csv = ext == "csv";
+        let text = match (self.mode, &self.report) {
+            (AuthorMode::Ownership, Some(report)) if csv => csv::ownership_csv(&report.modules),
+            (AuthorMode::Ownership, Some(report)) => ownership_markdown(&report.modules),
+            (AuthorMode::Age, _) if csv => csv::ages_csv(&self.selected_ages()),
+            (AuthorMode::Age, _) => ages_markdown(&self.selected_ages()),
+            _ if csv => csv::authors_csv(self.selected()),
+            _ => authors_markdown(self.selected()),
+        };
+        fs::write(path, text)
+    }
+}
+
+fn author_table(ui: &mut egui::Ui, authors: &[AuthorStat], t: &Texts) {
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    let total: usize = authors.iter().map(|a| a.lines.code).sum();
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::remainder().at_least(160.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 5)
+        .header(row_height + 6.0, |mut header| {
+            for label in [
+                t.col_author,
+                t.files_unit,
+                t.col_code,
+                t.col_share,
+                t.col_comment,
+                t.col_comment_ratio,
+            ] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, authors.len(), |mut row| {
+                let a = &authors[row.index()];
+                row.col(|ui| {
+                    let label = ui.label(&a.name);
+                    if !a.email.is_empty() {
+                        label.on_hover_text(&a.email);
+                    }
+                });
+                row.col(|ui| {
+                    ui.label(format::count(a.files, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(a.lines.code, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::percent(a.lines.code, total));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(a.lines.comment, t));
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}%", a.lines.comment_ratio() * 100.0));
+                });
+            });
+        });
+}
+
+// 每个模块一行；还没有可归属代码行的模块不列出
+fn ownership_table(ui: &mut egui::Ui, modules: &[ModuleAuthors], t: &Texts) {
+    let owned: Vec<_> = modules
+        .iter()
+        .filter_map(|m| Some((m, m.owner()?)))
+        .collect();
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(160.0).clip(true))
+        .column(Column::remainder().at_least(160.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 3)
+        .header(row_height + 6.0, |mut header| {
+            for label in [
+                t.col_module,
+                t.col_owner,
+                t.col_share,
+                t.col_code,
+                t.col_authors,
+            ] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, owned.len(), |mut row| {
+                let (m, (owner, share)) = owned[row.index()];
+                row.col(|ui| {
+                    ui.label(charts::module_name(
+                        m.name.as_deref(),
+                        m.plugin.as_deref(),
+                        t,
+                    ));
+                });
+                row.col(|ui| {
+                    let label = ui.label(&owner.name);
+                    if !owner.email.is_empty() {
+                        label.on_hover_text(&owner.email);
+                    }
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}%", share * 100.0));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(m.code(), t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(m.authors.len(), t));
+                });
+            });
+        });
+}
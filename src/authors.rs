@@ -0,0 +1,237 @@
+use serde::Serialize;
+use std::fmt::Write as _;
+
+use crate::stat::{LineCounts, ModuleStat};
+
+/* ---------------- 按作者汇总 ---------------- */
+// 每位作者名下的行数；行的归属由 git blame 决定，这里只负责汇总后的报告
+#[derive(Clone, Default, Serialize)]
+pub struct AuthorStat {
+    pub name: String,
+    pub email: String,
+    // 至少有一行归属于该作者的文件数
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+impl AuthorStat {
+    fn same_author(&self, other: &AuthorStat) -> bool {
+        self.name == other.name && self.email == other.email
+    }
+}
+
+// 把一个文件或模块中的各作者并入 into
+pub fn merge_authors(into: &mut Vec<AuthorStat>, authors: &[AuthorStat]) {
+    for author in authors {
+        match into.iter_mut().find(|a| a.same_author(author)) {
+            Some(existing) => {
+                existing.files += author.files;
+                existing.lines += author.lines;
+            }
+            None => into.push(author.clone()),
+        }
+    }
+}
+
+// 按有效代码行从多到少，相同时按名字
+pub fn sort_authors(authors: &mut [AuthorStat]) {
+    authors.sort_by(|a, b| b.lines.code.cmp(&a.lines.code).then(a.name.cmp(&b.name)));
+}
+
+fn share(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        "0.0%".to_owned()
+    } else {
+        format!("{:.1}%", part as f64 / whole as f64 * 100.0)
+    }
+}
+
+// 表头固定为英文，与其他 Markdown 报告一致
+pub fn authors_markdown(authors: &[AuthorStat]) -> String {
+    let total: usize = authors.iter().map(|a| a.lines.code).sum();
+    let mut out = String::from(
+        "| Author | Files | Code | Share | Comment | Comment ratio |\n\
+         |---|---:|---:|---:|---:|---:|\n",
+    );
+    for author in authors {
+        let name = if author.email.is_empty() {
+            author.name.clone()
+        } else {
+            format!("{} <{}>", author.name, author.email)
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {:.1}% |",
+            name.replace('|', "\\|"),
+            author.files,
+            author.lines.code,
+            share(author.lines.code, total),
+            author.lines.comment,
+            author.lines.comment_ratio() * 100.0
+        );
+    }
+    out
+}
+
+/* ---------------- 代码年龄 ---------------- */
+// 最后修改落在同一季度的行数；quarter 为 1 到 4，按年合并后为 0
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct AgeStat {
+    pub year: i32,
+    pub quarter: u32,
+    pub lines: LineCounts,
+}
+
+impl AgeStat {
+    pub fn label(&self) -> String {
+        if self.quarter == 0 {
+            self.year.to_string()
+        } else {
+            format!("{} Q{}", self.year, self.quarter)
+        }
+    }
+}
+
+// 把 ages 并入 into，保持从早到晚的顺序
+pub fn merge_ages(into: &mut Vec<AgeStat>, ages: &[AgeStat]) {
+    for age in ages {
+        let key = (age.year, age.quarter);
+        match into.binary_search_by_key(&key, |a| (a.year, a.quarter)) {
+            Ok(i) => into[i].lines += age.lines,
+            Err(i) => into.insert(i, *age),
+        }
+    }
+}
+
+pub fn ages_by_year(ages: &[AgeStat]) -> Vec<AgeStat> {
+    let years: Vec<AgeStat> = ages.iter().map(|a| AgeStat { quarter: 0, ..*a }).collect();
+    let mut out = Vec::new();
+    merge_ages(&mut out, &years);
+    out
+}
+
+pub fn ages_markdown(ages: &[AgeStat]) -> String {
+    let total: usize = ages.iter().map(|a| a.lines.code).sum();
+    let mut out = String::from(
+        "| Period | Code | Share | Comment |\n\
+         |---|---:|---:|---:|\n",
+    );
+    for age in ages {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            age.label(),
+            age.lines.code,
+            share(age.lines.code, total),
+            age.lines.comment
+        );
+    }
+    out
+}
+
+/* ---------------- 整个工程的归属结果 ---------------- */
+#[derive(Serialize)]
+pub struct ModuleAuthors {
+    pub name: Option<String>,
+    pub plugin: Option<String>,
+    pub authors: Vec<AuthorStat>,
+    pub ages: Vec<AgeStat>,
+}
+
+impl ModuleAuthors {
+    // 模块的主要作者（有效代码行最多）及其占比；没有可归属的代码行时为 None
+    pub fn owner(&self) -> Option<(&AuthorStat, f64)> {
+        let owner = self.authors.first()?;
+        let total: usize = self.authors.iter().map(|a| a.lines.code).sum();
+        (total > 0).then(|| (owner, owner.lines.code as f64 / total as f64))
+    }
+
+    pub fn code(&self) -> usize {
+        self.authors.iter().map(|a| a.lines.code).sum()
+    }
+}
+
+// 每个模块一行，列出主要作者，方便新人知道该找谁
+pub fn ownership_markdown(modules: &[ModuleAuthors]) -> String {
+    let mut out = String::from(
+        "| Module | Plugin | Owner | Share | Code |\n\
+         |---|---|---|---:|---:|\n",
+    );
+    let cell = |s: &str| s.replace('|', "\\|");
+    for m in modules {
+        let Some((owner, share)) = m.owner() else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {:.1}% | {} |",
+            cell(m.name.as_deref().unwrap_or_default()),
+            cell(m.plugin.as_deref().unwrap_or_default()),
+            cell(&owner.name),
+            share * 100.0,
+            m.code()
+        );
+    }
+    out
+}
+
+#[derive(Default, Serialize)]
+pub struct AuthorReport {
+    pub authors: Vec<AuthorStat>,
+    pub ages: Vec<AgeStat>,
+    // 顺序与统计结果中的模块相同
+    pub modules: Vec<ModuleAuthors>,
+    // 不在版本库中、无法归属的文件数
+    pub untracked: usize,
+}
+
+impl AuthorReport {
+    pub fn new(modules: &[ModuleStat]) -> Self {
+        Self {
+            modules: modules
+                .iter()
+                .map(|m| ModuleAuthors {
+                    name: m.name.clone(),
+                    plugin: m.plugin.clone(),
+                    authors: Vec::new(),
+                    ages: Vec::new(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn add_file(
+        &mut self,
+        module: Option<&str>,
+        plugin: Option<&str>,
+        authors: &[AuthorStat],
+        ages: &[AgeStat],
+    ) {
+        merge_authors(&mut self.authors, authors);
+        merge_ages(&mut self.ages, ages);
+        let found = self
+            .modules
+            .iter_mut()
+            .find(|m| m.name.as_deref() == module && m.plugin.as_deref() == plugin);
+        match found {
+            Some(m) => {
+                merge_authors(&mut m.authors, authors);
+                merge_ages(&mut m.ages, ages);
+            }
+            None => self.modules.push(ModuleAuthors {
+                name: module.map(str::to_owned),
+                plugin: plugin.map(str::to_owned),
+                authors: authors.to_vec(),
+                ages: ages.to_vec(),
+            }),
+        }
+    }
+
+    pub fn sort(&mut self) {
+        sort_authors(&mut self.authors);
+        for m in &mut self.modules {
+            sort_authors(&mut m.authors);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+/* ---------------- SVG 徽章 ---------------- */
+// 与 shields.io 的 flat 样式一致，可直接放进 README
+const LABEL: &str = "code lines";
+const LABEL_COLOR: &str = "#555";
+const VALUE_COLOR: &str = "#007ec6";
+
+// 412k、1.2M 这样的短写法，小于 10 时保留一位小数
+pub fn compact(n: usize) -> String {
+    // 按舍入后的值选单位和小数位：999_500 舍入后是 1000k，写作 1.0M；
+    // 9_950 保留一位小数是 10.0k，写作 10k
+    let (scale, unit) = match n {
+        0..=999 => return n.to_string(),
+        1_000..=999_499 => (1_000, "k"),
+        _ => (1_000_000, "M"),
+    };
+    let value = n as f64 / scale as f64;
+    if n < scale / 1_000 * 9_950 {
+        format!("{:.1}{}", value, unit)
+    } else {
+        format!("{:.0}{}", value, unit)
+    }
+}
+
+// 没有字体度量，按 Verdana 11px 的平均字宽估算
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
+
+pub fn svg(code_lines: usize) -> String {
+    let value = compact(code_lines);
+    let (lw, vw) = (text_width(LABEL), text_width(&value));
+    let width = lw + vw;
+    // 文字坐标放大 10 倍再缩小，与 shields.io 的做法相同，避免小数像素
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}">
+<title>{label}: {value}</title>
+<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+<g clip-path="url(#r)"><rect width="{lw}" height="20" fill="{lc}"/><rect x="{lw}" width="{vw}" height="20" fill="{vc}"/><rect width="{width}" height="20" fill="url(#s)"/></g>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="110">
+<text x="{lx}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{label}</text><text x="{lx}" y="140" transform="scale(.1)">{label}</text>
+<text x="{vx}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{value}</text><text x="{vx}" y="140" transform="scale(.1)">{value}</text>
+</g>
+</svg>
+"##,
+        width = width,
+        lw = lw,
+        vw = vw,
+        lc = LABEL_COLOR,
+        vc = VALUE_COLOR,
+        lx = lw * 5,
+        vx = lw * 10 + vw * 5,
+        label = LABEL,
+        value = value,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_shortens_to_k_and_m() {
+        for (n, text) in [
+            (0, "0"),
+            (999, "999"),
+            (1_000, "1.0k"),
+            (9_949, "9.9k"),
+            (12_345, "12k"),
+            (412_000, "412k"),
+            (1_200_000, "1.2M"),
+            (25_000_000, "25M"),
+        ] {
+            assert_eq!(compact(n), text);
+        }
+    }
+
+    #[test]
+    fn compact_picks_unit_after_rounding() {
+        for (n, text) in [
+            (9_950, "10k"),
+            (999_499, "999k"),
+            (999_500, "1.0M"),
+            (999_999, "1.0M"),
+            (9_949_999, "9.9M"),
+            (9_950_000, "10M"),
+        ] {
+            assert_eq!(compact(n), text);
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use clap::Args;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use ue_loc_gui::diff::Diff;
+use ue_loc_gui::git::RefDiff;
+use ue_loc_gui::stat::{LineCounts, StatResult};
+
+use crate::output::module_name;
+
+/* ---------------- 阈值检查 ---------------- */
+// 任意一项不满足时以非零退出码结束，供 CI 拦截合并
+#[derive(Args, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    /// Fail when the whole project has more total lines than this
+    #[arg(long, value_name = "N")]
+    pub max_total_lines: Option<usize>,
+    /// Fail when any single file has more total lines than this
+    #[arg(long, value_name = "N")]
+    pub max_file_lines: Option<usize>,
+    /// Fail when comment / (code + comment) is below this ratio (0-1)
+    #[arg(long, value_name = "RATIO")]
+    pub min_comment_ratio: Option<f64>,
+    /// Fail when code lines grew by more than N since --baseline
+    #[arg(long, value_name = "N")]
+    pub max_code_growth: Option<i64>,
+    /// Fail when the overall or any module's comment ratio is lower than in this exported JSON
+    /// result
+    #[arg(long, value_name = "FILE")]
+    pub fail_on_comment_ratio_drop: Option<PathBuf>,
+}
+
+pub struct Violation {
+    pub message: String,
+    // 针对单个文件的检查给出文件路径，便于在 CI 中标注到文件上
+    pub file: Option<PathBuf>,
+}
+
+// 一项已配置的检查及其结果，violations 为空表示通过
+pub struct CheckResult {
+    pub name: &'static str,
+    pub violations: Vec<Violation>,
+}
+
+impl Thresholds {
+    // 命令行给出的值优先，其余取自配置文件
+    pub fn or(self, other: Thresholds) -> Thresholds {
+        Thresholds {
+            max_total_lines: self.max_total_lines.or(other.max_total_lines),
+            max_file_lines: self.max_file_lines.or(other.max_file_lines),
+            min_comment_ratio: self.min_comment_ratio.or(other.min_comment_ratio),
+            max_code_growth: self.max_code_growth.or(other.max_code_growth),
+            fail_on_comment_ratio_drop: self
+                .fail_on_comment_ratio_drop
+                .or(other.fail_on_comment_ratio_drop),
+        }
+    }
+
+    pub fn check(&self, result: &StatResult) -> Vec<CheckResult> {
+        let mut checks = Vec::new();
+        if let Some(limit) = self.max_total_lines {
+            let total = result.totals.total;
+            let violations = (total > limit)
+                .then(|| Violation {
+                    message: format!("project has {} lines (limit {})", total, limit),
+                    file: None,
+                })
+                .into_iter()
+                .collect();
+            checks.push(CheckResult {
+                name: "max-total-lines",
+                violations,
+            });
+        }
+        if let Some(limit) = self.max_file_lines {
+            let violations = result
+                .files
+                .iter()
+                .filter(|f| f.lines.total > limit)
+                .map(|f| Violation {
+                    message: format!(
+                        "{} has {} lines (limit {})",
+                        f.rel_path, f.lines.total, limit
+                    ),
+                    file: Some(f.path.clone()),
+                })
+                .collect();
+            checks.push(CheckResult {
+                name: "max-file-lines",
+                violations,
+            });
+        }
+        if let Some(min) = self.min_comment_ratio {
+            let ratio = result.totals.comment_ratio();
+            let violations = (ratio < min)
+                .then(|| Violation {
+                    message: format!("comment ratio is {:.3} (minimum {:.3})", ratio, min),
+                    file: None,
+                })
+                .into_iter()
+                .collect();
+            checks.push(CheckResult {
+                name: "min-comment-ratio",
+                violations,
+            });
+        }
+        checks
+    }
+
+    // 与基准对比的检查，只在给出 --baseline 时进行
+    pub fn check_diff(&self, diff: &Diff) -> Vec<CheckResult> {
+        let mut checks = Vec::new();
+        if let Some(limit) = self.max_code_growth {
+            let growth = diff.delta.code;
+            let violations = (growth > limit)
+                .then(|| Violation {
+                    message: format!(
+                        "code grew by {} lines since the baseline (limit {})",
+                        growth, limit
+                    ),
+                    file: None,
+                })
+                .into_iter()
+                .collect();
+            checks.push(CheckResult {
+                name: "max-code-growth",
+                violations,
+            });
+        }
+        checks
+    }
+
+    // 提交钩子只看有变化的文件：单个文件和注释率按变化后的内容，代码增长按整体变化量；
+    // 整个工程的总行数和注释率下降在这里无从判断，不检查
+    pub fn check_changes(&self, root: &Path, diff: &RefDiff) -> Vec<CheckResult> {
+        let mut checks = Vec::new();
+        if let Some(limit) = self.max_file_lines {
+            let violations = diff
+                .files
+                .iter()
+                .filter_map(|f| Some((f, f.after?.total)))
+                .filter(|(_, total)| *total > limit)
+                .map(|(f, total)| Violation {
+                    message: format!("{} has {} lines (limit {})", f.rel_path, total, limit),
+                    file: Some(root.join(&f.rel_path)),
+                })
+                .collect();
+            checks.push(CheckResult {
+                name: "max-file-lines",
+                violations,
+            });
+        }
+        if let Some(min) = self.min_comment_ratio {
+            let mut lines = LineCounts::default();
+            for after in diff.files.iter().filter_map(|f| f.after) {
+                lines += after;
+            }
+            let ratio = lines.comment_ratio();
+            // 只删除文件时没有可比较的内容
+            let violations = (lines.code + lines.comment > 0 && ratio < min)
+                .then(|| Violation {
+                    message: format!(
+                        "comment ratio of the changed files is {:.3} (minimum {:.3})",
+                        ratio, min
+                    ),
+                    file: None,
+                })
+                .into_iter()
+                .collect();
+            checks.push(CheckResult {
+                name: "min-comment-ratio",
+                violations,
+            });
+        }
+        if let Some(limit) = self.max_code_growth {
+            let growth = diff.delta.code;
+            let violations = (growth > limit)
+                .then(|| Violation {
+                    message: format!(
+                        "code grows by {} lines from {} to {} (limit {})",
+                        growth, diff.from, diff.to, limit
+                    ),
+                    file: None,
+                })
+                .into_iter()
+                .collect();
+            checks.push(CheckResult {
+                name: "max-code-growth",
+                violations,
+            });
+        }
+        checks
+    }
+}
+
+// 整体和两边都存在的模块，注释率都不能比基准低；新增或删除的模块不比较
+pub fn comment_ratio_drop(diff: &Diff) -> CheckResult {
+    let fell = |before: &LineCounts, after: &LineCounts| {
+        let (before, after) = (before.comment_ratio(), after.comment_ratio());
+        (after < before).then(|| format!("comment ratio fell from {:.3} to {:.3}", before, after))
+    };
+    let mut violations: Vec<Violation> = fell(&diff.before.lines, &diff.after.lines)
+        .map(|message| Violation {
+            message,
+            file: None,
+        })
+        .into_iter()
+        .collect();
+    for m in &diff.modules {
+        if m.before.total == 0 || m.after.total == 0 {
+            continue;
+        }
+        if let Some(message) = fell(&m.before, &m.after) {
+            let name = module_name(m.name.as_deref(), m.plugin.as_deref());
+            violations.push(Violation {
+                message: format!("{}: {}", name, message),
+                file: None,
+            });
+        }
+    }
+    CheckResult {
+        name: "comment-ratio-drop",
+        violations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ue_loc_gui::diff::Totals;
+    use ue_loc_gui::stat::{FileStat, ModuleStat};
+
+    fn lines(code: usize, comment: usize) -> LineCounts {
+        LineCounts {
+            total: code + comment,
+            code,
+            comment,
+            ..Default::default()
+        }
+    }
+
+    fn file(rel_path: &str, code: usize, comment: usize) -> FileStat {
+        let lines = lines(code, comment);
+        FileStat {
+            path: PathBuf::from(rel_path),
+            rel_path: rel_path.to_owned(),
+            module: None,
+            plugin: None,
+            lines,
+            details: Default::default(),
+            churn: None,
+            last_commit: None,
+        }
+    }
+
+    fn module(name: &str, code: usize, comment: usize) -> ModuleStat {
+        ModuleStat {
+            name: Some(name.to_owned()),
+            plugin: None,
+            files: 1,
+            lines: lines(code, comment),
+            churn: None,
+        }
+    }
+
+    fn result(files: Vec<FileStat>) -> StatResult {
+        let mut totals = LineCounts::default();
+        for f in &files {
+            totals += f.lines;
+        }
+        StatResult {
+            files,
+            totals,
+            ..Default::default()
+        }
+    }
+
+    fn counts(checks: &[CheckResult]) -> Vec<(&str, usize)> {
+        checks
+            .iter()
+            .map(|c| (c.name, c.violations.len()))
+            .collect()
+    }
+
+    #[test]
+    fn only_configured_checks_run() {
+        let result = result(vec![file("A.cpp", 10, 0)]);
+        assert!(Thresholds::default().check(&result).is_empty());
+    }
+
+    #[test]
+    fn check_reports_each_threshold() {
+        let result = result(vec![file("A.cpp", 80, 10), file("B.cpp", 5, 5)]);
+        let thresholds = Thresholds {
+            max_total_lines: Some(99),
+            max_file_lines: Some(50),
+            min_comment_ratio: Some(0.2),
+            ..Default::default()
+        };
+        let checks = thresholds.check(&result);
+        assert_eq!(
+            counts(&checks),
+            [
+                ("max-total-lines", 1),
+                ("max-file-lines", 1),
+                ("min-comment-ratio", 1)
+            ]
+        );
+        assert_eq!(checks[1].violations[0].file, Some(PathBuf::from("A.cpp")));
+
+        // 恰好等于上限和下限时通过
+        let thresholds = Thresholds {
+            max_total_lines: Some(100),
+            max_file_lines: Some(90),
+            min_comment_ratio: Some(0.15),
+            ..Default::default()
+        };
+        assert_eq!(
+            counts(&thresholds.check(&result)),
+            [
+                ("max-total-lines", 0),
+                ("max-file-lines", 0),
+                ("min-comment-ratio", 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn check_diff_limits_code_growth() {
+        let before = result(vec![file("A.cpp", 10, 0)]);
+        let after = result(vec![file("A.cpp", 25, 0)]);
+        let diff = Diff::between(&before, &after);
+        let checks = |limit| {
+            Thresholds {
+                max_code_growth: Some(limit),
+                ..Default::default()
+            }
+            .check_diff(&diff)
+        };
+        assert_eq!(counts(&checks(14)), [("max-code-growth", 1)]);
+        assert_eq!(counts(&checks(15)), [("max-code-growth", 0)]);
+    }
+
+    #[test]
+    fn comment_ratio_drop_compares_overall_and_shared_modules() {
+        let totals = |code, comment| Totals {
+            file_count: 1,
+            lines: lines(code, comment),
+        };
+        // 整体和 Core 的注释率都下降；New 只在后一次出现，不比较
+        let diff = Diff::new(
+            totals(80, 20),
+            &[module("Core", 80, 20), module("Old", 10, 10)],
+            totals(90, 10),
+            &[module("Core", 90, 10), module("New", 10, 0)],
+        );
+        let check = comment_ratio_drop(&diff);
+        let messages: Vec<&str> = check
+            .violations
+            .iter()
+            .map(|v| v.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "comment ratio fell from 0.200 to 0.100",
+                "Core: comment ratio fell from 0.200 to 0.100"
+            ]
+        );
+
+        let diff = Diff::new(
+            totals(80, 20),
+            &[module("Core", 80, 20)],
+            totals(80, 30),
+            &[module("Core", 80, 30)],
+        );
+        assert!(comment_ratio_drop(&diff).violations.is_empty());
+    }
+}
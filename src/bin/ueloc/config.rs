@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ue_loc_gui::stat::ScanOptions;
+
+use crate::checks::Thresholds;
+
+/* ---------------- 配置文件 ---------------- */
+// 由 --config 指定，放在仓库里让 CI 的结果可以复现；命令行参数优先
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // 命令行没有给出目录时统计这些目录
+    pub paths: Vec<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub scan: ScanOptions,
+    pub thresholds: Thresholds,
+}
+
+impl Config {
+    // 相对路径以配置文件所在目录为基准，与从哪里运行无关
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut config: Config = toml::from_str(&text).map_err(|e| e.to_string())?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        for p in &mut config.paths {
+            *p = base.join(&*p);
+        }
+        if let Some(baseline) = &mut config.baseline {
+            *baseline = base.join(&*baseline);
+        }
+        if let Some(ratio) = &mut config.thresholds.fail_on_comment_ratio_drop {
+            *ratio = base.join(&*ratio);
+        }
+        Ok(config)
+    }
+}
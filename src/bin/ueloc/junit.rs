@@ -0,0 +1,67 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use ue_loc_gui::cloc::xml_escape;
+
+use crate::checks::CheckResult;
+
+/* ---------------- JUnit 报告 ---------------- */
+// 每个工程一个 testsuite，每项已配置的检查一个 testcase，不满足时附上全部问题
+pub fn report(suites: &[(&Path, Vec<CheckResult>)]) -> String {
+    let count = |checks: &[CheckResult]| {
+        let failures = checks.iter().filter(|c| !c.violations.is_empty()).count();
+        (checks.len(), failures)
+    };
+    let (tests, failures) = suites.iter().fold((0, 0), |(t, f), (_, checks)| {
+        let (tests, failures) = count(checks);
+        (t + tests, f + failures)
+    });
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"ueloc\" tests=\"{}\" failures=\"{}\">",
+        tests, failures
+    );
+    for (root, checks) in suites {
+        let (tests, failures) = count(checks);
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            xml_escape(&root.display().to_string()),
+            tests,
+            failures
+        );
+        for check in checks {
+            let _ = write!(
+                out,
+                "    <testcase classname=\"ueloc\" name=\"{}\"",
+                check.name
+            );
+            if check.violations.is_empty() {
+                let _ = writeln!(out, " />");
+                continue;
+            }
+            let _ = writeln!(out, ">");
+            let messages: Vec<&str> = check
+                .violations
+                .iter()
+                .map(|v| v.message.as_str())
+                .collect();
+            let summary = match messages.as_slice() {
+                [only] => only.to_string(),
+                _ => format!("{} violations", messages.len()),
+            };
+            let _ = writeln!(
+                out,
+                "      <failure message=\"{}\">{}</failure>",
+                xml_escape(&summary),
+                xml_escape(&messages.join("\n"))
+            );
+            let _ = writeln!(out, "    </testcase>");
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
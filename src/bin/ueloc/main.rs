@@ -0,0 +1,1137 @@
+mod checks;
+mod config;
+mod junit;
+mod output;
+mod progress;
+mod watch;
+
+use chrono::{Local, SecondsFormat};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use ue_loc_gui::authors::{
+    ages_by_year, merge_ages, merge_authors, sort_authors, AuthorReport, ModuleAuthors,
+};
+use ue_loc_gui::diff::{changes_markdown, Diff};
+use ue_loc_gui::git::{self, RefDiff, Sampling};
+use ue_loc_gui::json::{self, Baseline, JsonOptions};
+use ue_loc_gui::stat::{
+    apply_churn, build_glob, stat_file_list, stat_ue_code_with_progress, LineCounts, ScanOptions,
+    ScanProgress, StatResult,
+};
+use ue_loc_gui::{badge, cloc, csv, template};
+
+use checks::{CheckResult, Thresholds};
+use config::Config;
+use output::Style;
+
+// 阈值不满足时的退出码；参数或读写错误用 EXIT_ERROR
+const EXIT_VIOLATION: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+/* ---------------- 命令行参数 ---------------- */
+/// Count lines of code in an Unreal Engine project without starting the GUI.
+#[derive(Parser)]
+#[command(
+    name = "ueloc",
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Project root directories to scan
+    #[arg(required_unless_present_any = ["files_from", "diff", "config", "projects_list"])]
+    paths: Vec<PathBuf>,
+    /// Also scan every project root listed in FILE (one per line, "-" for stdin, # starts a
+    /// comment); relative entries are resolved against the file's directory
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["files_from", "diff"])]
+    projects_list: Option<PathBuf>,
+    /// Read roots, scan options and thresholds from a TOML file instead of the defaults
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Scan two directories and print per-metric and per-module deltas
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        conflicts_with_all = ["paths", "files_from", "csv", "watch"]
+    )]
+    diff: Option<Vec<PathBuf>>,
+    /// Compare against a previously exported JSON result and report only the deltas
+    #[arg(long, value_name = "FILE", conflicts_with = "csv")]
+    baseline: Option<PathBuf>,
+    /// Count the files listed in FILE (one per line, "-" for stdin) instead of walking the root;
+    /// relative entries are resolved against the root, which defaults to the current directory
+    #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+    files_from: Option<PathBuf>,
+    /// Count the files of a commit, branch or tag from the git object database instead of the
+    /// working tree; each root must be inside a git repository
+    #[arg(
+        long,
+        value_name = "REF",
+        conflicts_with_all = ["files_from", "diff", "watch"]
+    )]
+    git_ref: Option<String>,
+    /// Compare two commits, branches or tags of the git repository containing the root and print
+    /// per-module line deltas; only the files that differ between the two trees are read
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        conflicts_with_all = ["files_from", "diff", "git_ref", "csv", "template", "tree_json", "baseline", "authors", "trend", "watch"]
+    )]
+    diff_refs: Option<Vec<String>>,
+    /// Print the code lines added and removed per module since REF (--since=REF), or since the
+    /// most recent tag when REF is omitted, up to --git-ref (default HEAD), as a Markdown table
+    /// for release notes
+    #[arg(
+        long,
+        value_name = "REF",
+        num_args = 0..=1,
+        require_equals = true,
+        conflicts_with_all = ["files_from", "diff", "diff_refs", "csv", "template", "tree_json", "baseline", "authors", "trend", "watch"]
+    )]
+    since: Option<Option<String>>,
+    /// Print the per-module line deltas of the working tree, untracked files included, against
+    /// HEAD: what has been added locally before pushing
+    #[arg(
+        long,
+        conflicts_with_all = ["files_from", "diff", "diff_refs", "since", "git_ref", "csv", "template", "tree_json", "baseline", "authors", "trend", "watch"]
+    )]
+    uncommitted: bool,
+    /// Layout of the default (non-JSON, non-CSV) output
+    // github 的注释和 junit 的报告都写到 stdout，不能与其他机器可读输出混在一起
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Format::Table,
+        conflicts_with_all = ["json", "csv", "template", "tree_json"]
+    )]
+    format: Format,
+    /// With --format cloc, print cloc's XML instead of its text report
+    #[arg(long)]
+    xml: bool,
+    /// Color the terminal tables; auto colors only when stdout is a terminal and NO_COLOR is unset
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Also print the per-module / per-plugin table, sorted by code lines
+    #[arg(long)]
+    by_module: bool,
+    /// Also list the N files with the most code lines (in the table and in JSON)
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+    /// Print the full result as JSON instead of a summary table
+    #[arg(long, conflicts_with = "csv")]
+    json: bool,
+    /// Include per-file entries in the JSON output
+    #[arg(long, requires = "json")]
+    files: bool,
+    /// Include the per-directory tree in the JSON output
+    #[arg(long, requires = "json")]
+    tree: bool,
+    /// Render each project with a Handlebars template: the built-in "markdown" or "html", or a
+    /// template file; names ending in .html or .html.hbs are HTML-escaped
+    #[arg(
+        long,
+        value_name = "NAME|FILE",
+        conflicts_with_all = ["json", "csv", "diff", "baseline", "format"]
+    )]
+    template: Option<String>,
+    /// Print the directory tree as nested JSON with files as leaves, ready for d3.hierarchy
+    #[arg(long, conflicts_with_all = ["json", "csv", "template", "diff", "baseline"])]
+    tree_json: bool,
+    /// Attribute every counted line to its last author with git blame and print code lines
+    /// per author (per module as well with --by-module); with --csv print the authors, or the
+    /// module owners with --by-module, as CSV
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "template", "tree_json", "diff", "baseline", "git_ref"]
+    )]
+    authors: bool,
+    /// Blame every counted line like --authors and print the code lines last changed in each
+    /// year (default) or quarter, per module as well with --by-module; with --csv print the
+    /// periods of all counted code as CSV
+    #[arg(
+        long,
+        value_enum,
+        value_name = "BUCKET",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "year",
+        conflicts_with_all = ["json", "template", "tree_json", "diff", "baseline", "git_ref", "authors"]
+    )]
+    code_age: Option<AgeBucket>,
+    /// Add up the lines added and deleted per file in the git history of the last DAYS days
+    /// (merge commits excluded) and list the most churned files; JSON carries a churn field
+    #[arg(long, value_name = "DAYS", conflicts_with_all = ["trend", "authors"])]
+    churn: Option<u32>,
+    /// Walk the git history back from --git-ref (default HEAD) and print a CSV time series of
+    /// the totals, sampling every N commits ("10") or at most one commit per N days ("30d")
+    #[arg(
+        long,
+        value_name = "N|Nd",
+        value_parser = parse_sampling,
+        conflicts_with_all = ["json", "csv", "template", "tree_json", "diff", "baseline", "authors", "watch"]
+    )]
+    trend: Option<Sampling>,
+    /// Stop after this many points when walking the history with --trend
+    #[arg(long, value_name = "N", default_value_t = 100, requires = "trend")]
+    trend_max: usize,
+    /// Print CSV at the given granularity
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "summary")]
+    csv: Option<CsvKind>,
+    /// Write the output to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Skip files whose path relative to the root matches GLOB (repeatable)
+    #[arg(long, value_name = "GLOB", value_parser = parse_glob)]
+    exclude: Vec<String>,
+    /// Count only files matching GLOB instead of the default extensions (repeatable)
+    #[arg(long, value_name = "GLOB", value_parser = parse_glob)]
+    include: Vec<String>,
+    /// Count only files tracked in the git index, leaving out untracked and ignored files
+    #[arg(long)]
+    tracked_only: bool,
+    /// Count only files mapped in the current Perforce client (listed by p4 fstat, with the
+    /// connection taken from P4PORT, P4CLIENT or P4CONFIG as usual)
+    #[arg(long)]
+    p4: bool,
+    /// With --p4, count only the files opened in pending changelist CL (a number or "default")
+    #[arg(long, value_name = "CL", requires = "p4")]
+    p4_changelist: Option<String>,
+    /// Count the git submodule at PATH relative to the root (repeatable); submodules listed in
+    /// .gitmodules are skipped by default and reported separately with --by-module
+    #[arg(long, value_name = "PATH")]
+    submodule: Vec<String>,
+    /// Number of threads reading files (0 picks one per CPU core)
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+    /// Hide the progress bar, warnings and log output on stderr; reports are still written to
+    /// stdout and threshold violations are still reported
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+    /// List skipped files and warnings (-v), per-file progress (-vv) or everything (-vvv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Keep running and rescan whenever source files change
+    #[arg(long)]
+    watch: bool,
+    /// Write a shields.io-style SVG badge with the code line count to FILE after every scan
+    #[arg(long, value_name = "FILE")]
+    badge: Option<PathBuf>,
+    /// Append a timestamped summary line to FILE after every scan
+    #[arg(long, value_name = "FILE")]
+    append: Option<PathBuf>,
+    /// Append a CSV row (timestamp, git revision, files and line totals) to FILE after every
+    /// scan, writing the header first when FILE is new
+    #[arg(long, value_name = "FILE")]
+    append_csv: Option<PathBuf>,
+    /// Write the skipped, unreadable and over-limit files with their reasons to FILE as CSV
+    #[arg(long, value_name = "FILE")]
+    skipped_csv: Option<PathBuf>,
+    /// Write the full per-file dataset to FILE as MessagePack, with the same fields as --json
+    /// --files
+    #[arg(long, value_name = "FILE")]
+    msgpack: Option<PathBuf>,
+    #[command(flatten)]
+    thresholds: Thresholds,
+}
+
+// 在解析参数时就报告写错的 glob
+fn parse_glob(pattern: &str) -> Result<String, String> {
+    build_glob(pattern)
+        .map(|_| pattern.to_owned())
+        .map_err(|e| e.to_string())
+}
+
+fn parse_sampling(spec: &str) -> Result<Sampling, String> {
+    let invalid = || {
+        format!(
+            "expected a number of commits or days such as 10 or 30d, got {}",
+            spec
+        )
+    };
+    match spec.strip_suffix('d') {
+        Some(days) => days.parse().map(Sampling::Days).map_err(|_| invalid()),
+        None => spec.parse().map(Sampling::Commits).map_err(|_| invalid()),
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// For git hooks: count only the files staged for commit and print a short delta summary
+    /// for the commit message; thresholds apply to the changed files only
+    Hook {
+        /// Project root inside the git repository
+        #[arg(default_value = ".")]
+        root: PathBuf,
+        /// Compare REF with HEAD instead of HEAD with the index, e.g. in CI or a pre-push hook
+        #[arg(long, value_name = "REF")]
+        against: Option<String>,
+        /// Read scan options and thresholds from a TOML file
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+        /// Print the per-module and per-file changes as JSON instead of the summary
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        thresholds: Thresholds,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+impl Cli {
+    // -q 时不显示；-v 及以上会逐行打日志，进度条会和日志混在一起
+    fn progress(&self) -> bool {
+        !self.quiet && self.verbose == 0
+    }
+
+    // 写入文件或管道时不带转义序列
+    fn style(&self) -> Style {
+        Style::new(match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                self.output.is_none()
+                    && io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::env::var_os("TERM").is_none_or(|t| t != "dumb")
+            }
+        })
+    }
+
+    fn json_options(&self) -> JsonOptions {
+        JsonOptions {
+            files: self.files,
+            top: self.top,
+            tree: self.tree,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Table,
+    // 与 cloc 相同的版式，便于沿用现有的 cloc 解析脚本
+    Cloc,
+    // 摘要表格之外，把阈值问题和扫描警告输出为 GitHub Actions 注释
+    Github,
+    // 每项阈值检查作为一个测试用例，供只认 JUnit 结果的 CI 展示
+    Junit,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AgeBucket {
+    Year,
+    Quarter,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CsvKind {
+    Summary,
+    Files,
+    Modules,
+}
+
+/* ---------------- 输出 ---------------- */
+// 单个工程输出摘要，多个工程逐个列出并给出合计
+fn render(cli: &Cli, results: &[(&Path, &StatResult)]) -> serde_json::Result<String> {
+    // cloc 报告本来就把全部输入合在一起
+    if cli.format == Format::Cloc && cli.csv.is_none() && !cli.json {
+        let results: Vec<&StatResult> = results.iter().map(|(_, r)| *r).collect();
+        return Ok(if cli.xml {
+            cloc::xml(&results)
+        } else {
+            cloc::text(&results)
+        });
+    }
+    if let [(root, result)] = results {
+        return Ok(match cli.csv {
+            Some(CsvKind::Summary) => csv::summary_csv(result),
+            Some(CsvKind::Files) => csv::files_csv(result),
+            Some(CsvKind::Modules) => csv::modules_csv(result),
+            None if cli.json => json::result_json(root, result, cli.json_options())? + "\n",
+            None => table(cli, root, result, false),
+        });
+    }
+    Ok(match cli.csv {
+        Some(CsvKind::Summary) => csv::roots_csv(results),
+        Some(CsvKind::Files) => csv::files_csv_multi(results),
+        Some(CsvKind::Modules) => csv::modules_csv_multi(results),
+        None if cli.json => json::results_json(results, cli.json_options())? + "\n",
+        None => {
+            let mut out = output::roots_table(results, cli.style());
+            for (root, result) in results {
+                out += &table(cli, root, result, true);
+            }
+            out
+        }
+    })
+}
+
+// 多个工程时 projects 数组中每个元素是一个工程的目录树
+fn tree_json(results: &[(&Path, &StatResult)]) -> serde_json::Result<String> {
+    let text = match results {
+        [(root, result)] => json::tree_json(root, result)?,
+        _ => {
+            let trees: Vec<_> = results
+                .iter()
+                .map(|(root, result)| json::TreeNode::build(root, &result.files))
+                .collect();
+            json::to_json(&serde_json::json!({ "projects": trees }))?
+        }
+    };
+    Ok(text + "\n")
+}
+
+// 默认的终端表格；多个工程时摘要已合并列出，只补充模块表和大文件列表
+fn table(cli: &Cli, root: &Path, result: &StatResult, multi: bool) -> String {
+    let style = cli.style();
+    let mut out = if multi {
+        String::new()
+    } else {
+        output::summary_table(result, style)
+    };
+    if cli.by_module || cli.top.is_some() || cli.churn.is_some() {
+        out += &format!("\n{}\n", root.display());
+    }
+    if cli.by_module {
+        out += &output::modules_table(result, style);
+        if !result.submodules.is_empty() {
+            out += "\n";
+            out += &output::submodules_table(result, style);
+        }
+    }
+    if let Some(n) = cli.top {
+        if cli.by_module {
+            out += "\n";
+        }
+        out += &output::top_files_table(result, n, style);
+    }
+    if cli.churn.is_some() {
+        if cli.by_module || cli.top.is_some() {
+            out += "\n";
+        }
+        out += &output::churn_table(result, cli.top.unwrap_or(20), style);
+    }
+    out
+}
+
+// 每个工程先做 blame 再汇总，比普通统计慢得多
+fn authors(cli: &Cli, opts: &ScanOptions, results: &[(&Path, &StatResult)]) -> io::Result<String> {
+    let mut reports = Vec::with_capacity(results.len());
+    for (root, result) in results {
+        let label = format!("{} (blame)", root.display());
+        let report = progress::run(&label, cli.progress(), |p| {
+            git::blame_authors(root, &result.files, &result.modules, opts.worker_count(), p)
+        })
+        .map_err(|e| io::Error::other(format!("{}: {}", root.display(), e.message())))?;
+        reports.push(report);
+    }
+    if cli.csv.is_some() {
+        return Ok(authors_csv(cli, reports));
+    }
+    let mut out = String::new();
+    for (i, ((root, _), report)) in results.iter().zip(&reports).enumerate() {
+        if i > 0 || results.len() > 1 {
+            out += &format!("{}{}\n", if i > 0 { "\n" } else { "" }, root.display());
+        }
+        out += &match cli.code_age {
+            Some(bucket) => output::ages_table(
+                report,
+                bucket == AgeBucket::Year,
+                cli.by_module,
+                cli.style(),
+            ),
+            None => output::authors_table(report, cli.by_module, cli.style()),
+        };
+    }
+    Ok(out)
+}
+
+// 多个工程合成一张表，作者和时间段跨工程合并，模块依次列出
+fn authors_csv(cli: &Cli, reports: Vec<AuthorReport>) -> String {
+    if let Some(bucket) = cli.code_age {
+        let mut ages = Vec::new();
+        for report in &reports {
+            merge_ages(&mut ages, &report.ages);
+        }
+        if bucket == AgeBucket::Year {
+            ages = ages_by_year(&ages);
+        }
+        return csv::ages_csv(&ages);
+    }
+    if cli.by_module {
+        let modules: Vec<ModuleAuthors> = reports.into_iter().flat_map(|r| r.modules).collect();
+        return csv::ownership_csv(&modules);
+    }
+    let mut authors = Vec::new();
+    for report in &reports {
+        merge_authors(&mut authors, &report.authors);
+    }
+    sort_authors(&mut authors);
+    csv::authors_csv(&authors)
+}
+
+// 下游提前关闭管道（如 | head）时安静退出
+fn write_stdout(text: &str) -> io::Result<()> {
+    match io::stdout().lock().write_all(text.as_bytes()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    }
+}
+
+fn summary_line(results: &[(&Path, &StatResult)]) -> String {
+    let mut files = 0;
+    let mut totals = LineCounts::default();
+    for (_, result) in results {
+        files += result.files.len();
+        totals += result.totals;
+    }
+    format!(
+        "{} files={} total={} code={} comment={} blank={}\n",
+        Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+        files,
+        totals.total,
+        totals.code,
+        totals.comment,
+        totals.blank
+    )
+}
+
+// 多个工程时各项为合计，版本号只在单个工程时记录
+fn series_row(results: &[(&Path, &StatResult)]) -> String {
+    let mut files = 0;
+    let mut totals = LineCounts::default();
+    for (_, result) in results {
+        files += result.files.len();
+        totals += result.totals;
+    }
+    let revision = match results {
+        [(root, _)] => git::head_revision(root),
+        _ => None,
+    };
+    csv::series_row(Local::now(), revision.as_deref(), files, &totals)
+}
+
+fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+// 空行忽略，行尾的空白（含 Windows 换行）去掉
+fn read_file_list(source: &Path) -> io::Result<Vec<PathBuf>> {
+    let text = if source == Path::new("-") {
+        io::read_to_string(io::stdin())?
+    } else {
+        fs::read_to_string(source)?
+    };
+    Ok(text
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+// 工程列表允许空行和 # 注释
+fn read_projects_list(source: &Path) -> io::Result<Vec<PathBuf>> {
+    let base = match source.parent() {
+        Some(dir) if source != Path::new("-") => dir,
+        _ => Path::new(""),
+    };
+    Ok(read_file_list(source)?
+        .into_iter()
+        .filter(|p| !p.to_string_lossy().trim_start().starts_with('#'))
+        .map(|p| base.join(p))
+        .collect())
+}
+
+// 启动时读取一次的输入，监视模式下每次重新统计都沿用
+struct Inputs {
+    list: Option<Vec<PathBuf>>,
+    baseline: Option<Baseline>,
+    // --fail-on-comment-ratio-drop 的基准，只用于检查，不改变输出
+    ratio_baseline: Option<Baseline>,
+    template: Option<Template>,
+}
+
+struct Template {
+    text: String,
+    html: bool,
+}
+
+impl Template {
+    // 内置模板的名称优先，其余按文件路径读取
+    fn load(name: &str) -> io::Result<Self> {
+        let text = match template::builtin(name) {
+            Some(text) => text.to_owned(),
+            None => fs::read_to_string(name)?,
+        };
+        Ok(Self {
+            text,
+            html: template::is_html(name),
+        })
+    }
+
+    // 多个工程时逐个渲染，依次输出
+    fn render(&self, results: &[(&Path, &StatResult)]) -> io::Result<String> {
+        results
+            .iter()
+            .map(|(root, result)| template::render(&self.text, self.html, root, result))
+            .collect::<Result<_, _>>()
+            .map_err(io::Error::other)
+    }
+}
+
+fn read_baseline(path: &Path) -> Result<Baseline, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    json::load_baseline(&text).map_err(|e| e.to_string())
+}
+
+impl Inputs {
+    fn load(cli: &Cli) -> Result<Self, String> {
+        let list = cli
+            .files_from
+            .as_deref()
+            .map(read_file_list)
+            .transpose()
+            .map_err(|e| format!("cannot read file list: {}", e))?;
+        let baseline = cli
+            .baseline
+            .as_deref()
+            .map(read_baseline)
+            .transpose()
+            .map_err(|e| format!("cannot read baseline: {}", e))?;
+        let ratio_baseline = cli
+            .thresholds
+            .fail_on_comment_ratio_drop
+            .as_deref()
+            .map(read_baseline)
+            .transpose()
+            .map_err(|e| format!("cannot read comment ratio baseline: {}", e))?;
+        let template = cli
+            .template
+            .as_deref()
+            .map(Template::load)
+            .transpose()
+            .map_err(|e| format!("cannot read template: {}", e))?;
+        Ok(Self {
+            list,
+            baseline,
+            ratio_baseline,
+            template,
+        })
+    }
+}
+
+// 统计全部工程并输出，返回是否有阈值不满足
+fn scan_and_report(cli: &Cli, opts: &ScanOptions, inputs: &Inputs) -> io::Result<bool> {
+    let mut scans: Vec<(PathBuf, StatResult)> = match &inputs.list {
+        Some(files) => {
+            let root = cli.paths.first().cloned().unwrap_or_else(|| ".".into());
+            let result = progress::run(&root.display().to_string(), cli.progress(), |p| {
+                stat_file_list(&root, files, opts, p)
+            });
+            vec![(root, result)]
+        }
+        None => cli
+            .paths
+            .iter()
+            .map(|root| Ok((root.clone(), scan_root(cli, opts, root)?)))
+            .collect::<io::Result<_>>()?,
+    };
+    if let Some(days) = cli.churn {
+        for (root, result) in &mut scans {
+            let churn = git::file_churn(root, days)
+                .map_err(|e| io::Error::other(format!("{}: {}", root.display(), e.message())))?;
+            apply_churn(result, &churn);
+        }
+    }
+    let results: Vec<(&Path, &StatResult)> = scans
+        .iter()
+        .map(|(root, result)| (root.as_path(), result))
+        .collect();
+
+    // 有基准时只输出变化量
+    let diff = inputs
+        .baseline
+        .as_ref()
+        .map(|baseline| Diff::from_baseline(baseline, results[0].1));
+    // 各工程的检查结果，与基准对比的检查归入唯一的工程
+    let mut suites: Vec<(&Path, Vec<CheckResult>)> = results
+        .iter()
+        .map(|(root, result)| (*root, cli.thresholds.check(result)))
+        .collect();
+    if let Some(diff) = &diff {
+        suites[0].1.extend(cli.thresholds.check_diff(diff));
+    }
+    if let Some(baseline) = &inputs.ratio_baseline {
+        let diff = Diff::from_baseline(baseline, results[0].1);
+        suites[0].1.push(checks::comment_ratio_drop(&diff));
+    }
+
+    let text = match &diff {
+        // 与 --json、--csv 和 --template 的组合已由 clap 拒绝
+        _ if cli.format == Format::Junit => junit::report(&suites),
+        Some(diff) if cli.json => json::to_json(diff)? + "\n",
+        Some(diff) => output::diff_table(diff, cli.style()),
+        None => match &inputs.template {
+            Some(template) => template.render(&results)?,
+            None if cli.tree_json => tree_json(&results)?,
+            None if cli.authors || cli.code_age.is_some() => authors(cli, opts, &results)?,
+            None => render(cli, &results)?,
+        },
+    };
+    match &cli.output {
+        Some(path) => fs::write(path, text)?,
+        None => {
+            // 监视模式下在终端里原地刷新
+            if cli.watch && io::stdout().is_terminal() {
+                write_stdout("\x1b[2J\x1b[H")?;
+            }
+            write_stdout(&text)?;
+        }
+    }
+    if let Some(path) = &cli.append {
+        append_line(path, &summary_line(&results))?;
+    }
+    if let Some(path) = &cli.append_csv {
+        csv::append_series(path, &series_row(&results))?;
+    }
+    if let Some(path) = &cli.skipped_csv {
+        let csv = match results.as_slice() {
+            [(root, result)] => csv::warnings_csv(root, &result.warnings),
+            _ => csv::warnings_csv_multi(&results),
+        };
+        fs::write(path, csv)?;
+    }
+    if let Some(path) = &cli.msgpack {
+        let bytes = match results.as_slice() {
+            [(root, result)] => json::result_msgpack(root, result),
+            _ => json::results_msgpack(&results),
+        };
+        fs::write(path, bytes.map_err(io::Error::other)?)?;
+    }
+    if let Some(path) = &cli.badge {
+        let code = results.iter().map(|(_, r)| r.totals.code).sum();
+        fs::write(path, badge::svg(code))?;
+    }
+
+    let github = cli.format == Format::Github;
+    let mut failed = false;
+    for ((root, result), (_, checks)) in results.iter().zip(&suites) {
+        // 多个工程时在提示前标出所属工程
+        let prefix = if results.len() > 1 {
+            format!("{}: ", root.display())
+        } else {
+            String::new()
+        };
+        // 警告不影响退出码，默认只提示数量，-v 时逐条列出
+        if github {
+            for warning in &result.warnings {
+                write_stdout(&output::github_warning(warning))?;
+            }
+        } else if !result.warnings.is_empty() && !cli.quiet && cli.verbose == 0 {
+            eprintln!(
+                "ueloc: {}{} files skipped or read with warnings",
+                prefix,
+                result.warnings.len()
+            );
+        }
+        for check in checks {
+            for violation in &check.violations {
+                if github {
+                    write_stdout(&output::github_error(check.name, &prefix, violation))?;
+                } else {
+                    eprintln!("ueloc: {}: {}{}", check.name, prefix, violation.message);
+                }
+                failed = true;
+            }
+        }
+    }
+    Ok(failed)
+}
+
+// 日志写到 stderr，不影响 stdout 上的机器可读输出
+fn init_logging(cli: &Cli) {
+    let level = match (cli.quiet, cli.verbose) {
+        (true, _) | (false, 0) => log::LevelFilter::Error,
+        (false, 1) => log::LevelFilter::Info,
+        (false, 2) => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    // 依赖库的调试日志对用户没有意义，最多显示到警告
+    env_logger::Builder::new()
+        .filter_level(level.min(log::LevelFilter::Warn))
+        .filter_module("ue_loc_gui", level)
+        .format(|buf, record| {
+            let level = record.level().as_str().to_lowercase();
+            writeln!(buf, "ueloc: {}: {}", level, record.args())
+        })
+        .init();
+}
+
+fn scan_root(cli: &Cli, opts: &ScanOptions, root: &Path) -> io::Result<StatResult> {
+    let Some(reference) = &cli.git_ref else {
+        return Ok(progress::run(
+            &root.display().to_string(),
+            cli.progress(),
+            |p| stat_ue_code_with_progress(root, opts, p),
+        ));
+    };
+    let label = format!("{}@{}", root.display(), reference);
+    progress::run(&label, cli.progress(), |p| {
+        git::stat_git_ref(root, reference, opts, p)
+    })
+    .map_err(|e| io::Error::other(format!("{}: {}", label, e.message())))
+}
+
+// 每个取样点都从对象库完整统计一次，不做阈值检查
+fn trend(cli: &Cli, opts: &ScanOptions, sampling: Sampling) -> io::Result<()> {
+    let [root] = cli.paths.as_slice() else {
+        return Err(io::Error::other("--trend takes exactly one root directory"));
+    };
+    let reference = cli.git_ref.as_deref().unwrap_or("HEAD");
+    let git_error =
+        |e: git2::Error| io::Error::other(format!("{}: {}", root.display(), e.message()));
+    let samples =
+        git::sample_commits(root, reference, sampling, cli.trend_max).map_err(git_error)?;
+    let mut points = Vec::with_capacity(samples.len());
+    for (i, sample) in samples.iter().enumerate() {
+        let label = format!(
+            "{}@{} ({}/{})",
+            root.display(),
+            &sample.0.to_string()[..10],
+            i + 1,
+            samples.len()
+        );
+        let point = progress::run(&label, cli.progress(), |p| {
+            git::trend_point(root, *sample, opts, p)
+        });
+        points.push(point.map_err(git_error)?);
+    }
+    let text = csv::trend_csv(&points);
+    match &cli.output {
+        Some(path) => fs::write(path, text),
+        None => write_stdout(&text),
+    }
+}
+
+// --diff-refs、--since 和 --uncommitted 都只对比一个根目录所在的仓库
+fn since(cli: &Cli, opts: &ScanOptions) -> io::Result<()> {
+    let [root] = cli.paths.as_slice() else {
+        return Err(io::Error::other(
+            "--diff-refs, --since and --uncommitted take exactly one root directory",
+        ));
+    };
+    if cli.uncommitted {
+        return uncommitted(cli, opts, root);
+    }
+    if let Some([old, new]) = cli.diff_refs.as_deref() {
+        return diff_refs(cli, opts, root, old, new, false);
+    }
+    let new = cli.git_ref.as_deref().unwrap_or("HEAD");
+    let old = match cli.since.clone().flatten() {
+        Some(old) => old,
+        None => git::latest_tag(root, new)
+            .map_err(|e| io::Error::other(format!("{}: {}", root.display(), e.message())))?,
+    };
+    diff_refs(cli, opts, root, &old, new, true)
+}
+
+// 摘要写到 stdout，可以直接追加到提交信息；不满足的阈值写到 stderr，返回是否有
+fn hook(
+    root: &Path,
+    against: Option<&str>,
+    opts: &ScanOptions,
+    thresholds: &Thresholds,
+    json: bool,
+) -> io::Result<bool> {
+    let progress = ScanProgress::default();
+    let diff = match against {
+        Some(reference) => git::diff_refs(root, reference, "HEAD", opts, &progress),
+        None => git::diff_staged(root, opts, &progress),
+    }
+    .map_err(|e| io::Error::other(format!("{}: {}", root.display(), e.message())))?;
+    let text = if json {
+        json::to_json(&diff)? + "\n"
+    } else {
+        output::hook_summary(&diff)
+    };
+    write_stdout(&text)?;
+    let mut failed = false;
+    for check in thresholds.check_changes(root, &diff) {
+        for violation in &check.violations {
+            eprintln!("ueloc: {}: {}", check.name, violation.message);
+            failed = true;
+        }
+    }
+    Ok(failed)
+}
+
+// 对比同一仓库的两个提交，不做阈值检查；markdown 时输出各模块的增减
+fn diff_refs(
+    cli: &Cli,
+    opts: &ScanOptions,
+    root: &Path,
+    old: &str,
+    new: &str,
+    markdown: bool,
+) -> io::Result<()> {
+    let label = format!("{}@{}..{}", root.display(), old, new);
+    let diff = progress::run(&label, cli.progress(), |p| {
+        git::diff_refs(root, old, new, opts, p)
+    })
+    .map_err(|e| io::Error::other(format!("{}: {}", root.display(), e.message())))?;
+    write_ref_diff(cli, &diff, markdown)
+}
+
+// 工作区先照常统计一遍，HEAD 一侧只读有变化的文件
+fn uncommitted(cli: &Cli, opts: &ScanOptions, root: &Path) -> io::Result<()> {
+    let current = scan_root(cli, opts, root)?;
+    let label = format!("{}@HEAD", root.display());
+    let diff = progress::run(&label, cli.progress(), |p| {
+        git::diff_workdir(root, &current.files, opts, p)
+    })
+    .map_err(|e| io::Error::other(format!("{}: {}", root.display(), e.message())))?;
+    write_ref_diff(cli, &diff, false)
+}
+
+fn write_ref_diff(cli: &Cli, diff: &RefDiff, markdown: bool) -> io::Result<()> {
+    let text = if cli.json {
+        json::to_json(diff)? + "\n"
+    } else if markdown {
+        changes_markdown(&diff.modules)
+    } else {
+        output::ref_diff_table(diff, cli.style())
+    };
+    match &cli.output {
+        Some(path) => fs::write(path, text),
+        None => write_stdout(&text),
+    }
+}
+
+// 对比两个目录，不做阈值检查
+fn diff_dirs(cli: &Cli, opts: &ScanOptions, old: &Path, new: &Path) -> io::Result<()> {
+    let diff = Diff::between(&scan_root(cli, opts, old)?, &scan_root(cli, opts, new)?);
+    let text = if cli.json {
+        json::to_json(&diff)? + "\n"
+    } else {
+        output::diff_table(&diff, cli.style())
+    };
+    match &cli.output {
+        Some(path) => fs::write(path, text),
+        None => write_stdout(&text),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+    if let Some(Command::Completions { shell }) = cli.command {
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut Cli::command(), "ueloc", &mut script);
+        return match write_stdout(&String::from_utf8_lossy(&script)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("ueloc: {}", e);
+                ExitCode::from(EXIT_ERROR)
+            }
+        };
+    }
+    init_logging(&cli);
+    if let Some(Command::Hook {
+        root,
+        against,
+        config,
+        json,
+        thresholds,
+    }) = cli.command.take()
+    {
+        let config = match config.as_deref().map(Config::load).transpose() {
+            Ok(config) => config.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("ueloc: cannot read config: {}", e);
+                return ExitCode::from(EXIT_ERROR);
+            }
+        };
+        let thresholds = thresholds.or(config.thresholds);
+        return match hook(&root, against.as_deref(), &config.scan, &thresholds, json) {
+            Ok(false) => ExitCode::SUCCESS,
+            Ok(true) => ExitCode::from(EXIT_VIOLATION),
+            Err(e) => {
+                eprintln!("ueloc: {}", e);
+                ExitCode::from(EXIT_ERROR)
+            }
+        };
+    }
+    let config = match cli.config.as_deref().map(Config::load).transpose() {
+        Ok(config) => config.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("ueloc: cannot read config: {}", e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    if let Some(list) = &cli.projects_list {
+        match read_projects_list(list) {
+            Ok(roots) => cli.paths.extend(roots),
+            Err(e) => {
+                eprintln!("ueloc: cannot read projects list: {}", e);
+                return ExitCode::from(EXIT_ERROR);
+            }
+        }
+    }
+    if cli.paths.is_empty() && cli.diff.is_none() && cli.files_from.is_none() {
+        if config.paths.is_empty() {
+            eprintln!("ueloc: no project root given on the command line or in the config");
+            return ExitCode::from(EXIT_ERROR);
+        }
+        cli.paths = config.paths;
+    }
+    cli.baseline = cli.baseline.or(config.baseline);
+    cli.thresholds = std::mem::take(&mut cli.thresholds).or(config.thresholds);
+    if cli.xml && cli.format != Format::Cloc {
+        eprintln!("ueloc: --xml requires --format cloc");
+        return ExitCode::from(EXIT_ERROR);
+    }
+    let mut dirs = cli.paths.iter().chain(cli.diff.iter().flatten());
+    if let Some(path) = dirs.find(|p| !p.is_dir()) {
+        eprintln!("ueloc: {} is not a directory", path.display());
+        return ExitCode::from(EXIT_ERROR);
+    }
+    // 命令行、工程列表和配置可能写了同一个目录，重复统计会让合计翻倍
+    let mut seen = HashSet::new();
+    cli.paths.retain(|path| {
+        let first = seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone()));
+        if !first && !cli.quiet {
+            eprintln!(
+                "ueloc: {} is listed more than once, counting it once",
+                path.display()
+            );
+        }
+        first
+    });
+
+    let mut opts = config.scan;
+    if !cli.exclude.is_empty() {
+        opts.exclude_globs = cli.exclude.clone();
+    }
+    if !cli.include.is_empty() {
+        opts.include_globs = cli.include.clone();
+    }
+    if let Some(jobs) = cli.jobs {
+        opts.threads = jobs;
+    }
+    if cli.tracked_only {
+        opts.tracked_only = true;
+    }
+    if cli.p4 {
+        opts.perforce = true;
+    }
+    if let Some(change) = &cli.p4_changelist {
+        opts.p4_changelist = change.clone();
+    }
+    if !cli.submodule.is_empty() {
+        opts.include_submodules = cli.submodule.clone();
+    }
+    if let Some(sampling) = cli.trend {
+        return match trend(&cli, &opts, sampling) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("ueloc: {}", e);
+                ExitCode::from(EXIT_ERROR)
+            }
+        };
+    }
+    if cli.diff_refs.is_some() || cli.since.is_some() || cli.uncommitted {
+        return match since(&cli, &opts) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("ueloc: {}", e);
+                ExitCode::from(EXIT_ERROR)
+            }
+        };
+    }
+    if let Some([old, new]) = cli.diff.as_deref() {
+        return match diff_dirs(&cli, &opts, old, new) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("ueloc: {}", e);
+                ExitCode::from(EXIT_ERROR)
+            }
+        };
+    }
+
+    if cli.files_from.is_some() && cli.paths.len() > 1 {
+        eprintln!("ueloc: --files-from takes at most one root directory");
+        return ExitCode::from(EXIT_ERROR);
+    }
+    if cli.baseline.is_some() && cli.paths.len() > 1 {
+        eprintln!("ueloc: --baseline compares a single root directory");
+        return ExitCode::from(EXIT_ERROR);
+    }
+    if cli.thresholds.fail_on_comment_ratio_drop.is_some() && cli.paths.len() > 1 {
+        eprintln!("ueloc: --fail-on-comment-ratio-drop compares a single root directory");
+        return ExitCode::from(EXIT_ERROR);
+    }
+    let inputs = match Inputs::load(&cli) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("ueloc: {}", e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+
+    let failed = match scan_and_report(&cli, &opts, &inputs) {
+        Ok(failed) => failed,
+        Err(e) => {
+            eprintln!("ueloc: {}", e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+
+    // 监视模式下阈值只做提示，出错也继续监视
+    if cli.watch {
+        let watched = watch::run(&cli.paths, &opts, || {
+            if let Err(e) = scan_and_report(&cli, &opts, &inputs) {
+                eprintln!("ueloc: {}", e);
+            }
+        });
+        return match watched {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("ueloc: {}", e);
+                ExitCode::from(EXIT_ERROR)
+            }
+        };
+    }
+
+    if failed {
+        ExitCode::from(EXIT_VIOLATION)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
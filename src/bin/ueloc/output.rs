@@ -0,0 +1,555 @@
+use std::path::Path;
+
+use ue_loc_gui::authors::{ages_by_year, AgeStat, AuthorReport, AuthorStat};
+use ue_loc_gui::cloc;
+use ue_loc_gui::diff::{Diff, LineDelta};
+use ue_loc_gui::git::RefDiff;
+use ue_loc_gui::stat::{largest_files, LineCounts, ScanWarning, StatResult, WarningKind};
+
+use crate::checks::Violation;
+
+/* ---------------- 终端颜色 ---------------- */
+// 颜色只加在补齐宽度之后的单元格外面，转义序列不影响对齐
+#[derive(Clone, Copy)]
+pub struct Style {
+    color: bool,
+}
+
+impl Style {
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+
+    fn paint(self, code: &str, text: String) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text
+        }
+    }
+
+    fn bold(self, text: String) -> String {
+        self.paint("1", text)
+    }
+
+    fn code(self, text: String) -> String {
+        self.paint("32", text)
+    }
+
+    fn comment(self, text: String) -> String {
+        self.paint("36", text)
+    }
+
+    fn dim(self, text: String) -> String {
+        self.paint("2", text)
+    }
+
+    // 增加为绿色、减少为红色
+    fn delta(self, text: String, delta: i64) -> String {
+        match delta.signum() {
+            1 => self.paint("32", text),
+            -1 => self.paint("31", text),
+            _ => text,
+        }
+    }
+}
+
+/* ---------------- 终端表格 ---------------- */
+const RULE_CHAR: char = '-';
+
+fn percent(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        return "0.0%".to_owned();
+    }
+    format!("{:.1}%", part as f64 * 100.0 / whole as f64)
+}
+
+fn name_width<'a>(names: impl IntoIterator<Item = &'a str>, min: &str) -> usize {
+    let width = names.into_iter().map(|n| n.chars().count()).max();
+    width.unwrap_or(0).max(min.chars().count()) + 2
+}
+
+// 名称、文件数、各类行数和注释率，供语言、模块和工程表格共用
+fn header_row(style: Style, first: &str, width: usize) -> String {
+    style.bold(format!(
+        "{:<width$}{:>8}{:>12}{:>12}{:>12}{:>12}{:>10}",
+        first, "Files", "Total", "Code", "Comment", "Blank", "Comment%"
+    )) + "\n"
+}
+
+fn counts_row(style: Style, name: &str, width: usize, files: usize, lines: &LineCounts) -> String {
+    format!(
+        "{:<width$}{:>8}{:>12}{}{}{:>12}{:>9.1}%",
+        name,
+        files,
+        lines.total,
+        style.code(format!("{:>12}", lines.code)),
+        style.comment(format!("{:>12}", lines.comment)),
+        lines.blank,
+        lines.comment_ratio() * 100.0
+    )
+}
+
+fn rule(style: Style, width: usize) -> String {
+    let len = width + 8 + 12 * 4 + 10;
+    style.dim(RULE_CHAR.to_string().repeat(len)) + "\n"
+}
+
+// 每种语言一行，合计行下面再给出各类行数占总行数的比例
+pub fn summary_table(result: &StatResult, style: Style) -> String {
+    let languages = cloc::languages(&[result]);
+    let width = name_width(languages.iter().map(|l| l.name.as_str()), "Language");
+    let totals = &result.totals;
+
+    let mut out = header_row(style, "Language", width);
+    for lang in &languages {
+        out += &counts_row(style, &lang.name, width, lang.files, &lang.lines);
+        out += "\n";
+    }
+    out += &rule(style, width);
+    // 合计行整行加粗，单元格内不再单独着色
+    let plain = Style::new(false);
+    out += &style.bold(counts_row(
+        plain,
+        "Total",
+        width,
+        result.files.len(),
+        totals,
+    ));
+    out += "\n";
+    out += &style.dim(format!(
+        "{:<width$}{:>8}{:>12}{:>12}{:>12}{:>12}",
+        "Share",
+        "",
+        "",
+        percent(totals.code, totals.total),
+        percent(totals.comment, totals.total),
+        percent(totals.blank, totals.total)
+    ));
+    out += "\n";
+    out
+}
+
+// 多个工程时每个工程一行，最后一行为合计
+pub fn roots_table(results: &[(&Path, &StatResult)], style: Style) -> String {
+    let names: Vec<String> = results
+        .iter()
+        .map(|(root, _)| root.display().to_string())
+        .collect();
+    let width = name_width(names.iter().map(String::as_str), "Combined");
+
+    let mut out = header_row(style, "Root", width);
+    let mut files = 0;
+    let mut totals = LineCounts::default();
+    for (name, (_, result)) in names.iter().zip(results) {
+        files += result.files.len();
+        totals += result.totals;
+        out += &counts_row(style, name, width, result.files.len(), &result.totals);
+        out += "\n";
+    }
+    out += &rule(style, width);
+    let plain = Style::new(false);
+    out += &style.bold(counts_row(plain, "Combined", width, files, &totals));
+    out += "\n";
+    out
+}
+
+pub fn module_name(name: Option<&str>, plugin: Option<&str>) -> String {
+    let name = name.unwrap_or("(no module)");
+    match plugin {
+        Some(plugin) => format!("{} ({})", name, plugin),
+        None => name.to_owned(),
+    }
+}
+
+// 先列整体指标的变化，再列各模块有效代码行的变化
+pub fn diff_table(diff: &Diff, style: Style) -> String {
+    let (a, b, d) = (&diff.before.lines, &diff.after.lines, &diff.delta);
+    let rows = [
+        (
+            "Files",
+            diff.before.file_count,
+            diff.after.file_count,
+            diff.file_delta,
+        ),
+        ("Total", a.total, b.total, d.total),
+        ("Code", a.code, b.code, d.code),
+        ("Comment", a.comment, b.comment, d.comment),
+        ("Blank", a.blank, b.blank, d.blank),
+        ("Includes", a.includes, b.includes, d.includes),
+        ("Reflection", a.reflection, b.reflection, d.reflection),
+    ];
+    let mut out = style.bold(format!(
+        "{:<14}{:>12}{:>12}{:>10}",
+        "Metric", "Old", "New", "Delta"
+    )) + "\n";
+    for (label, old, new, delta) in rows {
+        out += &format!(
+            "{:<14}{:>12}{:>12}{}\n",
+            label,
+            old,
+            new,
+            style.delta(format!("{:>+10}", delta), delta)
+        );
+    }
+    let ratio_delta = diff.comment_ratio_delta * 100.0;
+    out += &format!(
+        "{:<14}{:>11.1}%{:>11.1}%{}\n",
+        "Comment ratio",
+        a.comment_ratio() * 100.0,
+        b.comment_ratio() * 100.0,
+        style.delta(
+            format!("{:>+9.1}%", ratio_delta),
+            // 四舍五入后为 0 的变化不着色
+            (ratio_delta * 10.0).round() as i64
+        )
+    );
+
+    if diff.modules.is_empty() {
+        return out;
+    }
+    let names: Vec<String> = diff
+        .modules
+        .iter()
+        .map(|m| module_name(m.name.as_deref(), m.plugin.as_deref()))
+        .collect();
+    let width = name_width(names.iter().map(String::as_str), "Module");
+    out += "\n";
+    out += &style.bold(format!(
+        "{:<width$}{:>12}{:>12}{:>10}",
+        "Module", "Old code", "New code", "Delta"
+    ));
+    out += "\n";
+    for (name, m) in names.iter().zip(&diff.modules) {
+        out += &format!(
+            "{:<width$}{:>12}{:>12}{}\n",
+            name,
+            m.before.code,
+            m.after.code,
+            style.delta(format!("{:>+10}", m.delta.code), m.delta.code)
+        );
+    }
+    out
+}
+
+// 两个提交之间有变化的文件按模块汇总，末行为合计
+pub fn ref_diff_table(diff: &RefDiff, style: Style) -> String {
+    let mut out = format!(
+        "{}..{}: {} files changed\n",
+        diff.from,
+        diff.to,
+        diff.files.len()
+    );
+    if diff.modules.is_empty() {
+        return out;
+    }
+    let names: Vec<String> = diff
+        .modules
+        .iter()
+        .map(|m| module_name(m.name.as_deref(), m.plugin.as_deref()))
+        .collect();
+    let width = name_width(names.iter().map(String::as_str), "Module");
+    let row = |name: &str, files: usize, d: &LineDelta| {
+        let cell = |delta: i64| style.delta(format!("{:>+10}", delta), delta);
+        format!(
+            "{:<width$}{:>8}{}{}{}{}\n",
+            name,
+            files,
+            cell(d.total),
+            cell(d.code),
+            cell(d.comment),
+            cell(d.blank)
+        )
+    };
+    out += "\n";
+    out += &style.bold(format!(
+        "{:<width$}{:>8}{:>10}{:>10}{:>10}{:>10}",
+        "Module", "Files", "Total", "Code", "Comment", "Blank"
+    ));
+    out += "\n";
+    for (name, m) in names.iter().zip(&diff.modules) {
+        out += &row(name, m.files, &m.delta);
+    }
+    out += &row("Total", diff.files.len(), &diff.delta);
+    out
+}
+
+// 适合附在提交信息末尾的两行，如 "Code-Delta: +35 (+42 -7), comment +5, 3 files"；
+// 没有变化时为空
+pub fn hook_summary(diff: &RefDiff) -> String {
+    if diff.files.is_empty() {
+        return String::new();
+    }
+    let added: usize = diff.modules.iter().map(|m| m.added).sum();
+    let removed: usize = diff.modules.iter().map(|m| m.removed).sum();
+    let mut out = format!(
+        "Code-Delta: {:+} (+{} -{}), comment {:+}, {} files\n",
+        diff.delta.code,
+        added,
+        removed,
+        diff.delta.comment,
+        diff.files.len()
+    );
+    let modules: Vec<String> = diff
+        .modules
+        .iter()
+        .map(|m| {
+            let name = module_name(m.name.as_deref(), m.plugin.as_deref());
+            format!("{} {:+}", name, m.delta.code)
+        })
+        .collect();
+    out += &format!("Code-Delta-Modules: {}\n", modules.join(", "));
+    out
+}
+
+// 各模块一行，顺序沿用统计结果（按有效代码行数从多到少）
+pub fn modules_table(result: &StatResult, style: Style) -> String {
+    let names: Vec<String> = result
+        .modules
+        .iter()
+        .map(|m| module_name(m.name.as_deref(), m.plugin.as_deref()))
+        .collect();
+    let width = name_width(names.iter().map(String::as_str), "Module");
+    let mut out = header_row(style, "Module", width);
+    for (name, m) in names.iter().zip(&result.modules) {
+        out += &counts_row(style, name, width, m.files, &m.lines);
+        out += "\n";
+    }
+    out
+}
+
+// 子模块单独列出；未纳入统计的只标出路径
+pub fn submodules_table(result: &StatResult, style: Style) -> String {
+    let width = name_width(
+        result.submodules.iter().map(|s| s.path.as_str()),
+        "Submodule",
+    );
+    let mut out = header_row(style, "Submodule", width);
+    for s in &result.submodules {
+        if s.included {
+            out += &counts_row(style, &s.path, width, s.files, &s.lines);
+        } else {
+            out += &format!("{:<width$}{:>8}", s.path, "excluded");
+        }
+        out += "\n";
+    }
+    out
+}
+
+// 作者名后附上邮箱，与 git log 的写法相同
+fn author_name(author: &AuthorStat) -> String {
+    if author.email.is_empty() {
+        author.name.clone()
+    } else {
+        format!("{} <{}>", author.name, author.email)
+    }
+}
+
+fn authors_rows(authors: &[AuthorStat], style: Style) -> String {
+    let names: Vec<String> = authors.iter().map(author_name).collect();
+    let width = name_width(names.iter().map(String::as_str), "Author");
+    let total: usize = authors.iter().map(|a| a.lines.code).sum();
+    let mut out = style.bold(format!(
+        "{:<width$}{:>8}{:>12}{:>8}{:>12}{:>10}",
+        "Author", "Files", "Code", "Share", "Comment", "Comment%"
+    )) + "\n";
+    for (name, a) in names.iter().zip(authors) {
+        out += &format!(
+            "{:<width$}{:>8}{}{:>8}{}{:>9.1}%\n",
+            name,
+            a.files,
+            style.code(format!("{:>12}", a.lines.code)),
+            percent(a.lines.code, total),
+            style.comment(format!("{:>12}", a.lines.comment)),
+            a.lines.comment_ratio() * 100.0
+        );
+    }
+    out
+}
+
+// 每个模块的主要作者，即 blame 中有效代码行最多的一位
+fn ownership_rows(report: &AuthorReport, style: Style) -> String {
+    let owned: Vec<_> = report
+        .modules
+        .iter()
+        .filter_map(|m| {
+            Some((
+                module_name(m.name.as_deref(), m.plugin.as_deref()),
+                m,
+                m.owner()?,
+            ))
+        })
+        .collect();
+    let width = name_width(owned.iter().map(|(name, ..)| name.as_str()), "Module");
+    let mut out = style.bold(format!(
+        "{:<width$}{:>12}{:>8}  {}",
+        "Module", "Code", "Share", "Owner"
+    )) + "\n";
+    for (name, m, (owner, share)) in &owned {
+        out += &format!(
+            "{:<width$}{}{:>7.1}%  {}\n",
+            name,
+            style.code(format!("{:>12}", m.code())),
+            share * 100.0,
+            author_name(owner)
+        );
+    }
+    out
+}
+
+// 整个工程的作者表，by_module 时再列出各模块的主要作者和各模块的作者表
+pub fn authors_table(report: &AuthorReport, by_module: bool, style: Style) -> String {
+    let mut out = authors_rows(&report.authors, style);
+    if by_module {
+        out += "\n";
+        out += &ownership_rows(report, style);
+        for m in report.modules.iter().filter(|m| !m.authors.is_empty()) {
+            out += &format!(
+                "\n{}\n",
+                style.bold(module_name(m.name.as_deref(), m.plugin.as_deref()))
+            );
+            out += &authors_rows(&m.authors, style);
+        }
+    }
+    if report.untracked > 0 {
+        out += &style.dim(format!(
+            "\n{} files are not in HEAD and were left out\n",
+            report.untracked
+        ));
+    }
+    out
+}
+
+// 每个时间段一行，末尾的条形按有效代码行最多的时间段缩放
+fn ages_rows(ages: &[AgeStat], style: Style) -> String {
+    const BAR: usize = 40;
+    let total: usize = ages.iter().map(|a| a.lines.code).sum();
+    let max = ages.iter().map(|a| a.lines.code).max().unwrap_or(0).max(1);
+    let mut out = style.bold(format!("{:<10}{:>12}{:>8}", "Period", "Code", "Share")) + "\n";
+    for age in ages {
+        out += &format!(
+            "{:<10}{}{:>8}  {}\n",
+            age.label(),
+            style.code(format!("{:>12}", age.lines.code)),
+            percent(age.lines.code, total),
+            "#".repeat(age.lines.code * BAR / max)
+        );
+    }
+    out
+}
+
+// 按最后修改时间汇总的代码年龄，by_module 时再逐个模块列出
+pub fn ages_table(report: &AuthorReport, by_year: bool, by_module: bool, style: Style) -> String {
+    let bucket = |ages: &[AgeStat]| {
+        if by_year {
+            ages_by_year(ages)
+        } else {
+            ages.to_vec()
+        }
+    };
+    let mut out = ages_rows(&bucket(&report.ages), style);
+    if by_module {
+        for m in report.modules.iter().filter(|m| !m.ages.is_empty()) {
+            out += &format!(
+                "\n{}\n",
+                style.bold(module_name(m.name.as_deref(), m.plugin.as_deref()))
+            );
+            out += &ages_rows(&bucket(&m.ages), style);
+        }
+    }
+    if report.untracked > 0 {
+        out += &style.dim(format!(
+            "\n{} files are not in HEAD and were left out\n",
+            report.untracked
+        ));
+    }
+    out
+}
+
+// 有效代码行数最多的文件，路径放在最后一列，不用对齐
+pub fn top_files_table(result: &StatResult, n: usize, style: Style) -> String {
+    let mut out = style.bold(format!("{:>10}{:>10}  {}", "Code", "Total", "File")) + "\n";
+    for file in largest_files(&result.files, n) {
+        out += &format!(
+            "{}{:>10}  {}\n",
+            style.code(format!("{:>10}", file.lines.code)),
+            file.lines.total,
+            file.rel_path
+        );
+    }
+    out
+}
+
+// 变动量最大的 n 个文件，窗口内没有改动的不列出
+pub fn churn_table(result: &StatResult, n: usize, style: Style) -> String {
+    let mut files: Vec<_> = result
+        .files
+        .iter()
+        .filter(|f| f.churn.is_some_and(|c| c > 0))
+        .collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.churn));
+    let mut out = style.bold(format!("{:>10}{:>10}  {}", "Churn", "Code", "File")) + "\n";
+    for file in files.into_iter().take(n) {
+        out += &format!(
+            "{}{:>10}  {}\n",
+            style.code(format!("{:>10}", file.churn.unwrap_or(0))),
+            file.lines.code,
+            file.rel_path
+        );
+    }
+    out
+}
+
+/* ---------------- GitHub Actions 注释 ---------------- */
+// 工作流命令里的消息和属性值需要转义，否则换行、冒号和逗号会截断命令
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+// 注释路径相对仓库根目录，去掉 ./ 前缀
+fn annotation_file(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    escape_property(&path.display().to_string().replace('\\', "/"))
+}
+
+// 阈值不满足记为错误
+pub fn github_error(check: &str, prefix: &str, violation: &Violation) -> String {
+    let file = violation
+        .file
+        .as_deref()
+        .map(|f| format!("file={},", annotation_file(f)))
+        .unwrap_or_default();
+    format!(
+        "::error {}title={}::{}\n",
+        file,
+        escape_property(&format!("ueloc {}", check)),
+        escape_data(&format!("{}{}", prefix, violation.message))
+    )
+}
+
+// 被跳过或按有损方式读取的文件记为警告
+pub fn github_warning(warning: &ScanWarning) -> String {
+    let what = match warning.kind {
+        WarningKind::Walk => "cannot read directory entry",
+        WarningKind::Read => "cannot read file",
+        WarningKind::Encoding => "invalid text encoding, counted with replacement characters",
+        WarningKind::TooLarge => "skipped: larger than the size limit",
+        WarningKind::Binary => "skipped: looks like a binary file",
+    };
+    let message = if warning.message.is_empty() {
+        what.to_owned()
+    } else {
+        format!("{} ({})", what, warning.message)
+    };
+    format!(
+        "::warning file={},title=ueloc::{}\n",
+        annotation_file(&warning.path),
+        escape_data(&message)
+    )
+}
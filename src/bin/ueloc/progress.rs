@@ -0,0 +1,64 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ue_loc_gui::stat::ScanProgress;
+
+// 终端里刷新进度条的间隔
+const TICK: Duration = Duration::from_millis(100);
+// 非终端（如 CI 日志）时输出一行进度的间隔，统计更快结束则不输出
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+const BAR_WIDTH: usize = 30;
+
+/* ---------------- 进度显示 ---------------- */
+fn bar_line(label: &str, progress: &ScanProgress) -> String {
+    match progress.snapshot() {
+        (counted, Some(total)) if total > 0 => {
+            let filled = counted * BAR_WIDTH / total;
+            format!(
+                "{} [{}{}] {}/{} files",
+                label,
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled),
+                counted,
+                total
+            )
+        }
+        (counted, Some(total)) => format!("{} {}/{} files", label, counted, total),
+        (_, None) => format!("{} walking, {} files found", label, progress.found()),
+    }
+}
+
+// 在后台线程执行 scan，同时在 stderr 显示进度
+pub fn run<T: Send>(label: &str, enabled: bool, scan: impl FnOnce(&ScanProgress) -> T + Send) -> T {
+    let progress = ScanProgress::default();
+    if !enabled {
+        return scan(&progress);
+    }
+
+    let tty = io::stderr().is_terminal();
+    thread::scope(|s| {
+        let handle = s.spawn(|| scan(&progress));
+        let started = Instant::now();
+        let mut logged = started;
+        let mut drawn = false;
+        while !handle.is_finished() {
+            thread::sleep(TICK);
+            if tty {
+                // \x1b[K 清掉上一次较长的内容
+                eprint!("\r{}\x1b[K", bar_line(label, &progress));
+                let _ = io::stderr().flush();
+                drawn = true;
+            } else if logged.elapsed() >= LOG_INTERVAL {
+                eprintln!("ueloc: {}", bar_line(label, &progress));
+                logged = Instant::now();
+            }
+        }
+        if drawn {
+            eprint!("\r\x1b[K");
+        }
+        handle.join().expect("统计线程异常退出")
+    })
+}
@@ -0,0 +1,43 @@
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::{path::PathBuf, sync::mpsc, time::Duration};
+
+use ue_loc_gui::stat::{ScanFilter, ScanOptions};
+
+// 文件事件停下这么久之后才重新统计，避免保存或切分支时连续触发
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/* ---------------- 监视模式 ---------------- */
+// 源文件有变化时调用 rescan，直到被 Ctrl+C 结束
+pub fn run(roots: &[PathBuf], opts: &ScanOptions, mut rescan: impl FnMut()) -> notify::Result<()> {
+    // 事件里的路径以监视时给出的路径开头，统一成绝对路径便于比较
+    let roots: Vec<PathBuf> = roots
+        .iter()
+        .map(|r| r.canonicalize().unwrap_or_else(|_| r.clone()))
+        .collect();
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for root in &roots {
+        watcher.watch(root, RecursiveMode::Recursive)?;
+    }
+
+    let filter = ScanFilter::new(opts);
+    // 统计本身会读文件，读取类事件必须忽略，否则会不停地重新统计
+    let relevant = |event: notify::Result<Event>| {
+        event.is_ok_and(|e| {
+            !matches!(e.kind, EventKind::Access(_))
+                && e.paths.iter().any(|p| {
+                    roots
+                        .iter()
+                        .any(|root| p.starts_with(root) && filter.affects_result(root, p))
+                })
+        })
+    };
+    while let Ok(event) = rx.recv() {
+        if !relevant(event) {
+            continue;
+        }
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+        rescan();
+    }
+    Ok(())
+}
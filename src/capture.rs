@@ -0,0 +1,96 @@
+use eframe::egui;
+use rfd::FileDialog;
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use crate::i18n::Texts;
+
+/* ---------------- 图表另存为图片 ---------------- */
+// 请求整个窗口的截图，收到后按图表所在区域裁剪并写成 PNG
+#[derive(Default)]
+pub struct ChartCapture {
+    pending: Option<Pending>,
+}
+
+struct Pending {
+    // 图表的屏幕区域（逻辑像素）
+    rect: egui::Rect,
+    path: PathBuf,
+    // 选择文件的那一帧里右键菜单还在，下一帧才请求截图
+    requested: bool,
+}
+
+impl ChartCapture {
+    // 在图表上右键时提供“保存为图片”，name 为默认文件名
+    pub fn menu(&mut self, response: Option<egui::Response>, name: &str, t: &Texts) {
+        let Some(response) = response else {
+            return;
+        };
+        response.context_menu(|ui| {
+            if !ui.button(t.save_chart).clicked() {
+                return;
+            }
+            ui.close_menu();
+            if let Some(path) = FileDialog::new()
+                .add_filter("PNG", &["png"])
+                .set_file_name(format!("{}.png", name))
+                .save_file()
+            {
+                self.pending = Some(Pending {
+                    rect: response.interact_rect,
+                    path,
+                    requested: false,
+                });
+                ui.ctx().request_repaint();
+            }
+        });
+    }
+
+    // 每帧调用，写出图片后返回结果
+    pub fn poll(&mut self, ctx: &egui::Context) -> Option<io::Result<PathBuf>> {
+        let pending = self.pending.as_mut()?;
+        if !pending.requested {
+            pending.requested = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot);
+            return None;
+        }
+        let image = ctx.input(|i| {
+            i.raw.events.iter().find_map(|e| match e {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        })?;
+        let pending = self.pending.take()?;
+        // 图表可能有一部分在窗口外
+        let ppp = ctx.pixels_per_point();
+        let [width, height] = image.size;
+        let window = egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(width as f32, height as f32) / ppp,
+        );
+        let rect = pending.rect.intersect(window);
+        if !rect.is_positive() {
+            return Some(Err(io::Error::other("the chart is not visible")));
+        }
+        let region = image.region(&rect, Some(ppp));
+        Some(write_png(&pending.path, &region).map(|()| pending.path))
+    }
+}
+
+fn write_png(path: &Path, image: &egui::ColorImage) -> io::Result<()> {
+    let [width, height] = image.size;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        width as u32,
+        height as u32,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer
+        .write_image_data(image.as_raw())
+        .map_err(io::Error::other)
+}
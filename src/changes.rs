@@ -0,0 +1,246 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::{
+    path::Path,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use crate::charts;
+use crate::compare::delta_label;
+use crate::diff::changes_markdown;
+use crate::format;
+use crate::git::{self, RefDiff};
+use crate::i18n::Texts;
+use crate::stat::{FileStat, ScanOptions, ScanProgress};
+
+/* ---------------- 两个提交之间的变化 ---------------- */
+// 只读取两棵树之间有变化的文件，仍在后台线程进行
+pub struct ChangesView {
+    from: String,
+    to: String,
+    pending: Option<Receiver<Result<RefDiff, String>>>,
+    diff: Option<RefDiff>,
+    error: Option<String>,
+}
+
+impl Default for ChangesView {
+    fn default() -> Self {
+        Self {
+            from: "HEAD~1".to_owned(),
+            to: "HEAD".to_owned(),
+            pending: None,
+            diff: None,
+            error: None,
+        }
+    }
+}
+
+impl ChangesView {
+    fn start(&mut self, root: &Path, opts: &ScanOptions, ctx: &egui::Context) {
+        let (from, to) = (self.from.trim().to_owned(), self.to.trim().to_owned());
+        self.spawn(root, opts, ctx, move |root, opts, progress| {
+            git::diff_refs(root, &from, &to, opts, progress)
+        });
+    }
+
+    // 工作区一侧用当前标签页已有的统计结果
+    fn start_local(
+        &mut self,
+        root: &Path,
+        files: &[FileStat],
+        opts: &ScanOptions,
+        ctx: &egui::Context,
+    ) {
+        let files = files.to_vec();
+        self.spawn(root, opts, ctx, move |root, opts, progress| {
+            git::diff_workdir(root, &files, opts, progress)
+        });
+    }
+
+    fn spawn(
+        &mut self,
+        root: &Path,
+        opts: &ScanOptions,
+        ctx: &egui::Context,
+        diff: impl FnOnce(&Path, &ScanOptions, &ScanProgress) -> Result<RefDiff, git2::Error>
+            + Send
+            + 'static,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        let (root, opts) = (root.to_path_buf(), opts.clone());
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let diff =
+                diff(&root, &opts, &ScanProgress::default()).map_err(|e| e.message().to_owned());
+            let _ = tx.send(diff);
+            ctx.request_repaint();
+        });
+        self.pending = Some(rx);
+        self.error = None;
+    }
+
+    fn poll(&mut self) {
+        let Some(rx) = &self.pending else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(diff)) => {
+                self.pending = None;
+                self.diff = Some(diff);
+            }
+            Ok(Err(e)) => {
+                self.pending = None;
+                self.error = Some(e);
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.pending = None,
+        }
+    }
+
+    // files 为当前的工作区统计结果；查看历史提交的标签页传 None，不能对比工作区
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: &Path,
+        files: Option<&[FileStat]>,
+        opts: &ScanOptions,
+        t: &Texts,
+    ) {
+        self.poll();
+        let running = self.pending.is_some();
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!running, |ui| {
+                ui.label(t.changes_from);
+                ui.add(egui::TextEdit::singleline(&mut self.from).desired_width(120.0));
+                ui.label(t.changes_to);
+                ui.add(egui::TextEdit::singleline(&mut self.to).desired_width(120.0));
+                let ready = !self.from.trim().is_empty() && !self.to.trim().is_empty();
+                if ui
+                    .add_enabled(ready, egui::Button::new(t.changes_run))
+                    .on_hover_text(t.changes_hint)
+                    .clicked()
+                {
+                    self.start(root, opts, ui.ctx());
+                }
+                // 找标签很快，直接在界面线程进行
+                if ui
+                    .add_enabled(!self.to.trim().is_empty(), egui::Button::new(t.since_tag))
+                    .on_hover_text(t.since_tag_hint)
+                    .clicked()
+                {
+                    match git::latest_tag(root, self.to.trim()) {
+                        Ok(tag) => {
+                            self.from = tag;
+                            self.start(root, opts, ui.ctx());
+                        }
+                        Err(e) => self.error = Some(e.message().to_owned()),
+                    }
+                }
+                ui.separator();
+                if let Some(files) = files {
+                    if ui
+                        .button(t.changes_local)
+                        .on_hover_text(t.changes_local_hint)
+                        .clicked()
+                    {
+                        self.start_local(root, files, opts, ui.ctx());
+                    }
+                }
+            });
+            if running {
+                ui.spinner();
+            }
+        });
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        ui.add_space(4.0);
+        let Some(diff) = &self.diff else {
+            if !running {
+                ui.weak(t.changes_empty);
+            }
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{}..{}{}{} {}",
+                diff.from,
+                diff.to,
+                t.colon,
+                format::count(diff.files.len(), t),
+                t.changes_files
+            ));
+            ui.separator();
+            ui.label(t.col_code);
+            delta_label(
+                ui,
+                format::signed(diff.delta.code, t),
+                diff.delta.code as f64,
+            );
+            ui.separator();
+            if ui
+                .button(t.copy_markdown)
+                .on_hover_text(t.changes_markdown_hint)
+                .clicked()
+            {
+                let text = changes_markdown(&diff.modules);
+                ui.output_mut(|o| o.copied_text = text);
+            }
+        });
+        ui.add_space(4.0);
+        change_table(ui, diff, t);
+    }
+}
+
+fn change_table(ui: &mut egui::Ui, diff: &RefDiff, t: &Texts) {
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::remainder().at_least(120.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 7)
+        .header(row_height + 6.0, |mut header| {
+            for label in [
+                t.col_module,
+                t.changes_files,
+                t.diff_added,
+                t.diff_removed,
+                t.col_total,
+                t.col_code,
+                t.col_comment,
+                t.col_blank,
+            ] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, diff.modules.len(), |mut row| {
+                let m = &diff.modules[row.index()];
+                row.col(|ui| {
+                    ui.label(charts::module_name(
+                        m.name.as_deref(),
+                        m.plugin.as_deref(),
+                        t,
+                    ));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(m.files, t));
+                });
+                row.col(|ui| {
+                    delta_label(ui, format::signed(m.added as i64, t), m.added as f64);
+                });
+                row.col(|ui| {
+                    let removed = -(m.removed as i64);
+                    delta_label(ui, format::signed(removed, t), removed as f64);
+                });
+                for delta in [m.delta.total, m.delta.code, m.delta.comment, m.delta.blank] {
+                    row.col(|ui| {
+                        delta_label(ui, format::signed(delta, t), delta as f64);
+                    });
+                }
+            });
+        });
+}
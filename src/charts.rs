@@ -0,0 +1,346 @@
+use chrono::{DateTime, Local};
+use eframe::egui;
+use egui::Color32;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints, Points, Polygon};
+use std::f64::consts::TAU;
+
+use crate::authors::AgeStat;
+use crate::format;
+use crate::history::HistoryEntry;
+use crate::i18n::Texts;
+use crate::stat::{by_extension, FileStat, LineCounts, ModuleStat};
+
+pub const CODE_COLOR: Color32 = Color32::from_rgb(66, 133, 244);
+pub const COMMENT_COLOR: Color32 = Color32::from_rgb(52, 168, 83);
+pub const BLANK_COLOR: Color32 = Color32::from_rgb(154, 160, 166);
+
+// 环形图内外半径
+const OUTER_RADIUS: f64 = 1.0;
+const INNER_RADIUS: f64 = 0.55;
+// egui 只能填充凸多边形，扇区按这个角度切成小段
+const SEGMENT_ANGLE: f64 = TAU / 180.0;
+
+/* ---------------- 环形图 ---------------- */
+// 从 start 到 end（弧度）的一段环，拆成若干凸四边形
+fn donut_segments(start: f64, end: f64) -> Vec<Vec<[f64; 2]>> {
+    let steps = ((end - start) / SEGMENT_ANGLE).ceil().max(1.0) as usize;
+    let step = (end - start) / steps as f64;
+    let point = |r: f64, a: f64| [r * a.cos(), r * a.sin()];
+
+    (0..steps)
+        .map(|i| {
+            let a0 = start + step * i as f64;
+            let a1 = a0 + step;
+            vec![
+                point(OUTER_RADIUS, a0),
+                point(OUTER_RADIUS, a1),
+                point(INNER_RADIUS, a1),
+                point(INNER_RADIUS, a0),
+            ]
+        })
+        .collect()
+}
+
+// slices 为（名称、数值、颜色），数值为 0 的扇区不画；各图表返回整个图的响应，没有可画的数据时为 None
+fn donut(
+    ui: &mut egui::Ui,
+    id: &str,
+    slices: &[(String, usize, Color32)],
+    size: f32,
+) -> Option<egui::Response> {
+    let total: usize = slices.iter().map(|s| s.1).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let plot = Plot::new(id)
+        .width(size)
+        .height(size)
+        .data_aspect(1.0)
+        .show_axes(false)
+        .show_grid(false)
+        .show_x(false)
+        .show_y(false)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .label_formatter(|name, _| name.to_owned())
+        .legend(Legend::default())
+        .show(ui, |plot_ui| {
+            // 从 12 点方向开始顺时针排布
+            let mut angle = TAU / 4.0;
+            for (label, value, color) in slices {
+                if *value == 0 {
+                    continue;
+                }
+                let share = *value as f64 / total as f64;
+                let name = format!("{} {:.1}%", label, share * 100.0);
+                let end = angle - share * TAU;
+                for quad in donut_segments(end, angle) {
+                    plot_ui.polygon(
+                        Polygon::new(PlotPoints::from(quad))
+                            .name(&name)
+                            .stroke(egui::Stroke::new(1.0, *color))
+                            .fill_color(*color),
+                    );
+                }
+                angle = end;
+            }
+        });
+    Some(plot.response)
+}
+
+pub fn line_category_donut(
+    ui: &mut egui::Ui,
+    counts: &LineCounts,
+    t: &Texts,
+    size: f32,
+) -> Option<egui::Response> {
+    let slices = [
+        (t.col_code.to_owned(), counts.code, CODE_COLOR),
+        (t.col_comment.to_owned(), counts.comment, COMMENT_COLOR),
+        (t.col_blank.to_owned(), counts.blank, BLANK_COLOR),
+    ];
+    donut(ui, "line_category_donut", &slices, size)
+}
+
+/* ---------------- 文件类型分布 ---------------- */
+// 扩展名扇区的配色，超出部分并入“其他”
+const EXTENSION_COLORS: [Color32; 6] = [
+    Color32::from_rgb(66, 133, 244),
+    Color32::from_rgb(234, 67, 53),
+    Color32::from_rgb(251, 188, 5),
+    Color32::from_rgb(52, 168, 83),
+    Color32::from_rgb(171, 71, 188),
+    Color32::from_rgb(0, 172, 193),
+];
+
+// 按扩展名统计有效代码行数占比
+pub fn extension_donut(
+    ui: &mut egui::Ui,
+    files: &[FileStat],
+    t: &Texts,
+    size: f32,
+) -> Option<egui::Response> {
+    let by_ext = by_extension(files);
+    let mut slices: Vec<(String, usize, Color32)> = by_ext
+        .iter()
+        .zip(EXTENSION_COLORS)
+        .map(|(e, color)| (format!(".{}", e.extension), e.lines.code, color))
+        .collect();
+    let other: usize = by_ext
+        .iter()
+        .skip(EXTENSION_COLORS.len())
+        .map(|e| e.lines.code)
+        .sum();
+    if other > 0 {
+        slices.push((t.other.to_owned(), other, BLANK_COLOR));
+    }
+    donut(ui, "extension_donut", &slices, size)
+}
+
+/* ---------------- 模块条形图 ---------------- */
+// 每根横条占用的像素高度
+const BAR_ROW_HEIGHT: f32 = 24.0;
+
+pub fn module_label(module: &ModuleStat, t: &Texts) -> String {
+    module_name(module.name.as_deref(), module.plugin.as_deref(), t)
+}
+
+pub fn module_name(name: Option<&str>, plugin: Option<&str>, t: &Texts) -> String {
+    let name = name.unwrap_or(t.no_module);
+    match plugin {
+        Some(plugin) => format!("{} ({})", name, plugin),
+        None => name.to_owned(),
+    }
+}
+
+pub fn module_bar_chart(
+    ui: &mut egui::Ui,
+    modules: &[ModuleStat],
+    t: &Texts,
+) -> Option<egui::Response> {
+    if modules.is_empty() {
+        return None;
+    }
+
+    let names: Vec<String> = modules.iter().map(|m| module_label(m, t)).collect();
+    let count = modules.len();
+
+    // 代码最多的模块排在最上方
+    let bars = modules
+        .iter()
+        .zip(&names)
+        .enumerate()
+        .map(|(i, (module, name))| {
+            Bar::new((count - 1 - i) as f64, module.lines.code as f64)
+                .name(name)
+                .width(0.7)
+        })
+        .collect();
+
+    let total_label = t.col_total.to_owned();
+    let code_label = t.col_code.to_owned();
+    let sep = t.thousands_sep;
+    let totals: Vec<usize> = modules.iter().map(|m| m.lines.total).collect();
+    let chart = BarChart::new(bars)
+        .horizontal()
+        .color(CODE_COLOR)
+        .element_formatter(Box::new(move |bar, _| {
+            let idx = count - 1 - bar.argument as usize;
+            format!(
+                "{}\n{}: {}\n{}: {}",
+                bar.name,
+                code_label,
+                format::grouped(bar.value as usize, sep),
+                total_label,
+                format::grouped(totals[idx], sep)
+            )
+        }));
+
+    let axis_names = names;
+    let scroll = egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            Plot::new("module_bar_chart")
+                .height((count as f32 * BAR_ROW_HEIGHT).max(200.0))
+                .allow_drag(false)
+                .allow_zoom(false)
+                .allow_scroll(false)
+                .allow_boxed_zoom(false)
+                .show_x(false)
+                .show_y(false)
+                .y_axis_width(6)
+                .y_axis_formatter(move |mark, _, _| {
+                    // 只在整数刻度上标模块名
+                    let v = mark.value;
+                    if v < 0.0 || v.fract() != 0.0 || v as usize >= count {
+                        return String::new();
+                    }
+                    axis_names[count - 1 - v as usize].clone()
+                })
+                .show(ui, |plot_ui| plot_ui.bar_chart(chart))
+                .response
+        });
+    Some(scroll.inner)
+}
+
+/* ---------------- 代码年龄直方图 ---------------- */
+// 每个时间段一根柱子，从早到晚排列；代码和注释叠在一起
+pub fn age_chart(ui: &mut egui::Ui, ages: &[AgeStat], t: &Texts) -> Option<egui::Response> {
+    if ages.is_empty() {
+        return None;
+    }
+    let labels: Vec<String> = ages.iter().map(AgeStat::label).collect();
+    let bars = |value: fn(&AgeStat) -> usize| -> Vec<Bar> {
+        ages.iter()
+            .zip(&labels)
+            .enumerate()
+            .map(|(i, (age, label))| Bar::new(i as f64, value(age) as f64).name(label).width(0.7))
+            .collect()
+    };
+    let sep = t.thousands_sep;
+    let formatter = move |name: &'static str| {
+        Box::new(move |bar: &Bar, _: &BarChart| {
+            format!(
+                "{}\n{}: {}",
+                bar.name,
+                name,
+                format::grouped(bar.value as usize, sep)
+            )
+        })
+    };
+    let code = BarChart::new(bars(|a| a.lines.code))
+        .name(t.col_code)
+        .color(CODE_COLOR)
+        .element_formatter(formatter(t.col_code));
+    let comment = BarChart::new(bars(|a| a.lines.comment))
+        .name(t.col_comment)
+        .color(COMMENT_COLOR)
+        .element_formatter(formatter(t.col_comment))
+        .stack_on(&[&code]);
+
+    let count = ages.len();
+    let plot = Plot::new("age_chart")
+        .legend(Legend::default())
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .show_x(false)
+        .x_axis_formatter(move |mark, _, _| {
+            // 只在整数刻度上标时间段
+            let v = mark.value;
+            if v < 0.0 || v.fract() != 0.0 || v as usize >= count {
+                return String::new();
+            }
+            labels[v as usize].clone()
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(code);
+            plot_ui.bar_chart(comment);
+        });
+    Some(plot.response)
+}
+
+/* ---------------- 历史趋势图 ---------------- */
+fn format_timestamp(secs: f64, fmt: &str) -> String {
+    DateTime::from_timestamp(secs as i64, 0)
+        .map(|d| d.with_timezone(&Local).format(fmt).to_string())
+        .unwrap_or_default()
+}
+
+pub fn history_chart(
+    ui: &mut egui::Ui,
+    entries: &[&HistoryEntry],
+    t: &Texts,
+) -> Option<egui::Response> {
+    if entries.is_empty() {
+        ui.weak(t.history_empty);
+        return None;
+    }
+
+    // 横轴为 Unix 时间戳（秒）
+    let series = |value: fn(&HistoryEntry) -> usize| -> Vec<[f64; 2]> {
+        entries
+            .iter()
+            .map(|e| [e.timestamp.timestamp() as f64, value(e) as f64])
+            .collect()
+    };
+    let total = series(|e| e.lines.total);
+    let code = series(|e| e.lines.code);
+
+    let sep = t.thousands_sep;
+    let plot = Plot::new("history_chart")
+        .legend(Legend::default())
+        .x_axis_formatter(|mark, _, _| format_timestamp(mark.value, "%m-%d %H:%M"))
+        .label_formatter(move |name, point| {
+            let time = format_timestamp(point.x, "%Y-%m-%d %H:%M");
+            if name.is_empty() {
+                time
+            } else {
+                let value = format::grouped(point.y as usize, sep);
+                format!("{}\n{}\n{}", name, time, value)
+            }
+        })
+        .show(ui, |plot_ui| {
+            for (name, points, color) in [
+                (t.col_total, total, BLANK_COLOR),
+                (t.col_code, code, CODE_COLOR),
+            ] {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(points.clone()))
+                        .name(name)
+                        .color(color),
+                );
+                plot_ui.points(
+                    Points::new(PlotPoints::from(points))
+                        .name(name)
+                        .color(color)
+                        .radius(3.0),
+                );
+            }
+        });
+    Some(plot.response)
+}
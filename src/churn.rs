@@ -0,0 +1,212 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use egui_plot::{Legend, Plot, PlotPoints, Points};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use crate::charts::{self, CODE_COLOR};
+use crate::format;
+use crate::git;
+use crate::i18n::Texts;
+use crate::stat::{apply_churn, ModuleStat, StatResult};
+
+/* ---------------- 代码变动量 ---------------- */
+// 遍历统计窗口内的提交并逐个比较，只在点击后于后台线程进行；
+// 结果保留到下次计算，重新统计后由 assign 写回新的统计结果
+pub struct ChurnView {
+    days: u32,
+    pending: Option<Receiver<Result<HashMap<PathBuf, usize>, String>>>,
+    churn: Option<HashMap<PathBuf, usize>>,
+    error: Option<String>,
+}
+
+impl Default for ChurnView {
+    fn default() -> Self {
+        Self {
+            days: 90,
+            pending: None,
+            churn: None,
+            error: None,
+        }
+    }
+}
+
+impl ChurnView {
+    pub fn assign(&self, result: &mut StatResult) {
+        if let Some(churn) = &self.churn {
+            apply_churn(result, churn);
+        }
+    }
+
+    fn start(&mut self, root: &Path, ctx: &egui::Context) {
+        let (tx, rx) = mpsc::channel();
+        let (root, days) = (root.to_path_buf(), self.days);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let churn = git::file_churn(&root, days).map_err(|e| e.message().to_owned());
+            let _ = tx.send(churn);
+            ctx.request_repaint();
+        });
+        self.pending = Some(rx);
+        self.error = None;
+    }
+
+    // 收到结果后返回 true，由调用方重新排序文件表
+    fn poll(&mut self, result: &mut StatResult) -> bool {
+        let Some(rx) = &self.pending else {
+            return false;
+        };
+        match rx.try_recv() {
+            Ok(Ok(churn)) => {
+                self.pending = None;
+                self.churn = Some(churn);
+                self.assign(result);
+                return true;
+            }
+            Ok(Err(e)) => {
+                self.pending = None;
+                self.error = Some(e);
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.pending = None,
+        }
+        false
+    }
+
+    // 返回散点图的响应（供右键保存图片）和是否刚换上新结果
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: &Path,
+        result: &mut StatResult,
+        t: &Texts,
+    ) -> (Option<egui::Response>, bool) {
+        let updated = self.poll(result);
+        let running = self.pending.is_some();
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!running, |ui| {
+                ui.label(t.churn_days);
+                ui.add(egui::DragValue::new(&mut self.days).clamp_range(1..=3650));
+                if ui.button(t.churn_run).on_hover_text(t.churn_hint).clicked() {
+                    self.start(root, ui.ctx());
+                }
+            });
+            if running {
+                ui.spinner();
+            }
+        });
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        ui.add_space(4.0);
+        if self.churn.is_none() {
+            if !running {
+                ui.weak(t.churn_empty);
+            }
+            return (None, updated);
+        }
+        let chart = churn_scatter(ui, result, t);
+        ui.add_space(4.0);
+        module_table(ui, &result.modules, t);
+        (Some(chart), updated)
+    }
+}
+
+// 横轴为代码行数，纵轴为变动量；右上角的文件又大又常改
+fn churn_scatter(ui: &mut egui::Ui, result: &StatResult, t: &Texts) -> egui::Response {
+    // 窗口内没有改动的文件不画，免得挤在横轴上
+    let files: Vec<_> = result
+        .files
+        .iter()
+        .filter_map(|f| {
+            let churn = f.churn.filter(|&n| n > 0)?;
+            Some(([f.lines.code as f64, churn as f64], f.rel_path.clone()))
+        })
+        .collect();
+    let points: Vec<[f64; 2]> = files.iter().map(|(p, _)| *p).collect();
+    let sep = t.thousands_sep;
+    let (code, churn, colon) = (t.col_code, t.col_churn, t.colon);
+    Plot::new("churn_scatter")
+        .height(ui.available_height() * 0.6)
+        .legend(Legend::default())
+        .x_axis_label(code)
+        .y_axis_label(churn)
+        .label_formatter(move |_, point| {
+            let name = files
+                .iter()
+                .find(|(p, _)| *p == [point.x, point.y])
+                .map_or("", |(_, name)| name.as_str());
+            format!(
+                "{}\n{}{}{}\n{}{}{}",
+                name,
+                code,
+                colon,
+                format::grouped(point.x.max(0.0) as usize, sep),
+                churn,
+                colon,
+                format::grouped(point.y.max(0.0) as usize, sep)
+            )
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.points(
+                Points::new(PlotPoints::from(points))
+                    .name(t.files_unit)
+                    .color(CODE_COLOR)
+                    .radius(3.0),
+            );
+        })
+        .response
+}
+
+fn module_table(ui: &mut egui::Ui, modules: &[ModuleStat], t: &Texts) {
+    let mut modules: Vec<&ModuleStat> = modules.iter().collect();
+    modules.sort_by_key(|m| std::cmp::Reverse(m.churn.unwrap_or(0)));
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::remainder().at_least(120.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 4)
+        .header(row_height + 6.0, |mut header| {
+            for label in [
+                t.col_module,
+                t.files_unit,
+                t.col_code,
+                t.col_churn,
+                t.churn_ratio,
+            ] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, modules.len(), |mut row| {
+                let m = modules[row.index()];
+                let churn = m.churn.unwrap_or(0);
+                row.col(|ui| {
+                    ui.label(charts::module_name(
+                        m.name.as_deref(),
+                        m.plugin.as_deref(),
+                        t,
+                    ));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(m.files, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(m.lines.code, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(churn, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::percent(churn, m.lines.code));
+                });
+            });
+        });
+}
@@ -0,0 +1,268 @@
+use std::{collections::HashMap, fmt::Write, time::Duration};
+
+use crate::stat::{by_extension, LineCounts, StatResult};
+
+/* ---------------- cloc 兼容输出 ---------------- */
+// 版式与 cloc 相同，现有的 cloc 解析脚本可以直接读取
+const RULE: &str =
+    "-------------------------------------------------------------------------------";
+
+// 与 cloc 使用的语言名一致，未收录的后缀用大写的后缀名代替
+pub fn language(extension: &str) -> String {
+    match extension {
+        "c" => "C",
+        "cc" | "cpp" | "cxx" | "c++" | "inl" | "ipp" => "C++",
+        "h" | "hh" | "hpp" | "hxx" => "C/C++ Header",
+        "cs" => "C#",
+        "py" => "Python",
+        "usf" | "ush" | "hlsl" => "HLSL",
+        "glsl" => "GLSL",
+        "ini" => "INI",
+        "json" => "JSON",
+        "lua" => "Lua",
+        "m" => "Objective-C",
+        "mm" => "Objective-C++",
+        "" => "(none)",
+        other => return other.to_uppercase(),
+    }
+    .to_owned()
+}
+
+pub struct Language {
+    pub name: String,
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+// 多个工程合在一起，按有效代码行数从多到少
+pub fn languages(results: &[&StatResult]) -> Vec<Language> {
+    let mut map: HashMap<String, Language> = HashMap::new();
+    for result in results {
+        for ext in by_extension(&result.files) {
+            let name = language(&ext.extension);
+            let entry = map.entry(name.clone()).or_insert_with(|| Language {
+                name,
+                files: 0,
+                lines: LineCounts::default(),
+            });
+            entry.files += ext.files;
+            entry.lines += ext.lines;
+        }
+    }
+    let mut languages: Vec<Language> = map.into_values().collect();
+    languages.sort_by(|a, b| {
+        b.lines
+            .code
+            .cmp(&a.lines.code)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    languages
+}
+
+struct Header {
+    elapsed: f64,
+    files: usize,
+    lines: usize,
+}
+
+impl Header {
+    fn new(results: &[&StatResult]) -> Self {
+        let elapsed: Duration = results.iter().map(|r| r.elapsed).sum();
+        Self {
+            elapsed: elapsed.as_secs_f64(),
+            files: results.iter().map(|r| r.files.len()).sum(),
+            lines: results.iter().map(|r| r.totals.total).sum(),
+        }
+    }
+
+    fn rate(&self, n: usize) -> f64 {
+        if self.elapsed > 0.0 {
+            n as f64 / self.elapsed
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn text(results: &[&StatResult]) -> String {
+    let header = Header::new(results);
+    let languages = languages(results);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "ueloc v {}  T={:.2} s ({:.1} files/s, {:.1} lines/s)",
+        env!("CARGO_PKG_VERSION"),
+        header.elapsed,
+        header.rate(header.files),
+        header.rate(header.lines)
+    );
+    let _ = writeln!(out, "{}", RULE);
+    let _ = writeln!(
+        out,
+        "{:<25}{:>9}{:>15}{:>15}{:>15}",
+        "Language", "files", "blank", "comment", "code"
+    );
+    let _ = writeln!(out, "{}", RULE);
+    let mut sum = Language {
+        name: "SUM:".to_owned(),
+        files: 0,
+        lines: LineCounts::default(),
+    };
+    for lang in &languages {
+        sum.files += lang.files;
+        sum.lines += lang.lines;
+        let _ = writeln!(
+            out,
+            "{:<25}{:>9}{:>15}{:>15}{:>15}",
+            lang.name, lang.files, lang.lines.blank, lang.lines.comment, lang.lines.code
+        );
+    }
+    // 与 cloc 一样，只有一种语言时不输出合计
+    if languages.len() > 1 {
+        let _ = writeln!(out, "{}", RULE);
+        let _ = writeln!(
+            out,
+            "{:<25}{:>9}{:>15}{:>15}{:>15}",
+            sum.name, sum.files, sum.lines.blank, sum.lines.comment, sum.lines.code
+        );
+    }
+    let _ = writeln!(out, "{}", RULE);
+    out
+}
+
+pub fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn xml(results: &[&StatResult]) -> String {
+    let header = Header::new(results);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?><results>\n");
+    let _ = writeln!(out, "<header>");
+    let _ = writeln!(out, "  <cloc_url>ueloc</cloc_url>");
+    let _ = writeln!(
+        out,
+        "  <cloc_version>{}</cloc_version>",
+        env!("CARGO_PKG_VERSION")
+    );
+    let _ = writeln!(
+        out,
+        "  <elapsed_seconds>{:.3}</elapsed_seconds>",
+        header.elapsed
+    );
+    let _ = writeln!(out, "  <n_files>{}</n_files>", header.files);
+    let _ = writeln!(out, "  <n_lines>{}</n_lines>", header.lines);
+    let _ = writeln!(
+        out,
+        "  <files_per_second>{:.3}</files_per_second>",
+        header.rate(header.files)
+    );
+    let _ = writeln!(
+        out,
+        "  <lines_per_second>{:.3}</lines_per_second>",
+        header.rate(header.lines)
+    );
+    let _ = writeln!(out, "</header>");
+    let _ = writeln!(out, "<languages>");
+    let mut files = 0;
+    let mut sum = LineCounts::default();
+    for lang in languages(results) {
+        files += lang.files;
+        sum += lang.lines;
+        let _ = writeln!(
+            out,
+            "  <language name=\"{}\" files_count=\"{}\" blank=\"{}\" comment=\"{}\" code=\"{}\" />",
+            xml_escape(&lang.name),
+            lang.files,
+            lang.lines.blank,
+            lang.lines.comment,
+            lang.lines.code
+        );
+    }
+    let _ = writeln!(
+        out,
+        "  <total sum_files=\"{}\" blank=\"{}\" comment=\"{}\" code=\"{}\" />",
+        files, sum.blank, sum.comment, sum.code
+    );
+    let _ = writeln!(out, "</languages>");
+    let _ = writeln!(out, "</results>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stat::FileStat;
+    use std::path::PathBuf;
+
+    fn result(files: &[(&str, usize, usize, usize)]) -> StatResult {
+        let mut result = StatResult::default();
+        for &(rel_path, code, comment, blank) in files {
+            let lines = LineCounts {
+                total: code + comment + blank,
+                code,
+                comment,
+                blank,
+                ..Default::default()
+            };
+            result.totals += lines;
+            result.files.push(FileStat {
+                path: PathBuf::from(rel_path),
+                rel_path: rel_path.to_owned(),
+                module: None,
+                plugin: None,
+                lines,
+                details: Default::default(),
+                churn: None,
+                last_commit: None,
+            });
+        }
+        result
+    }
+
+    #[test]
+    fn language_names_follow_cloc() {
+        assert_eq!(language("cpp"), "C++");
+        assert_eq!(language("h"), "C/C++ Header");
+        assert_eq!(language("usf"), "HLSL");
+        assert_eq!(language("uproject"), "UPROJECT");
+        assert_eq!(language(""), "(none)");
+    }
+
+    #[test]
+    fn text_lists_languages_and_sum() {
+        let a = result(&[("A.cpp", 10, 2, 1), ("A.h", 3, 1, 0)]);
+        let b = result(&[("B.cpp", 5, 0, 0)]);
+        let text = text(&[&a, &b]);
+        let rows: Vec<&str> = text
+            .lines()
+            .skip(3)
+            .filter(|l| !l.starts_with('-'))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                format!("{:<25}{:>9}{:>15}{:>15}{:>15}", "C++", 2, 1, 2, 15),
+                format!("{:<25}{:>9}{:>15}{:>15}{:>15}", "C/C++ Header", 1, 0, 1, 3),
+                format!("{:<25}{:>9}{:>15}{:>15}{:>15}", "SUM:", 3, 1, 3, 18),
+            ]
+        );
+        // 只有一种语言时没有合计行
+        assert!(!super::text(&[&b]).contains("SUM:"));
+    }
+
+    #[test]
+    fn xml_totals_and_escaping() {
+        let result = result(&[("A.cpp", 10, 2, 1), ("B.a&b", 1, 0, 0)]);
+        let xml = xml(&[&result]);
+        assert!(xml.contains(
+            r#"<language name="C++" files_count="1" blank="1" comment="2" code="10" />"#
+        ));
+        assert!(xml.contains(r#"<language name="A&amp;B" files_count="1""#));
+        assert!(xml.contains(r#"<total sum_files="2" blank="1" comment="2" code="11" />"#));
+        assert!(xml.contains("<n_files>2</n_files>"));
+        assert!(xml.contains("<n_lines>14</n_lines>"));
+    }
+}
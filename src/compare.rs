@@ -0,0 +1,249 @@
+use crate::charts;
+use crate::diff::module_deltas;
+use crate::file_table::Metric;
+use crate::format;
+use crate::i18n::Texts;
+use crate::project::{dir_display_name, ProjectState};
+use crate::report::{diff_report, DiffFormat};
+use crate::stat::LineCounts;
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use rfd::FileDialog;
+use std::{fs, io};
+
+const GROWTH_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 180, 90);
+
+/* ---------------- 两次统计结果对比 ---------------- */
+// 基准与对比对象都取自已打开的工程标签页
+pub struct CompareView {
+    left: usize,
+    right: usize,
+}
+
+impl Default for CompareView {
+    fn default() -> Self {
+        Self { left: 0, right: 1 }
+    }
+}
+
+pub fn delta_text(
+    metric: Metric,
+    before: &LineCounts,
+    after: &LineCounts,
+    t: &Texts,
+) -> (String, f64) {
+    let delta = metric.value(after) - metric.value(before);
+    let text = match metric {
+        // 注释率的变化按百分点显示
+        Metric::CommentRatio => format!("{:+.1}%", delta * 100.0),
+        _ => format::signed(delta as i64, t),
+    };
+    (text, delta)
+}
+
+// 增加为绿色，减少为红色，不变不着色
+pub fn delta_label(ui: &mut egui::Ui, text: String, delta: f64) {
+    if delta > 0.0 {
+        ui.colored_label(GROWTH_COLOR, text);
+    } else if delta < 0.0 {
+        ui.colored_label(ui.visuals().error_fg_color, text);
+    } else {
+        ui.weak(text);
+    }
+}
+
+fn project_picker(
+    ui: &mut egui::Ui,
+    id: &str,
+    label: &str,
+    selected: &mut usize,
+    projects: &[ProjectState],
+) {
+    ui.label(label);
+    egui::ComboBox::from_id_source(id)
+        .selected_text(projects[*selected].name())
+        .show_ui(ui, |ui| {
+            for (i, p) in projects.iter().enumerate() {
+                ui.selectable_value(selected, i, p.name())
+                    .on_hover_text(p.root_dir.display().to_string());
+            }
+        });
+}
+
+impl CompareView {
+    // 返回导出变化报告时的错误
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        projects: &[ProjectState],
+        t: &Texts,
+    ) -> io::Result<()> {
+        if projects.len() < 2 {
+            return Ok(());
+        }
+        // 关闭标签页后下标可能越界
+        self.left = self.left.min(projects.len() - 1);
+        self.right = self.right.min(projects.len() - 1);
+        let mut exported = None;
+
+        ui.horizontal(|ui| {
+            project_picker(ui, "compare_left", t.compare_base, &mut self.left, projects);
+            ui.add_space(12.0);
+            project_picker(
+                ui,
+                "compare_right",
+                t.compare_target,
+                &mut self.right,
+                projects,
+            );
+            if ui.button("⇄").on_hover_text(t.compare_swap).clicked() {
+                std::mem::swap(&mut self.left, &mut self.right);
+            }
+            ui.add_space(12.0);
+            ui.menu_button(t.export_diff, |ui| {
+                for format in DiffFormat::ALL {
+                    if ui.button(format.name()).clicked() {
+                        ui.close_menu();
+                        exported = Some(format);
+                    }
+                }
+            });
+        });
+        ui.add_space(8.0);
+
+        let (before, after) = (&projects[self.left], &projects[self.right]);
+        ui.columns(2, |cols| {
+            cols[0].push_id("compare_metrics", |ui| metric_table(ui, before, after, t));
+            cols[1].push_id("compare_modules", |ui| module_table(ui, before, after, t));
+        });
+        match exported {
+            Some(format) => export_diff(format, before, after, t),
+            None => Ok(()),
+        }
+    }
+}
+
+fn export_diff(
+    format: DiffFormat,
+    before: &ProjectState,
+    after: &ProjectState,
+    t: &Texts,
+) -> io::Result<()> {
+    let Some(path) = FileDialog::new()
+        .add_filter(format.name(), &[format.extension()])
+        .set_file_name(format!(
+            "{}-diff.{}",
+            dir_display_name(&after.root_dir),
+            format.extension()
+        ))
+        .save_file()
+    else {
+        return Ok(());
+    };
+    let report = diff_report(
+        format,
+        (&before.name(), &before.result),
+        (&after.name(), &after.result),
+        t,
+    );
+    fs::write(path, report)
+}
+
+fn metric_table(ui: &mut egui::Ui, before: &ProjectState, after: &ProjectState, t: &Texts) {
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    let (a, b) = (&before.result, &after.result);
+    TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(100.0))
+        .columns(Column::auto().at_least(70.0), 3)
+        .header(row_height + 6.0, |mut header| {
+            for label in [t.metric, t.compare_base, t.compare_target, t.col_delta] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|mut body| {
+            body.row(row_height, |mut row| {
+                row.col(|ui| {
+                    ui.label(t.files_unit).on_hover_text(t.tip_files);
+                });
+                let (before, after) = (a.files.len(), b.files.len());
+                row.col(|ui| {
+                    ui.label(format::count(before, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(after, t));
+                });
+                row.col(|ui| {
+                    let delta = after as i64 - before as i64;
+                    delta_label(ui, format::signed(delta, t), delta as f64);
+                });
+            });
+            for metric in Metric::ALL {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| {
+                        ui.label(metric.label(t)).on_hover_text(metric.tooltip(t));
+                    });
+                    row.col(|ui| {
+                        ui.label(metric.format(&a.totals, t));
+                    });
+                    row.col(|ui| {
+                        ui.label(metric.format(&b.totals, t));
+                    });
+                    row.col(|ui| {
+                        let (text, delta) = delta_text(metric, &a.totals, &b.totals, t);
+                        delta_label(ui, text, delta);
+                    });
+                });
+            }
+        });
+}
+
+// 按 (模块, 插件) 对齐两边，变化最大的排在前面
+fn module_table(ui: &mut egui::Ui, before: &ProjectState, after: &ProjectState, t: &Texts) {
+    let rows = module_deltas(&before.result.modules, &after.result.modules);
+
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::remainder().at_least(120.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 3)
+        .header(row_height + 6.0, |mut header| {
+            for label in [
+                t.view_modules,
+                t.compare_base,
+                t.compare_target,
+                t.col_delta,
+            ] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, rows.len(), |mut row| {
+                let m = &rows[row.index()];
+                let (a, b) = (&m.before, &m.after);
+                row.col(|ui| {
+                    ui.label(charts::module_name(
+                        m.name.as_deref(),
+                        m.plugin.as_deref(),
+                        t,
+                    ));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(a.code, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(b.code, t));
+                });
+                row.col(|ui| {
+                    let (text, delta) = delta_text(Metric::Code, a, b, t);
+                    delta_label(ui, text, delta);
+                });
+            });
+        });
+}
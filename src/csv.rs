@@ -0,0 +1,289 @@
+use chrono::{DateTime, Local, SecondsFormat};
+use std::{
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::authors::{AgeStat, AuthorStat, ModuleAuthors};
+use crate::cloc;
+use crate::git::TrendPoint;
+use crate::stat::{LineCounts, ScanWarning, StatResult};
+
+/* ---------------- CSV ---------------- */
+// 表头固定为英文，方便脚本处理；列与界面中的表格一致
+const LINE_COLUMNS: &str = "total,code,comment,blank,comment_ratio,includes,reflection";
+// 逐文件导出在行数之后附上文件详情
+const FILE_COLUMNS: &str = "path,language,module,plugin";
+const DETAIL_COLUMNS: &str = "size,longest_line,encoding,modified";
+
+// 含逗号、引号或换行的字段需要用引号包起来
+fn field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn line_fields(lines: &LineCounts) -> String {
+    format!(
+        "{},{},{},{},{:.4},{},{}",
+        lines.total,
+        lines.code,
+        lines.comment,
+        lines.blank,
+        lines.comment_ratio(),
+        lines.includes,
+        lines.reflection
+    )
+}
+
+// 每项指标一行
+pub fn summary_csv(result: &StatResult) -> String {
+    let totals = &result.totals;
+    let mut out = String::from("metric,value\n");
+    for (name, value) in [
+        ("files", result.files.len()),
+        ("total", totals.total),
+        ("code", totals.code),
+        ("comment", totals.comment),
+        ("blank", totals.blank),
+        ("includes", totals.includes),
+        ("reflection", totals.reflection),
+    ] {
+        let _ = writeln!(out, "{},{}", name, value);
+    }
+    let _ = writeln!(out, "comment_ratio,{:.4}", totals.comment_ratio());
+    out
+}
+
+// 多个工程时每行前面加上工程根目录一列
+fn root_prefix(root: Option<&Path>) -> String {
+    root.map(|r| field(&r.display().to_string()) + ",")
+        .unwrap_or_default()
+}
+
+// 每个文件一行
+pub fn files_csv(result: &StatResult) -> String {
+    let mut out = format!("{},{},{}\n", FILE_COLUMNS, LINE_COLUMNS, DETAIL_COLUMNS);
+    write_files(&mut out, None, result);
+    out
+}
+
+pub fn files_csv_multi(results: &[(&Path, &StatResult)]) -> String {
+    let mut out = format!(
+        "root,{},{},{}\n",
+        FILE_COLUMNS, LINE_COLUMNS, DETAIL_COLUMNS
+    );
+    for (root, result) in results {
+        write_files(&mut out, Some(root), result);
+    }
+    out
+}
+
+fn write_files(out: &mut String, root: Option<&Path>, result: &StatResult) {
+    let prefix = root_prefix(root);
+    for file in &result.files {
+        let details = &file.details;
+        let _ = writeln!(
+            out,
+            "{}{},{},{},{},{},{},{},{},{}",
+            prefix,
+            field(&file.rel_path),
+            field(&cloc::language(&file.extension())),
+            field(file.module.as_deref().unwrap_or_default()),
+            field(file.plugin.as_deref().unwrap_or_default()),
+            line_fields(&file.lines),
+            details.size,
+            details.longest_line,
+            details.encoding.name(),
+            details
+                .modified
+                .map(|m| m.to_rfc3339_opts(SecondsFormat::Secs, false))
+                .unwrap_or_default()
+        );
+    }
+}
+
+// 每个模块一行，顺序与模块视图相同
+pub fn modules_csv(result: &StatResult) -> String {
+    let mut out = format!("module,plugin,files,{}\n", LINE_COLUMNS);
+    write_modules(&mut out, None, result);
+    out
+}
+
+pub fn modules_csv_multi(results: &[(&Path, &StatResult)]) -> String {
+    let mut out = format!("root,module,plugin,files,{}\n", LINE_COLUMNS);
+    for (root, result) in results {
+        write_modules(&mut out, Some(root), result);
+    }
+    out
+}
+
+fn write_modules(out: &mut String, root: Option<&Path>, result: &StatResult) {
+    let prefix = root_prefix(root);
+    for module in &result.modules {
+        let _ = writeln!(
+            out,
+            "{}{},{},{},{}",
+            prefix,
+            field(module.name.as_deref().unwrap_or_default()),
+            field(module.plugin.as_deref().unwrap_or_default()),
+            module.files,
+            line_fields(&module.lines)
+        );
+    }
+}
+
+// 多个工程的摘要：每个工程一行，最后一行为合计，root 列为空
+pub fn roots_csv(results: &[(&Path, &StatResult)]) -> String {
+    let mut out = format!("root,files,{}\n", LINE_COLUMNS);
+    let mut files = 0;
+    let mut totals = LineCounts::default();
+    for (root, result) in results {
+        files += result.files.len();
+        totals += result.totals;
+        let _ = writeln!(
+            out,
+            "{}{},{}",
+            root_prefix(Some(root)),
+            result.files.len(),
+            line_fields(&result.totals)
+        );
+    }
+    let _ = writeln!(out, ",{},{}", files, line_fields(&totals));
+    out
+}
+
+// 被跳过或读取有问题的文件，每个一行；counted 表示是否仍计入了统计
+pub fn warnings_csv(root: &Path, warnings: &[ScanWarning]) -> String {
+    let mut out = String::from("reason,path,detail,counted\n");
+    write_warnings(&mut out, root, None, warnings);
+    out
+}
+
+pub fn warnings_csv_multi(results: &[(&Path, &StatResult)]) -> String {
+    let mut out = String::from("root,reason,path,detail,counted\n");
+    for (root, result) in results {
+        write_warnings(&mut out, root, Some(root), &result.warnings);
+    }
+    out
+}
+
+fn write_warnings(out: &mut String, root: &Path, column: Option<&Path>, warnings: &[ScanWarning]) {
+    let prefix = root_prefix(column);
+    for warning in warnings {
+        let rel = warning.path.strip_prefix(root).unwrap_or(&warning.path);
+        let _ = writeln!(
+            out,
+            "{}{},{},{},{}",
+            prefix,
+            warning.kind.name(),
+            field(&rel.display().to_string()),
+            field(&warning.message),
+            !warning.kind.skipped()
+        );
+    }
+}
+
+// 每位作者一行
+pub fn authors_csv(authors: &[AuthorStat]) -> String {
+    let mut out = format!("author,email,files,{}\n", LINE_COLUMNS);
+    for author in authors {
+        let _ = writeln!(
+            out,
+            "{},{},{},{}",
+            field(&author.name),
+            field(&author.email),
+            author.files,
+            line_fields(&author.lines)
+        );
+    }
+    out
+}
+
+// 每个模块的主要作者；share 为该作者占模块有效代码行的比例
+pub fn ownership_csv(modules: &[ModuleAuthors]) -> String {
+    let mut out = String::from("module,plugin,owner,email,share,code\n");
+    for m in modules {
+        let Some((owner, share)) = m.owner() else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{:.4},{}",
+            field(m.name.as_deref().unwrap_or_default()),
+            field(m.plugin.as_deref().unwrap_or_default()),
+            field(&owner.name),
+            field(&owner.email),
+            share,
+            m.code()
+        );
+    }
+    out
+}
+
+// 每个时间段一行；period 为年份或“年 Q季度”
+pub fn ages_csv(ages: &[AgeStat]) -> String {
+    let mut out = format!("period,{}\n", LINE_COLUMNS);
+    for age in ages {
+        let _ = writeln!(out, "{},{}", field(&age.label()), line_fields(&age.lines));
+    }
+    out
+}
+
+/* ---------------- 追加式时间序列 ---------------- */
+// 每次统计追加一行，长期积累后可以直接在表格软件里画增长曲线
+pub fn series_header() -> String {
+    format!("timestamp,revision,files,{}\n", LINE_COLUMNS)
+}
+
+pub fn series_row(
+    at: DateTime<Local>,
+    revision: Option<&str>,
+    files: usize,
+    totals: &LineCounts,
+) -> String {
+    format!(
+        "{},{},{},{}\n",
+        at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        revision.unwrap_or_default(),
+        files,
+        line_fields(totals)
+    )
+}
+
+// 沿提交历史取样的结果，与追加式时间序列的列相同，时间为提交时间
+pub fn trend_csv(points: &[TrendPoint]) -> String {
+    let mut out = series_header();
+    for point in points {
+        out += &series_row(point.time, Some(&point.revision), point.files, &point.lines);
+    }
+    out
+}
+
+// 文件不存在或为空时先写表头
+pub fn append_series(path: &Path, row: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        file.write_all(series_header().as_bytes())?;
+    }
+    file.write_all(row.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_quotes_only_when_needed() {
+        assert_eq!(field("Source/A.cpp"), "Source/A.cpp");
+        assert_eq!(field(""), "");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(field("cr\r"), "\"cr\r\"");
+    }
+}
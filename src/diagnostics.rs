@@ -0,0 +1,79 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::path::Path;
+
+use crate::i18n::Texts;
+use crate::stat::{ScanWarning, WarningKind};
+
+/* ---------------- 诊断信息 ---------------- */
+const KINDS: [WarningKind; 5] = [
+    WarningKind::Walk,
+    WarningKind::Read,
+    WarningKind::Encoding,
+    WarningKind::TooLarge,
+    WarningKind::Binary,
+];
+
+pub fn kind_label(kind: WarningKind, t: &Texts) -> &'static str {
+    match kind {
+        WarningKind::Walk => t.warn_walk,
+        WarningKind::Read => t.warn_read,
+        WarningKind::Encoding => t.warn_encoding,
+        WarningKind::TooLarge => t.warn_too_large,
+        WarningKind::Binary => t.warn_binary,
+    }
+}
+
+// 列出扫描中被跳过或有问题的文件及原因
+pub fn show_diagnostics(ui: &mut egui::Ui, root: &Path, warnings: &[ScanWarning], t: &Texts) {
+    if warnings.is_empty() {
+        ui.weak(t.diagnostics_empty);
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for kind in KINDS {
+            let count = warnings.iter().filter(|w| w.kind == kind).count();
+            if count > 0 {
+                ui.label(format!("{}{}{}", kind_label(kind, t), t.colon, count));
+                ui.separator();
+            }
+        }
+    });
+    ui.add_space(4.0);
+
+    let mut rows: Vec<&ScanWarning> = warnings.iter().collect();
+    rows.sort_by_key(|w| KINDS.iter().position(|&k| k == w.kind));
+
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(100.0))
+        .column(Column::remainder().at_least(200.0).clip(true))
+        .column(Column::auto().at_least(120.0))
+        .header(row_height + 6.0, |mut header| {
+            for label in [t.col_reason, t.col_path, t.col_detail] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, rows.len(), |mut row| {
+                let w = rows[row.index()];
+                row.col(|ui| {
+                    ui.label(kind_label(w.kind, t));
+                });
+                row.col(|ui| {
+                    let rel = w.path.strip_prefix(root).unwrap_or(&w.path);
+                    ui.label(rel.display().to_string())
+                        .on_hover_text(w.path.display().to_string());
+                });
+                row.col(|ui| {
+                    ui.label(&w.message).on_hover_text(&w.message);
+                });
+            });
+        });
+}
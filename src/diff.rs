@@ -0,0 +1,335 @@
+use serde::Serialize;
+use std::{cmp::Reverse, collections::BTreeMap, fmt::Write, ops::AddAssign};
+
+use crate::json::Baseline;
+use crate::stat::{FileStat, LineCounts, ModuleStat, StatResult};
+
+/* ---------------- 两次统计的差异 ---------------- */
+// 各项行数的变化量，新减旧
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct LineDelta {
+    pub total: i64,
+    pub code: i64,
+    pub comment: i64,
+    pub blank: i64,
+    pub includes: i64,
+    pub reflection: i64,
+}
+
+impl LineDelta {
+    pub fn between(before: &LineCounts, after: &LineCounts) -> Self {
+        let d = |a: usize, b: usize| b as i64 - a as i64;
+        Self {
+            total: d(before.total, after.total),
+            code: d(before.code, after.code),
+            comment: d(before.comment, after.comment),
+            blank: d(before.blank, after.blank),
+            includes: d(before.includes, after.includes),
+            reflection: d(before.reflection, after.reflection),
+        }
+    }
+}
+
+impl AddAssign for LineDelta {
+    fn add_assign(&mut self, other: Self) {
+        self.total += other.total;
+        self.code += other.code;
+        self.comment += other.comment;
+        self.blank += other.blank;
+        self.includes += other.includes;
+        self.reflection += other.reflection;
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct Totals {
+    pub file_count: usize,
+    pub lines: LineCounts,
+}
+
+impl Totals {
+    pub fn of(result: &StatResult) -> Self {
+        Self {
+            file_count: result.files.len(),
+            lines: result.totals,
+        }
+    }
+}
+
+// 只在一边出现的模块，另一边按全 0 处理
+#[derive(Serialize)]
+pub struct ModuleDelta {
+    pub name: Option<String>,
+    pub plugin: Option<String>,
+    pub before: LineCounts,
+    pub after: LineCounts,
+    pub delta: LineDelta,
+}
+
+#[derive(Serialize)]
+pub struct Diff {
+    pub before: Totals,
+    pub after: Totals,
+    pub file_delta: i64,
+    pub delta: LineDelta,
+    // 注释率的变化，按比例而非百分点
+    pub comment_ratio_delta: f64,
+    pub modules: Vec<ModuleDelta>,
+}
+
+// 按 (模块, 插件) 对齐两边，有效代码变化最大的排在前面
+pub fn module_deltas(before: &[ModuleStat], after: &[ModuleStat]) -> Vec<ModuleDelta> {
+    type Key = (Option<String>, Option<String>);
+    let mut rows: BTreeMap<Key, (LineCounts, LineCounts)> = BTreeMap::new();
+    for m in before {
+        rows.entry((m.name.clone(), m.plugin.clone()))
+            .or_default()
+            .0 = m.lines;
+    }
+    for m in after {
+        rows.entry((m.name.clone(), m.plugin.clone()))
+            .or_default()
+            .1 = m.lines;
+    }
+    let mut deltas: Vec<ModuleDelta> = rows
+        .into_iter()
+        .map(|((name, plugin), (before, after))| ModuleDelta {
+            name,
+            plugin,
+            before,
+            after,
+            delta: LineDelta::between(&before, &after),
+        })
+        .collect();
+    deltas.sort_by_key(|m| Reverse(m.delta.code.abs()));
+    deltas
+}
+
+impl Diff {
+    pub fn new(
+        before: Totals,
+        before_modules: &[ModuleStat],
+        after: Totals,
+        after_modules: &[ModuleStat],
+    ) -> Self {
+        Self {
+            before,
+            after,
+            file_delta: after.file_count as i64 - before.file_count as i64,
+            delta: LineDelta::between(&before.lines, &after.lines),
+            comment_ratio_delta: after.lines.comment_ratio() - before.lines.comment_ratio(),
+            modules: module_deltas(before_modules, after_modules),
+        }
+    }
+
+    pub fn from_baseline(baseline: &Baseline, after: &StatResult) -> Self {
+        let before = Totals {
+            file_count: baseline.file_count,
+            lines: baseline.totals,
+        };
+        Self::new(before, &baseline.modules, Totals::of(after), &after.modules)
+    }
+
+    pub fn between(before: &StatResult, after: &StatResult) -> Self {
+        Self::new(
+            Totals::of(before),
+            &before.modules,
+            Totals::of(after),
+            &after.modules,
+        )
+    }
+}
+
+/* ---------------- 逐文件差异 ---------------- */
+// 按相对路径对齐两边的文件；只在一边出现的文件是新增或删除
+#[derive(Serialize)]
+pub struct FileDelta {
+    pub rel_path: String,
+    pub module: Option<String>,
+    pub plugin: Option<String>,
+    pub before: Option<LineCounts>,
+    pub after: Option<LineCounts>,
+    pub delta: LineDelta,
+}
+
+// 行数没有变化的文件不列出，按有效代码变化从大到小
+pub fn file_deltas(before: &[FileStat], after: &[FileStat]) -> Vec<FileDelta> {
+    let mut rows: BTreeMap<&str, (Option<&FileStat>, Option<&FileStat>)> = BTreeMap::new();
+    for f in before {
+        rows.entry(&f.rel_path).or_default().0 = Some(f);
+    }
+    for f in after {
+        rows.entry(&f.rel_path).or_default().1 = Some(f);
+    }
+    let mut deltas: Vec<FileDelta> = rows
+        .into_iter()
+        .filter_map(|(rel_path, (a, b))| {
+            let lines = |f: Option<&FileStat>| f.map(|f| f.lines).unwrap_or_default();
+            let delta = LineDelta::between(&lines(a), &lines(b));
+            let unchanged = a.is_some() && b.is_some() && delta.total == 0 && delta.code == 0;
+            if unchanged {
+                return None;
+            }
+            let side = b.or(a)?;
+            Some(FileDelta {
+                rel_path: rel_path.to_owned(),
+                module: side.module.clone(),
+                plugin: side.plugin.clone(),
+                before: a.map(|f| f.lines),
+                after: b.map(|f| f.lines),
+                delta,
+            })
+        })
+        .collect();
+    deltas.sort_by_key(|f| Reverse(f.delta.code.abs()));
+    deltas
+}
+
+// 模块内有效代码的增减，按文件分别计：增加的文件记入 added，减少的记入 removed
+#[derive(Serialize)]
+pub struct ModuleChurn {
+    pub name: Option<String>,
+    pub plugin: Option<String>,
+    pub added: usize,
+    pub removed: usize,
+    pub new_files: usize,
+    pub deleted_files: usize,
+}
+
+pub fn module_churn(files: &[FileDelta]) -> Vec<ModuleChurn> {
+    type Key<'a> = (Option<&'a str>, Option<&'a str>);
+    let mut rows: BTreeMap<Key, ModuleChurn> = BTreeMap::new();
+    for f in files {
+        let key = (f.module.as_deref(), f.plugin.as_deref());
+        let row = rows.entry(key).or_insert_with(|| ModuleChurn {
+            name: f.module.clone(),
+            plugin: f.plugin.clone(),
+            added: 0,
+            removed: 0,
+            new_files: 0,
+            deleted_files: 0,
+        });
+        if f.delta.code > 0 {
+            row.added += f.delta.code as usize;
+        } else {
+            row.removed += f.delta.code.unsigned_abs() as usize;
+        }
+        row.new_files += usize::from(f.before.is_none());
+        row.deleted_files += usize::from(f.after.is_none());
+    }
+    let mut churn: Vec<ModuleChurn> = rows.into_values().collect();
+    churn.sort_by_key(|m| Reverse(m.added + m.removed));
+    churn
+}
+
+// 按模块汇总有变化的文件，有效代码变化最大的排在前面
+#[derive(Serialize)]
+pub struct ModuleChange {
+    pub name: Option<String>,
+    pub plugin: Option<String>,
+    // 有变化的文件数，含新增和删除的
+    pub files: usize,
+    // 有效代码的增减，按文件分别计，与 ModuleChurn 相同
+    pub added: usize,
+    pub removed: usize,
+    pub delta: LineDelta,
+}
+
+pub fn module_changes(files: &[FileDelta]) -> Vec<ModuleChange> {
+    type Key<'a> = (Option<&'a str>, Option<&'a str>);
+    let mut rows: BTreeMap<Key, ModuleChange> = BTreeMap::new();
+    for f in files {
+        let key = (f.module.as_deref(), f.plugin.as_deref());
+        let row = rows.entry(key).or_insert_with(|| ModuleChange {
+            name: f.module.clone(),
+            plugin: f.plugin.clone(),
+            files: 0,
+            added: 0,
+            removed: 0,
+            delta: LineDelta::default(),
+        });
+        row.files += 1;
+        if f.delta.code > 0 {
+            row.added += f.delta.code as usize;
+        } else {
+            row.removed += f.delta.code.unsigned_abs() as usize;
+        }
+        row.delta += f.delta;
+    }
+    let mut changes: Vec<ModuleChange> = rows.into_values().collect();
+    changes.sort_by_key(|m| Reverse(m.delta.code.abs()));
+    changes
+}
+
+// 供发布说明使用的 Markdown 表格，末行为合计
+pub fn changes_markdown(changes: &[ModuleChange]) -> String {
+    let mut out = String::from(
+        "| Module | Plugin | Files | Added | Removed | Net |\n\
+         |---|---|---:|---:|---:|---:|\n",
+    );
+    let cell = |s: Option<&str>| s.unwrap_or_default().replace('|', "\\|");
+    let (mut files, mut added, mut removed) = (0, 0, 0);
+    for m in changes {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | +{} | -{} | {:+} |",
+            cell(m.name.as_deref()),
+            cell(m.plugin.as_deref()),
+            m.files,
+            m.added,
+            m.removed,
+            m.delta.code
+        );
+        files += m.files;
+        added += m.added;
+        removed += m.removed;
+    }
+    let _ = writeln!(
+        out,
+        "| **Total** | | {} | +{} | -{} | {:+} |",
+        files,
+        added,
+        removed,
+        added as i64 - removed as i64
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(name: &str, code: usize) -> ModuleStat {
+        let lines = LineCounts {
+            total: code,
+            code,
+            ..Default::default()
+        };
+        ModuleStat {
+            name: Some(name.to_owned()),
+            plugin: None,
+            files: 1,
+            lines,
+            churn: None,
+        }
+    }
+
+    #[test]
+    fn module_deltas_align_by_module_and_sort_by_change() {
+        let before = [module("Core", 10), module("Old", 5)];
+        let after = [module("Core", 4), module("New", 20)];
+        let deltas = module_deltas(&before, &after);
+        let rows: Vec<(Option<&str>, usize, usize, i64)> = deltas
+            .iter()
+            .map(|m| (m.name.as_deref(), m.before.code, m.after.code, m.delta.code))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (Some("New"), 0, 20, 20),
+                (Some("Core"), 10, 4, -6),
+                (Some("Old"), 5, 0, -5)
+            ]
+        );
+    }
+}
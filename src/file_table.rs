@@ -0,0 +1,555 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::format;
+use crate::heatmap::Heatmap;
+use crate::i18n::Texts;
+use crate::stat::{FileStat, LineCounts};
+
+/* ---------------- 逐文件结果表 ---------------- */
+// 可以在列选择菜单里开关的统计列
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Metric {
+    Total,
+    Code,
+    Comment,
+    Blank,
+    CommentRatio,
+    Includes,
+    Reflection,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 7] = [
+        Metric::Total,
+        Metric::Code,
+        Metric::Comment,
+        Metric::Blank,
+        Metric::CommentRatio,
+        Metric::Includes,
+        Metric::Reflection,
+    ];
+
+    // 默认显示的列
+    pub const DEFAULT: [Metric; 4] = [Metric::Total, Metric::Code, Metric::Comment, Metric::Blank];
+
+    pub fn label(self, t: &Texts) -> &'static str {
+        match self {
+            Metric::Total => t.col_total,
+            Metric::Code => t.col_code,
+            Metric::Comment => t.col_comment,
+            Metric::Blank => t.col_blank,
+            Metric::CommentRatio => t.col_comment_ratio,
+            Metric::Includes => t.col_includes,
+            Metric::Reflection => t.col_reflection,
+        }
+    }
+
+    // 悬停时显示的统计口径说明
+    pub fn tooltip(self, t: &Texts) -> &'static str {
+        match self {
+            Metric::Total => t.tip_total,
+            Metric::Code => t.tip_code,
+            Metric::Comment => t.tip_comment,
+            Metric::Blank => t.tip_blank,
+            Metric::CommentRatio => t.tip_comment_ratio,
+            Metric::Includes => t.tip_includes,
+            Metric::Reflection => t.tip_reflection,
+        }
+    }
+
+    pub fn value(self, lines: &LineCounts) -> f64 {
+        match self {
+            Metric::Total => lines.total as f64,
+            Metric::Code => lines.code as f64,
+            Metric::Comment => lines.comment as f64,
+            Metric::Blank => lines.blank as f64,
+            Metric::CommentRatio => lines.comment_ratio(),
+            Metric::Includes => lines.includes as f64,
+            Metric::Reflection => lines.reflection as f64,
+        }
+    }
+
+    pub fn format(self, lines: &LineCounts, t: &Texts) -> String {
+        match self {
+            Metric::CommentRatio => format!("{:.1}%", lines.comment_ratio() * 100.0),
+            _ => format::count(self.value(lines) as usize, t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Path,
+    Metric(Metric),
+    // 算过变动量后才有这一列
+    Churn,
+    // 查到最后一次提交后才有这两列
+    LastCommitTime,
+    LastCommitAuthor,
+}
+
+// 表格里触发的、需要由上层处理的操作
+pub enum FileAction {
+    OpenInEditor(PathBuf),
+    Reveal(PathBuf),
+}
+
+/* ---------------- 路径过滤 ---------------- */
+// 含通配符时按 glob 匹配，否则按子串匹配，均不区分大小写
+enum PathFilter {
+    All,
+    Substring(String),
+    Glob(GlobMatcher),
+    Invalid,
+}
+
+impl PathFilter {
+    fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text.is_empty() {
+            PathFilter::All
+        } else if text.contains(['*', '?', '[', '{']) {
+            GlobBuilder::new(text)
+                .case_insensitive(true)
+                .build()
+                .map(|g| PathFilter::Glob(g.compile_matcher()))
+                .unwrap_or(PathFilter::Invalid)
+        } else {
+            PathFilter::Substring(text.to_lowercase().replace('\\', "/"))
+        }
+    }
+
+    fn matches(&self, rel_path: &str) -> bool {
+        // 统一成正斜杠，Windows 下的路径也能用同样的写法过滤
+        let path = rel_path.replace('\\', "/");
+        match self {
+            PathFilter::All => true,
+            PathFilter::Substring(s) => path.to_lowercase().contains(s.as_str()),
+            PathFilter::Glob(g) => g.is_match(&path),
+            PathFilter::Invalid => false,
+        }
+    }
+}
+
+pub struct FileTable {
+    sort: SortColumn,
+    ascending: bool,
+    filter_text: String,
+    // 当前过滤后可见行在 files 中的下标，以及这些行的合计
+    visible: Vec<usize>,
+    visible_totals: LineCounts,
+    filter_invalid: bool,
+    // 有文件查到了最后一次提交，显示对应的两列
+    has_last_commit: bool,
+    // 排序、过滤条件或数据变化后需要重新计算可见行
+    dirty: bool,
+    // 当前选中的文件，以及下一帧需要滚动到的文件
+    selected: Option<PathBuf>,
+    scroll_to: Option<PathBuf>,
+    // 下一帧让过滤框获得焦点
+    focus_filter: bool,
+    // 详情弹窗显示的文件
+    details: Option<PathBuf>,
+}
+
+impl Default for FileTable {
+    // 默认按有效代码行数从多到少，最先看到大文件
+    fn default() -> Self {
+        Self {
+            sort: SortColumn::Metric(Metric::Code),
+            ascending: false,
+            filter_text: String::new(),
+            visible: Vec::new(),
+            visible_totals: LineCounts::default(),
+            filter_invalid: false,
+            has_last_commit: false,
+            dirty: true,
+            selected: None,
+            scroll_to: None,
+            focus_filter: false,
+            details: None,
+        }
+    }
+}
+
+impl FileTable {
+    pub fn sort(&mut self, files: &mut [FileStat]) {
+        files.sort_by(|a, b| {
+            let ord = match self.sort {
+                SortColumn::Path => a.rel_path.cmp(&b.rel_path),
+                SortColumn::Metric(m) => m.value(&a.lines).total_cmp(&m.value(&b.lines)),
+                SortColumn::Churn => a.churn.unwrap_or(0).cmp(&b.churn.unwrap_or(0)),
+                // 没有提交的文件排在最旧的之前
+                SortColumn::LastCommitTime => {
+                    let time = |f: &FileStat| f.last_commit.as_ref().map(|c| c.time);
+                    time(a).cmp(&time(b))
+                }
+                SortColumn::LastCommitAuthor => {
+                    let author =
+                        |f: &FileStat| f.last_commit.as_ref().map(|c| c.author.to_lowercase());
+                    author(a).cmp(&author(b))
+                }
+            };
+            if self.ascending {
+                ord
+            } else {
+                ord.reverse()
+            }
+        });
+        self.dirty = true;
+    }
+
+    // 选中并滚动到指定文件；被过滤条件挡住时先清空过滤
+    pub fn reveal(&mut self, path: &Path, files: &[FileStat]) {
+        let filter = PathFilter::parse(&self.filter_text);
+        let hidden = files
+            .iter()
+            .find(|f| f.path == path)
+            .is_some_and(|f| !filter.matches(&f.rel_path));
+        if hidden {
+            self.filter_text.clear();
+            self.dirty = true;
+        }
+        self.selected = Some(path.to_path_buf());
+        self.scroll_to = Some(path.to_path_buf());
+    }
+
+    pub fn selected(&self) -> Option<&Path> {
+        self.selected.as_deref()
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected = None;
+    }
+
+    fn show_details(&mut self, ctx: &egui::Context, files: &[FileStat], t: &Texts) {
+        let Some(file) = self
+            .details
+            .as_ref()
+            .and_then(|path| files.iter().find(|f| &f.path == path))
+        else {
+            self.details = None;
+            return;
+        };
+
+        let mut open = true;
+        let title = Path::new(&file.rel_path).file_name().map_or_else(
+            || file.rel_path.clone(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        egui::Window::new(title)
+            .id(egui::Id::new("file_details"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| file_details(ui, file, t));
+        if !open {
+            self.details = None;
+        }
+    }
+
+    pub fn focus_filter(&mut self) {
+        self.focus_filter = true;
+    }
+
+    fn refresh_visible(&mut self, files: &[FileStat]) {
+        let filter = PathFilter::parse(&self.filter_text);
+        self.filter_invalid = matches!(filter, PathFilter::Invalid);
+        self.visible.clear();
+        self.visible_totals = LineCounts::default();
+        self.has_last_commit = files.iter().any(|f| f.last_commit.is_some());
+        for (i, file) in files.iter().enumerate() {
+            if filter.matches(&file.rel_path) {
+                self.visible.push(i);
+                self.visible_totals += file.lines;
+            }
+        }
+        self.dirty = false;
+    }
+
+    fn header_button(&mut self, ui: &mut egui::Ui, column: SortColumn, t: &Texts) -> bool {
+        let (label, hint) = match column {
+            SortColumn::Path => (t.col_path, t.tip_path),
+            SortColumn::Metric(m) => (m.label(t), m.tooltip(t)),
+            SortColumn::Churn => (t.col_churn, t.tip_churn),
+            SortColumn::LastCommitTime => (t.col_last_commit, t.tip_last_commit),
+            SortColumn::LastCommitAuthor => (t.col_last_author, t.tip_last_author),
+        };
+        let text = if self.sort == column {
+            format!("{} {}", label, if self.ascending { "⏶" } else { "⏷" })
+        } else {
+            label.to_owned()
+        };
+
+        if !ui.button(text).on_hover_text(hint).clicked() {
+            return false;
+        }
+
+        if self.sort == column {
+            self.ascending = !self.ascending;
+        } else {
+            // 路径和作者默认升序，数字和日期列默认降序
+            self.sort = column;
+            self.ascending = matches!(column, SortColumn::Path | SortColumn::LastCommitAuthor);
+        }
+        true
+    }
+
+    fn show_filter_bar(
+        &mut self,
+        ui: &mut egui::Ui,
+        files: &[FileStat],
+        columns: &mut Vec<Metric>,
+        t: &Texts,
+    ) {
+        ui.horizontal(|ui| {
+            column_menu(ui, columns, t);
+            ui.separator();
+            ui.label(t.filter);
+            let edit = egui::TextEdit::singleline(&mut self.filter_text)
+                .hint_text(t.filter_hint)
+                .desired_width(300.0);
+            let response = ui.add(edit);
+            if std::mem::take(&mut self.focus_filter) {
+                response.request_focus();
+            }
+            if response.changed() {
+                self.dirty = true;
+            }
+            if !self.filter_text.is_empty() && ui.small_button("✖").clicked() {
+                self.filter_text.clear();
+                self.dirty = true;
+            }
+            if self.dirty {
+                self.refresh_visible(files);
+            }
+
+            if self.filter_invalid {
+                ui.colored_label(ui.visuals().error_fg_color, t.filter_invalid);
+            } else {
+                ui.label(format!(
+                    "{} {}    {} {}    {} {}",
+                    t.files_unit,
+                    format::count(self.visible.len(), t),
+                    t.col_total,
+                    format::count(self.visible_totals.total, t),
+                    t.col_code,
+                    format::count(self.visible_totals.code, t)
+                ));
+            }
+        });
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        files: &mut [FileStat],
+        columns: &mut Vec<Metric>,
+        heatmap: &Heatmap,
+        t: &Texts,
+    ) -> Option<FileAction> {
+        self.show_filter_bar(ui, files, columns, t);
+
+        let mut resort = false;
+        let mut action = None;
+        let mut clicked = None;
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        let show_churn = files.first().is_some_and(|f| f.churn.is_some());
+
+        // 列宽可拖动调整，由 egui 的持久化内存记住
+        let mut builder = TableBuilder::new(ui);
+        if let Some(target) = self.scroll_to.take() {
+            if let Some(row) = self.visible.iter().position(|&i| files[i].path == target) {
+                builder = builder.scroll_to_row(row, Some(egui::Align::Center));
+            }
+        }
+        builder
+            .striped(true)
+            .resizable(true)
+            .sense(egui::Sense::click())
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::remainder().at_least(200.0).clip(true))
+            .columns(
+                Column::auto().at_least(70.0),
+                columns.len() + usize::from(show_churn) + 2 * usize::from(self.has_last_commit),
+            )
+            .header(row_height + 6.0, |mut header| {
+                header.col(|ui| {
+                    resort |= self.header_button(ui, SortColumn::Path, t);
+                });
+                for &metric in columns.iter() {
+                    header.col(|ui| {
+                        resort |= self.header_button(ui, SortColumn::Metric(metric), t);
+                    });
+                }
+                if show_churn {
+                    header.col(|ui| {
+                        resort |= self.header_button(ui, SortColumn::Churn, t);
+                    });
+                }
+                if self.has_last_commit {
+                    for column in [SortColumn::LastCommitTime, SortColumn::LastCommitAuthor] {
+                        header.col(|ui| {
+                            resort |= self.header_button(ui, column, t);
+                        });
+                    }
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, self.visible.len(), |mut row| {
+                    let file = &files[self.visible[row.index()]];
+                    row.set_selected(self.selected.as_ref() == Some(&file.path));
+                    // 单元格文字不可选中，点击交给整行处理
+                    row.col(|ui| {
+                        heatmap.paint_cell(ui, &file.lines);
+                        ui.add(egui::Label::new(&file.rel_path).selectable(false))
+                            .on_hover_text(file.path.display().to_string());
+                    });
+                    for metric in columns.iter() {
+                        row.col(|ui| {
+                            heatmap.paint_cell(ui, &file.lines);
+                            let text = metric.format(&file.lines, t);
+                            ui.add(egui::Label::new(text).selectable(false));
+                        });
+                    }
+                    if show_churn {
+                        row.col(|ui| {
+                            let text = format::count(file.churn.unwrap_or(0), t);
+                            ui.add(egui::Label::new(text).selectable(false));
+                        });
+                    }
+                    if self.has_last_commit {
+                        let commit = file.last_commit.as_ref();
+                        row.col(|ui| {
+                            let text = commit
+                                .map_or(String::new(), |c| c.time.format("%Y-%m-%d").to_string());
+                            ui.add(egui::Label::new(text).selectable(false));
+                        });
+                        row.col(|ui| {
+                            let text = commit.map_or("", |c| c.author.as_str());
+                            ui.add(egui::Label::new(text).selectable(false));
+                        });
+                    }
+
+                    let response = row.response();
+                    if response.clicked() {
+                        clicked = Some(file.path.clone());
+                    }
+                    if response.double_clicked() {
+                        action = Some(FileAction::OpenInEditor(file.path.clone()));
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button(t.open_in_editor).clicked() {
+                            action = Some(FileAction::OpenInEditor(file.path.clone()));
+                            ui.close_menu();
+                        }
+                        if ui.button(t.reveal_in_file_manager).clicked() {
+                            action = Some(FileAction::Reveal(file.path.clone()));
+                            ui.close_menu();
+                        }
+                        if ui.button(t.file_details).clicked() {
+                            clicked = Some(file.path.clone());
+                            ui.close_menu();
+                        }
+                        if ui.button(t.copy_path).clicked() {
+                            let path = file.path.display().to_string();
+                            ui.output_mut(|o| o.copied_text = path);
+                            ui.close_menu();
+                        }
+                    });
+                });
+            });
+
+        if let Some(path) = clicked {
+            self.details = Some(path.clone());
+            self.selected = Some(path);
+        }
+        self.show_details(ui.ctx(), files, t);
+        if resort {
+            self.sort(files);
+        }
+        action
+    }
+}
+
+// 勾选要显示的列，顺序始终与 Metric::ALL 一致
+fn column_menu(ui: &mut egui::Ui, columns: &mut Vec<Metric>, t: &Texts) {
+    ui.menu_button(t.columns, |ui| {
+        for metric in Metric::ALL {
+            let mut shown = columns.contains(&metric);
+            if ui.checkbox(&mut shown, metric.label(t)).changed() {
+                *columns = Metric::ALL
+                    .into_iter()
+                    .filter(|&m| {
+                        if m == metric {
+                            shown
+                        } else {
+                            columns.contains(&m)
+                        }
+                    })
+                    .collect();
+            }
+        }
+    });
+}
+
+fn file_details(ui: &mut egui::Ui, file: &FileStat, t: &Texts) {
+    let lines = &file.lines;
+    let details = &file.details;
+    ui.label(&file.rel_path)
+        .on_hover_text(file.path.display().to_string());
+    ui.separator();
+    egui::Grid::new("file_details_grid")
+        .num_columns(2)
+        .spacing([16.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            let mut row = |label: &str, value: String, hint: Option<&str>| {
+                let response = ui.label(label);
+                if let Some(hint) = hint {
+                    response.on_hover_text(hint);
+                }
+                ui.label(value);
+                ui.end_row();
+            };
+            row(
+                t.col_module,
+                file.module
+                    .clone()
+                    .unwrap_or_else(|| t.no_module.to_owned()),
+                None,
+            );
+            if let Some(plugin) = &file.plugin {
+                row(t.col_plugin, plugin.clone(), None);
+            }
+            for metric in Metric::ALL {
+                let mut value = metric.format(lines, t);
+                if matches!(metric, Metric::Code | Metric::Comment | Metric::Blank) {
+                    let share = format::percent(metric.value(lines) as usize, lines.total);
+                    value = format!("{}    {}", value, share);
+                }
+                row(metric.label(t), value, Some(metric.tooltip(t)));
+            }
+            row(t.longest_line, format::count(details.longest_line, t), None);
+            row(t.encoding, details.encoding.name().to_owned(), None);
+            row(
+                t.file_size,
+                format!(
+                    "{} KB",
+                    format::count(details.size.div_ceil(1024) as usize, t)
+                ),
+                None,
+            );
+            if let Some(modified) = details.modified {
+                row(
+                    t.last_modified,
+                    modified.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    None,
+                );
+            }
+        });
+}
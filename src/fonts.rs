@@ -0,0 +1,143 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::i18n::Texts;
+
+const FONT_NAME: &str = "cjk";
+
+// 常见系统自带的中文字体，按顺序尝试；PDF 导出也从中选字体
+pub const SYSTEM_CJK_FONTS: &[&str] = &[
+    // Windows
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\msyh.ttf",
+    "C:\\Windows\\Fonts\\simhei.ttf",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+    // macOS
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/Library/Fonts/Arial Unicode.ttf",
+    // Linux
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+];
+
+/* ---------------- 字体来源 ---------------- */
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FontSource {
+    // 优先系统字体，找不到再用内置字体
+    #[default]
+    Auto,
+    Embedded,
+    Custom,
+}
+
+impl FontSource {
+    pub fn all() -> Vec<FontSource> {
+        let mut all = vec![FontSource::Auto];
+        if cfg!(feature = "embedded-font") {
+            all.push(FontSource::Embedded);
+        }
+        all.push(FontSource::Custom);
+        all
+    }
+
+    pub fn label(self, t: &Texts) -> &'static str {
+        match self {
+            FontSource::Auto => t.font_auto,
+            FontSource::Embedded => t.font_embedded,
+            FontSource::Custom => t.font_custom,
+        }
+    }
+}
+
+// 实际生效的字体，用于在设置里提示
+#[derive(Clone, PartialEq)]
+pub enum LoadedFont {
+    File(PathBuf),
+    Embedded,
+    // 没有可用的中文字体，只有 egui 自带字体
+    Builtin,
+}
+
+impl LoadedFont {
+    pub fn describe(&self, t: &Texts) -> String {
+        match self {
+            LoadedFont::File(path) => path.display().to_string(),
+            LoadedFont::Embedded => t.font_embedded.to_owned(),
+            LoadedFont::Builtin => t.font_builtin.to_owned(),
+        }
+    }
+}
+
+/* ---------------- 字体加载 ---------------- */
+// 界面和 PDF 导出共用同一份数据
+#[cfg(feature = "embedded-font")]
+pub static EMBEDDED_FONT: Option<&[u8]> = Some(include_bytes!("../fonts/LXGWWenKai-Regular.ttf"));
+
+#[cfg(not(feature = "embedded-font"))]
+pub static EMBEDDED_FONT: Option<&[u8]> = None;
+
+fn embedded_font() -> Option<egui::FontData> {
+    EMBEDDED_FONT.map(egui::FontData::from_static)
+}
+
+// ttc 字体集合取第一个字体即可
+fn load_file(path: &Path) -> Option<egui::FontData> {
+    fs::read(path).ok().map(egui::FontData::from_owned)
+}
+
+fn find_system_font() -> Option<(PathBuf, egui::FontData)> {
+    SYSTEM_CJK_FONTS.iter().map(PathBuf::from).find_map(|path| {
+        let data = load_file(&path)?;
+        Some((path, data))
+    })
+}
+
+fn pick_font(source: FontSource, custom: &Path) -> Option<(LoadedFont, egui::FontData)> {
+    let custom_font = || {
+        let data = load_file(custom)?;
+        Some((LoadedFont::File(custom.to_path_buf()), data))
+    };
+    let system_font = || find_system_font().map(|(p, d)| (LoadedFont::File(p), d));
+    let embedded = || embedded_font().map(|d| (LoadedFont::Embedded, d));
+
+    // 指定的来源不可用时依次回退
+    match source {
+        FontSource::Auto => system_font().or_else(embedded),
+        FontSource::Embedded => embedded().or_else(system_font),
+        FontSource::Custom => custom_font().or_else(system_font).or_else(embedded),
+    }
+}
+
+pub fn init_fonts(ctx: &egui::Context, source: FontSource, custom: &Path) -> LoadedFont {
+    let mut fonts = egui::FontDefinitions::default();
+
+    let Some((loaded, data)) = pick_font(source, custom) else {
+        ctx.set_fonts(fonts);
+        return LoadedFont::Builtin;
+    };
+
+    fonts.font_data.insert(FONT_NAME.to_owned(), data);
+
+    fonts
+        .families
+        .get_mut(&egui::FontFamily::Proportional)
+        .unwrap()
+        .insert(0, FONT_NAME.to_owned());
+
+    fonts
+        .families
+        .get_mut(&egui::FontFamily::Monospace)
+        .unwrap()
+        .insert(0, FONT_NAME.to_owned());
+
+    ctx.set_fonts(fonts);
+    loaded
+}
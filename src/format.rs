@@ -0,0 +1,55 @@
+use crate::i18n::Texts;
+
+/* ---------------- 数值格式化 ---------------- */
+// 按三位分组插入千位分隔符，只用于界面显示，复制和导出仍使用原始数字
+pub fn grouped(n: usize, sep: &str) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3 * sep.len());
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push_str(sep);
+        }
+        out.push(c);
+    }
+    out
+}
+
+pub fn count(n: usize, t: &Texts) -> String {
+    grouped(n, t.thousands_sep)
+}
+
+// 带正负号，用于对比视图中的变化量
+pub fn signed(n: i64, t: &Texts) -> String {
+    let sign = if n < 0 { "-" } else { "+" };
+    format!("{}{}", sign, count(n.unsigned_abs() as usize, t))
+}
+
+// whole 为 0 时显示 0%
+pub fn percent(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        return "0.0%".to_owned();
+    }
+    format!("{:.1}%", part as f64 * 100.0 / whole as f64)
+}
+
+pub fn average(sum: usize, count: usize) -> String {
+    if count == 0 {
+        return "0".to_owned();
+    }
+    format!("{:.1}", sum as f64 / count as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouped_inserts_separator_every_three_digits() {
+        assert_eq!(grouped(0, ","), "0");
+        assert_eq!(grouped(999, ","), "999");
+        assert_eq!(grouped(1_000, ","), "1,000");
+        assert_eq!(grouped(123_456, ","), "123,456");
+        assert_eq!(grouped(1_234_567, "\u{a0}"), "1\u{a0}234\u{a0}567");
+        assert_eq!(grouped(1_234_567, ""), "1234567");
+    }
+}
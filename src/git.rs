@@ -0,0 +1,924 @@
+use chrono::{DateTime, Datelike, Local};
+use git2::{
+    BlameOptions, Delta, DescribeFormatOptions, DescribeOptions, DiffOptions, ErrorCode,
+    ObjectType, Oid, Patch, ReferenceType, Repository, Sort, Tree, TreeWalkMode, TreeWalkResult,
+};
+use log::{info, trace};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::Instant,
+};
+
+use crate::authors::{merge_ages, AgeStat, AuthorReport, AuthorStat};
+use crate::diff::{file_deltas, module_changes, FileDelta, LineDelta, ModuleChange};
+use crate::stat::{
+    count_line, count_paths, FileOutcome, FileStat, LastCommit, LineCounts, ModuleStat, ScanFilter,
+    ScanOptions, ScanProgress, Selection, StatResult, WarningKind,
+};
+
+/* ---------------- 统计版本库中的某个提交 ---------------- */
+// root 所在仓库中 root 对应的子目录，root 就是工作区根目录时为空
+fn prefix_in_repo(repo: &Repository, root: &Path) -> Result<PathBuf, git2::Error> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("bare repositories are not supported"))?;
+    let canonical = |p: &Path| {
+        p.canonicalize()
+            .map_err(|e| git2::Error::from_str(&format!("{}: {}", p.display(), e)))
+    };
+    let (root, workdir) = (canonical(root)?, canonical(workdir)?);
+    Ok(root
+        .strip_prefix(&workdir)
+        .map(Path::to_path_buf)
+        .unwrap_or_default())
+}
+
+// 仓库内的路径一律以 / 分隔，Windows 上不能直接 join
+fn repo_path(prefix: &Path, rel_path: &str) -> PathBuf {
+    if prefix.as_os_str().is_empty() {
+        return PathBuf::from(rel_path);
+    }
+    let prefix = prefix.to_string_lossy().replace('\\', "/");
+    PathBuf::from(format!("{}/{}", prefix, rel_path))
+}
+
+// 从对象库读取文件内容，不要求检出；规则与遍历工作区时相同，结果中的路径仍以 root 开头
+pub fn stat_git_ref(
+    root: &Path,
+    reference: &str,
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<StatResult, git2::Error> {
+    let started = Instant::now();
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    info!("scanning {} at {}", root.display(), reference);
+    let (selection, blobs) = {
+        let tree = subtree(&repo, &prefix, reference)?;
+        select_tree(&tree, root, &ScanFilter::new(opts))?
+    };
+    Ok(count_blobs(
+        repo, root, selection, &blobs, opts, started, progress,
+    ))
+}
+
+// reference 所指提交中 root 对应的子树
+fn subtree<'r>(
+    repo: &'r Repository,
+    prefix: &Path,
+    reference: &str,
+) -> Result<Tree<'r>, git2::Error> {
+    subtree_of(
+        repo,
+        prefix,
+        repo.revparse_single(reference)?.peel_to_tree()?,
+    )
+}
+
+fn subtree_of<'r>(
+    repo: &'r Repository,
+    prefix: &Path,
+    tree: Tree<'r>,
+) -> Result<Tree<'r>, git2::Error> {
+    if prefix.as_os_str().is_empty() {
+        return Ok(tree);
+    }
+    tree.get_path(prefix)?.to_object(repo)?.peel_to_tree()
+}
+
+// 按统计规则选出树中的文件及其 blob，沿途记录模块和插件
+fn select_tree(
+    tree: &Tree,
+    root: &Path,
+    filter: &ScanFilter,
+) -> Result<(Selection, HashMap<PathBuf, Oid>), git2::Error> {
+    let mut selection = Selection::default();
+    let mut blobs = HashMap::new();
+    // 子模块在树中是提交对象，和符号链接一样不统计
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        let (Some(ObjectType::Blob), Some(name)) = (entry.kind(), entry.name()) else {
+            return TreeWalkResult::Ok;
+        };
+        if entry.filemode() == 0o120000 {
+            return TreeWalkResult::Ok;
+        }
+        let path = root.join(dir).join(name);
+        if filter.skip(root, &path) {
+            trace!("excluded {}", path.display());
+            return TreeWalkResult::Ok;
+        }
+        selection.roots.record(&path);
+        if filter.count(root, &path) {
+            blobs.insert(path.clone(), entry.id());
+            selection.paths.push(path);
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok((selection, blobs))
+}
+
+fn count_blobs(
+    repo: Repository,
+    root: &Path,
+    selection: Selection,
+    blobs: &HashMap<PathBuf, Oid>,
+    opts: &ScanOptions,
+    started: Instant,
+    progress: &ScanProgress,
+) -> StatResult {
+    // 仓库对象不能在线程间共享，读取时加锁，数行仍在各线程中进行
+    let repo = Mutex::new(repo);
+    let max_bytes = opts.max_bytes();
+    let read = |path: &Path| {
+        let bytes = {
+            let repo = repo.lock().expect("版本库锁异常");
+            let blob = match repo.find_blob(blobs[path]) {
+                Ok(blob) => blob,
+                Err(e) => return FileOutcome::skipped(WarningKind::Read, e.message().to_owned()),
+            };
+            if let Some(skipped) = FileOutcome::too_large(blob.size() as u64, max_bytes) {
+                return skipped;
+            }
+            blob.content().to_vec()
+        };
+        FileOutcome::from_bytes(bytes, None)
+    };
+    count_paths(root, selection, opts, started, progress, &read)
+}
+
+// reference 所指的提交号，供界面在统计前先检查输入
+pub fn resolve_commit(root: &Path, reference: &str) -> Result<String, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+    Ok(commit.id().to_string())
+}
+
+// root 所在仓库当前检出的提交，不在仓库中或还没有提交时为 None
+pub fn head_revision(root: &Path) -> Option<String> {
+    let repo = Repository::discover(root).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+// 本地分支、远程分支和标签的短名，依次排列、各自按名字排序；
+// origin/HEAD 这样的符号引用不列出
+pub fn list_refs(root: &Path) -> Result<Vec<String>, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let mut groups: [Vec<String>; 3] = Default::default();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if reference.kind() != Some(ReferenceType::Direct) {
+            continue;
+        }
+        let group = if reference.is_branch() {
+            0
+        } else if reference.is_remote() {
+            1
+        } else if reference.is_tag() {
+            2
+        } else {
+            continue;
+        };
+        if let Some(name) = reference.shorthand() {
+            groups[group].push(name.to_owned());
+        }
+    }
+    for group in &mut groups {
+        group.sort();
+    }
+    Ok(groups.concat())
+}
+
+/* ---------------- 已跟踪的文件 ---------------- */
+// 索引中位于 root 之下的文件，路径相对 root；子模块的内容不在其中
+pub fn tracked_files(root: &Path) -> Result<HashSet<PathBuf>, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let index = repo.index()?;
+    let files = index
+        .iter()
+        .filter_map(|entry| {
+            let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+            path.strip_prefix(&prefix).ok().map(Path::to_path_buf)
+        })
+        .collect();
+    Ok(files)
+}
+
+/* ---------------- 子模块 ---------------- */
+// .gitmodules 中位于 root 之下的子模块，路径相对 root、以 / 分隔
+pub fn submodules(root: &Path) -> Result<Vec<String>, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let mut paths: Vec<String> = repo
+        .submodules()?
+        .iter()
+        .filter_map(|s| {
+            let path = s.path().strip_prefix(&prefix).ok()?;
+            Some(path.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/* ---------------- 按作者归属 ---------------- */
+// 工作区中改过但尚未提交的行归到这个名字下
+pub const UNCOMMITTED: &str = "Not committed yet";
+
+// 文件中每行的最后修改者和修改所在的季度，以工作区的内容为准；未提交的行算作当前季度。
+// 文件不在 HEAD 中时为 NotFound 错误
+fn blame_file(
+    repo: &Repository,
+    path: &Path,
+    bytes: &[u8],
+) -> Result<(Vec<AuthorStat>, Vec<AgeStat>), git2::Error> {
+    let mut opts = BlameOptions::new();
+    opts.use_mailmap(true);
+    let blame = repo.blame_file(path, Some(&mut opts))?;
+    let head = repo
+        .head()?
+        .peel_to_tree()?
+        .get_path(path)?
+        .to_object(repo)?
+        .peel_to_blob()?;
+    let hunks = workdir_hunks(&head, path, bytes)?;
+    let mut authors: Vec<AuthorStat> = Vec::new();
+    let mut ages: Vec<AgeStat> = Vec::new();
+    let now = Local::now();
+    for (number, line) in blame_lines(bytes) {
+        let head_line = head_line(&hunks, number);
+        let (name, email, time) = match head_line.and_then(|n| blame.get_line(n)) {
+            Some(hunk) if !hunk.final_commit_id().is_zero() => {
+                let sig = hunk.final_signature();
+                let time = DateTime::from_timestamp(sig.when().seconds(), 0)
+                    .map_or(now, |t| t.with_timezone(&Local));
+                (
+                    String::from_utf8_lossy(sig.name_bytes()).into_owned(),
+                    String::from_utf8_lossy(sig.email_bytes()).into_owned(),
+                    time,
+                )
+            }
+            _ => (UNCOMMITTED.to_owned(), String::new(), now),
+        };
+        let mut age = AgeStat {
+            year: time.year(),
+            quarter: time.month0() / 3 + 1,
+            ..Default::default()
+        };
+        count_line(&mut age.lines, &line);
+        merge_ages(&mut ages, &[age]);
+        let index = match authors
+            .iter()
+            .position(|a| a.name == name && a.email == email)
+        {
+            Some(j) => j,
+            None => {
+                authors.push(AuthorStat {
+                    name,
+                    email,
+                    files: 1,
+                    ..Default::default()
+                });
+                authors.len() - 1
+            }
+        };
+        count_line(&mut authors[index].lines, &line);
+    }
+    Ok((authors, ages))
+}
+
+// HEAD 中的内容与工作区内容之间改动的区段，各为 (旧起始行, 旧行数, 新起始行, 新行数)。
+// 不用 blame_buffer：它把 UTF-16 等 git 视为二进制的文件整个当作未改动，拆分出的区段也不带签名。
+// 强制按文本比较；忽略行尾空白，免得 autocrlf 检出的 CRLF 让每一行都算作改动
+fn workdir_hunks(
+    head: &git2::Blob,
+    path: &Path,
+    bytes: &[u8],
+) -> Result<Vec<(usize, usize, usize, usize)>, git2::Error> {
+    if head.content() == bytes {
+        return Ok(Vec::new());
+    }
+    let mut opts = DiffOptions::new();
+    opts.force_text(true)
+        .ignore_whitespace_eol(true)
+        .context_lines(0);
+    let patch = Patch::from_blob_and_buffer(head, Some(path), bytes, Some(path), Some(&mut opts))?;
+    (0..patch.num_hunks())
+        .map(|i| {
+            let (hunk, _) = patch.hunk(i)?;
+            let (old, new) = (hunk.old_lines() as usize, hunk.new_lines() as usize);
+            // 一侧没有行时起始行指的是它前面那一行
+            let old_start = hunk.old_start() as usize + usize::from(old == 0);
+            let new_start = hunk.new_start() as usize + usize::from(new == 0);
+            Ok((old_start, old, new_start, new))
+        })
+        .collect()
+}
+
+// 工作区第 number 行在 HEAD 中的行号，是新加或改过的行时为 None
+fn head_line(hunks: &[(usize, usize, usize, usize)], number: usize) -> Option<usize> {
+    let mut shift = 0isize;
+    for &(old_start, old, new_start, new) in hunks {
+        if number < new_start {
+            break;
+        }
+        if number < new_start + new {
+            return None;
+        }
+        shift = (old_start + old) as isize - (new_start + new) as isize;
+    }
+    Some((number as isize + shift) as usize)
+}
+
+// 文件的每一行及其在 blame 和比较中的行号（从 1 开始）。两者都按字节 \n 分行，
+// UTF-16 文件要按码元找换行，再数出此前的 \n 字节换算成 blame 的行号
+fn blame_lines(bytes: &[u8]) -> Vec<(usize, String)> {
+    let little_endian = match bytes {
+        [0xFF, 0xFE, ..] => true,
+        [0xFE, 0xFF, ..] => false,
+        _ => {
+            let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+            let mut lines: Vec<&[u8]> = bytes.split(|&b| b == b'\n').collect();
+            // 与 str::lines 一致，末尾换行之后不再算一行
+            if lines.last().is_some_and(|l| l.is_empty()) {
+                lines.pop();
+            }
+            return lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    let line = line.strip_suffix(b"\r").unwrap_or(line);
+                    (i + 1, String::from_utf8_lossy(line).into_owned())
+                })
+                .collect();
+        }
+    };
+    let mut lines = Vec::new();
+    let (mut number, mut counted) = (1, 0);
+    let mut push = |start: usize, units: &[u16]| {
+        number += bytes[counted..start]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        counted = start;
+        let mut line: String = char::decode_utf16(units.iter().copied())
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        lines.push((number, line));
+    };
+    let (mut start, mut units) = (2, Vec::new());
+    for (i, pair) in bytes[2..].chunks_exact(2).enumerate() {
+        let pair = [pair[0], pair[1]];
+        let unit = if little_endian {
+            u16::from_le_bytes(pair)
+        } else {
+            u16::from_be_bytes(pair)
+        };
+        if unit == u16::from(b'\n') {
+            push(start, &units);
+            units.clear();
+            start = 2 + (i + 1) * 2;
+        } else {
+            units.push(unit);
+        }
+    }
+    if !units.is_empty() {
+        push(start, &units);
+    }
+    lines
+}
+
+// 逐个文件做 blame 并按作者和模块汇总；不在 HEAD 中的文件只计数，不归属
+pub fn blame_authors(
+    root: &Path,
+    files: &[FileStat],
+    modules: &[ModuleStat],
+    workers: usize,
+    progress: &ScanProgress,
+) -> Result<AuthorReport, git2::Error> {
+    let started = Instant::now();
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let git_dir = repo.path().to_path_buf();
+    info!("blaming {} files under {}", files.len(), root.display());
+    progress.set_total(files.len());
+
+    // 仓库对象不能在线程间共享，每个线程各自打开
+    type Blamed = Option<(Vec<AuthorStat>, Vec<AgeStat>)>;
+    let blame_part = |part: &[FileStat]| -> Result<Vec<Blamed>, git2::Error> {
+        let repo = Repository::open(&git_dir)?;
+        let mut out = Vec::with_capacity(part.len());
+        for file in part {
+            let authors = match fs::read(&file.path) {
+                Ok(bytes) => match blame_file(&repo, &repo_path(&prefix, &file.rel_path), &bytes) {
+                    Ok(authors) => Some(authors),
+                    Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+                    Err(e) => return Err(e),
+                },
+                Err(e) => {
+                    trace!("{}: {}", file.path.display(), e);
+                    None
+                }
+            };
+            out.push(authors);
+            progress.advance();
+        }
+        Ok(out)
+    };
+    let chunk = files.len().div_ceil(workers.max(1)).max(1);
+    let blamed: Vec<Blamed> = thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk)
+            .map(|part| scope.spawn(move || blame_part(part)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("blame 线程异常退出"))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|parts| parts.into_iter().flatten().collect())
+    })?;
+
+    let mut report = AuthorReport::new(modules);
+    for (file, authors) in files.iter().zip(blamed) {
+        match authors {
+            Some((authors, ages)) => report.add_file(
+                file.module.as_deref(),
+                file.plugin.as_deref(),
+                &authors,
+                &ages,
+            ),
+            None => report.untracked += 1,
+        }
+    }
+    report.sort();
+    info!(
+        "{}: {} authors in {:.2?}",
+        root.display(),
+        report.authors.len(),
+        started.elapsed()
+    );
+    Ok(report)
+}
+
+/* ---------------- 代码变动量 ---------------- */
+// 提交时间不一定随历史单调递减（rebase、时钟偏差），遇到早于窗口的提交不立即停下，
+// 连续这么多个提交都早于窗口才停止，与 git log --since 的做法相同
+const SINCE_LOOK_BEHIND: usize = 5;
+
+// 最近 days 天内每个文件增加与删除的行数之和，与 git log --numstat --no-merges 相同，
+// 不区分代码和注释；键为 root 下的路径，与统计结果中的一致
+pub fn file_churn(root: &Path, days: u32) -> Result<HashMap<PathBuf, usize>, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let since = Local::now().timestamp() - i64::from(days) * 86_400;
+    // 不排序：按时间排序要先把整个历史走一遍，这里沿父提交边走边判断，窗口外即可停下
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    let mut opts = DiffOptions::new();
+    if !prefix.as_os_str().is_empty() {
+        opts.pathspec(&prefix);
+    }
+
+    let mut churn = HashMap::new();
+    let mut older = 0;
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < since {
+            older += 1;
+            if older >= SINCE_LOOK_BEHIND {
+                break;
+            }
+            continue;
+        }
+        older = 0;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let parent = commit.parents().next().map(|p| p.tree()).transpose()?;
+        let mut diff =
+            repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), Some(&mut opts))?;
+        // 重命名的文件只计内容的改动
+        diff.find_similar(None)?;
+        for i in 0..diff.deltas().len() {
+            // 二进制文件没有逐行差异
+            let Some(patch) = Patch::from_diff(&diff, i)? else {
+                continue;
+            };
+            let Some(path) = patch.delta().new_file().path() else {
+                continue;
+            };
+            let Ok(rel) = path.strip_prefix(&prefix) else {
+                continue;
+            };
+            let (_, added, deleted) = patch.line_stats()?;
+            *churn.entry(root.join(rel)).or_default() += added + deleted;
+        }
+    }
+    Ok(churn)
+}
+
+/* ---------------- 文件的最后一次提交 ---------------- */
+// reference 中按统计规则选出的每个文件最后一次被修改的提交，与 git log -1 --no-merges 相同；
+// 从新到旧遍历，全部找到即停止。键为 root 下的路径，与统计结果中的一致
+pub fn last_commits(
+    root: &Path,
+    reference: &str,
+    opts: &ScanOptions,
+) -> Result<HashMap<PathBuf, LastCommit>, git2::Error> {
+    let started = Instant::now();
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let (selection, _) = select_tree(
+        &subtree(&repo, &prefix, reference)?,
+        root,
+        &ScanFilter::new(opts),
+    )?;
+    let mut pending: HashSet<PathBuf> = selection.paths.into_iter().collect();
+    let mailmap = repo.mailmap()?;
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TIME)?;
+    walk.push(repo.revparse_single(reference)?.peel_to_commit()?.id())?;
+    let mut diff_opts = DiffOptions::new();
+    if !prefix.as_os_str().is_empty() {
+        diff_opts.pathspec(&prefix);
+    }
+
+    let mut commits = HashMap::new();
+    for oid in walk {
+        if pending.is_empty() {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let parent = commit.parents().next().map(|p| p.tree()).transpose()?;
+        let diff =
+            repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), Some(&mut diff_opts))?;
+        let mut info = None;
+        for delta in diff.deltas() {
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+            let Ok(rel) = path.strip_prefix(&prefix) else {
+                continue;
+            };
+            let path = root.join(rel);
+            if !pending.remove(&path) {
+                continue;
+            }
+            let info = info.get_or_insert_with(|| {
+                let author = commit.author_with_mailmap(&mailmap).ok();
+                LastCommit {
+                    time: DateTime::from_timestamp(commit.time().seconds(), 0)
+                        .unwrap_or_default()
+                        .with_timezone(&Local),
+                    author: author
+                        .map(|a| String::from_utf8_lossy(a.name_bytes()).into_owned())
+                        .unwrap_or_default(),
+                }
+            });
+            commits.insert(path, info.clone());
+        }
+    }
+    info!(
+        "{}: last commits of {} files in {:.2?}",
+        root.display(),
+        commits.len(),
+        started.elapsed()
+    );
+    Ok(commits)
+}
+
+/* ---------------- 提交历史上的行数趋势 ---------------- */
+// 沿提交历史取样的方式
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    // 每隔若干个提交取一个
+    Commits(usize),
+    // 相邻两个取样点至少相隔若干天
+    Days(u32),
+}
+
+pub struct TrendPoint {
+    pub revision: String,
+    // 提交时间
+    pub time: DateTime<Local>,
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+// 从 reference 沿第一父提交往回取样，最多 max_points 个，返回从旧到新的提交；
+// reference 本身总是第一个取样点
+pub fn sample_commits(
+    root: &Path,
+    reference: &str,
+    sampling: Sampling,
+    max_points: usize,
+) -> Result<Vec<(Oid, DateTime<Local>)>, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let mut walk = repo.revwalk()?;
+    walk.push(repo.revparse_single(reference)?.peel_to_commit()?.id())?;
+    walk.simplify_first_parent()?;
+    let mut samples = Vec::new();
+    let mut last_taken: Option<i64> = None;
+    for (i, oid) in walk.enumerate() {
+        if samples.len() >= max_points {
+            break;
+        }
+        let oid = oid?;
+        let seconds = repo.find_commit(oid)?.time().seconds();
+        let take = match sampling {
+            Sampling::Commits(n) => i % n.max(1) == 0,
+            Sampling::Days(days) => {
+                last_taken.is_none_or(|last| last - seconds >= i64::from(days) * 86_400)
+            }
+        };
+        if take {
+            last_taken = Some(seconds);
+            let time = DateTime::from_timestamp(seconds, 0).unwrap_or_default();
+            samples.push((oid, time.with_timezone(&Local)));
+        }
+    }
+    samples.reverse();
+    Ok(samples)
+}
+
+// 逐个取样点从对象库统计，不需要检出
+pub fn trend_point(
+    root: &Path,
+    (oid, time): (Oid, DateTime<Local>),
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<TrendPoint, git2::Error> {
+    let revision = oid.to_string();
+    let result = stat_git_ref(root, &revision, opts, progress)?;
+    Ok(TrendPoint {
+        revision,
+        time,
+        files: result.files.len(),
+        lines: result.totals,
+    })
+}
+
+/* ---------------- 两个提交之间的差异 ---------------- */
+// 只统计两棵树之间有变化的文件，不需要检出任何一边
+#[derive(Serialize)]
+pub struct RefDiff {
+    pub from: String,
+    pub to: String,
+    pub delta: LineDelta,
+    pub modules: Vec<ModuleChange>,
+    pub files: Vec<FileDelta>,
+}
+
+// reference 之前（含）最近的标签，作为“自上次发布以来”的起点
+pub fn latest_tag(root: &Path, reference: &str) -> Result<String, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+    let describe = commit
+        .as_object()
+        .describe(DescribeOptions::new().describe_tags())?;
+    let tag = describe.format(Some(DescribeFormatOptions::new().abbreviated_size(0)))?;
+    info!("latest tag before {} is {}", reference, tag);
+    Ok(tag)
+}
+
+pub fn diff_refs(
+    root: &Path,
+    from: &str,
+    to: &str,
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<RefDiff, git2::Error> {
+    info!("comparing {} between {} and {}", root.display(), from, to);
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let old = subtree(&repo, &prefix, from)?.id();
+    let new = subtree(&repo, &prefix, to)?.id();
+    diff_trees(root, (from, old), (to, new), opts, progress)
+}
+
+// 暂存区相对 HEAD 的变化，供提交前的钩子使用；暂存区先写成树对象，与提交时写入的相同
+pub fn diff_staged(
+    root: &Path,
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<RefDiff, git2::Error> {
+    info!("comparing {} between HEAD and the index", root.display());
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    // 还没有任何提交时与空树对比
+    let old = match repo.head() {
+        Ok(head) => subtree_of(&repo, &prefix, head.peel_to_tree()?)?.id(),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => repo.treebuilder(None)?.write()?,
+        Err(e) => return Err(e),
+    };
+    let index = repo.find_tree(repo.index()?.write_tree()?)?;
+    let new = subtree_of(&repo, &prefix, index)?.id();
+    diff_trees(root, ("HEAD", old), ("index", new), opts, progress)
+}
+
+// 工作区（含未跟踪的文件）相对 HEAD 的变化，即推送前本地新增了多少；
+// 工作区一侧直接取 current（这次统计的文件）中有变化的，不再读盘
+pub fn diff_workdir(
+    root: &Path,
+    current: &[FileStat],
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<RefDiff, git2::Error> {
+    info!(
+        "comparing {} between HEAD and the working tree",
+        root.display()
+    );
+    let started = Instant::now();
+    let repo = Repository::discover(root)?;
+    let prefix = prefix_in_repo(&repo, root)?;
+    let head = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e),
+    };
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(true);
+    if !prefix.as_os_str().is_empty() {
+        diff_opts.pathspec(&prefix);
+    }
+    let diff = repo.diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut diff_opts))?;
+    let (mut old_paths, mut new_paths) = (HashSet::new(), HashSet::new());
+    for delta in diff.deltas() {
+        let rel = |path: Option<&Path>| Some(root.join(path?.strip_prefix(&prefix).ok()?));
+        if delta.status() != Delta::Added && delta.status() != Delta::Untracked {
+            old_paths.extend(rel(delta.old_file().path()));
+        }
+        if delta.status() != Delta::Deleted {
+            new_paths.extend(rel(delta.new_file().path()));
+        }
+    }
+
+    let before = match head {
+        Some(tree) => {
+            let tree = subtree_of(&repo, &prefix, tree)?.id();
+            count_changed(root, tree, &old_paths, opts, started, progress)?
+        }
+        None => StatResult::default(),
+    };
+    let after: Vec<FileStat> = current
+        .iter()
+        .filter(|f| new_paths.contains(&f.path))
+        .cloned()
+        .collect();
+    let mut totals = LineCounts::default();
+    for file in &after {
+        totals += file.lines;
+    }
+    let files = file_deltas(&before.files, &after);
+    Ok(RefDiff {
+        from: "HEAD".to_owned(),
+        to: "working tree".to_owned(),
+        delta: LineDelta::between(&before.totals, &totals),
+        modules: module_changes(&files),
+        files,
+    })
+}
+
+// 只统计树中列在 changed 里的文件；模块仍按整棵树归属
+fn count_changed(
+    root: &Path,
+    tree: Oid,
+    changed: &HashSet<PathBuf>,
+    opts: &ScanOptions,
+    started: Instant,
+    progress: &ScanProgress,
+) -> Result<StatResult, git2::Error> {
+    let repo = Repository::discover(root)?;
+    let filter = ScanFilter::new(opts);
+    let (mut selection, blobs) = select_tree(&repo.find_tree(tree)?, root, &filter)?;
+    selection.paths.retain(|path| changed.contains(path));
+    Ok(count_blobs(
+        repo, root, selection, &blobs, opts, started, progress,
+    ))
+}
+
+// 只统计两棵树之间有变化的文件
+fn diff_trees(
+    root: &Path,
+    (from, old): (&str, Oid),
+    (to, new): (&str, Oid),
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<RefDiff, git2::Error> {
+    let started = Instant::now();
+
+    let (old_paths, new_paths) = {
+        let repo = Repository::discover(root)?;
+        let (old, new) = (repo.find_tree(old)?, repo.find_tree(new)?);
+        let diff = repo.diff_tree_to_tree(Some(&old), Some(&new), None)?;
+        let (mut old_paths, mut new_paths) = (HashSet::new(), HashSet::new());
+        for delta in diff.deltas() {
+            if let Some(path) = delta.old_file().path() {
+                old_paths.insert(root.join(path));
+            }
+            if let Some(path) = delta.new_file().path() {
+                new_paths.insert(root.join(path));
+            }
+        }
+        (old_paths, new_paths)
+    };
+    let before = count_changed(root, old, &old_paths, opts, started, progress)?;
+    let after = count_changed(root, new, &new_paths, opts, started, progress)?;
+    let files = file_deltas(&before.files, &after.files);
+    Ok(RefDiff {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        delta: LineDelta::between(&before.totals, &after.totals),
+        modules: module_changes(&files),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 以 days_ago 天前的时间提交一个文件
+    fn commit(repo: &Repository, file: &str, text: &str, days_ago: i64) {
+        fs::write(repo.workdir().unwrap().join(file), text).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let time = git2::Time::new(Local::now().timestamp() - days_ago * 86_400, 0);
+        let signature = git2::Signature::new("dev", "dev@example.com", &time).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, file, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn file_churn_looks_past_commits_with_older_dates() {
+        let dir = std::env::temp_dir().join(format!("ueloc-churn-{}", std::process::id()));
+        let repo = Repository::init(&dir).unwrap();
+        commit(&repo, "A.cpp", "a\n", 200);
+        commit(&repo, "B.cpp", "b\nb\n", 10);
+        // rebase 过来的提交保留了更早的时间，排在窗口内的提交之前
+        commit(&repo, "C.cpp", "c\n", 300);
+        commit(&repo, "D.cpp", "d\n", 1);
+        let churn = file_churn(&dir, 90);
+        fs::remove_dir_all(&dir).unwrap();
+        let churn = churn.unwrap();
+        assert_eq!(churn.len(), 2);
+        assert_eq!(churn[&dir.join("B.cpp")], 2);
+        assert_eq!(churn[&dir.join("D.cpp")], 1);
+    }
+
+    #[test]
+    fn head_line_skips_changed_lines() {
+        // 工作区在 HEAD 第 1 行后插入两行，又把 HEAD 第 3 行改成一行、删掉第 5 行
+        let hunks = [(2, 0, 2, 2), (3, 1, 5, 1), (5, 1, 7, 0)];
+        let mapped: Vec<Option<usize>> = (1..=8).map(|n| head_line(&hunks, n)).collect();
+        assert_eq!(
+            mapped,
+            [
+                Some(1),
+                None,
+                None,
+                Some(2),
+                None,
+                Some(4),
+                Some(6),
+                Some(7)
+            ]
+        );
+        assert_eq!(head_line(&[], 3), Some(3));
+    }
+
+    #[test]
+    fn blame_lines_number_by_newline_bytes() {
+        assert_eq!(
+            blame_lines(b"\xEF\xBB\xBFa\r\n\nb\n"),
+            [(1, "a".to_owned()), (2, String::new()), (3, "b".to_owned())]
+        );
+        // UTF-16 中码元含 0x0A 字节的字符（如 U+010A）在 git 看来也是换行
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("\u{10A}\r\nb".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(
+            blame_lines(&utf16),
+            [(1, "\u{10A}".to_owned()), (3, "b".to_owned())]
+        );
+    }
+}
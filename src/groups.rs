@@ -0,0 +1,132 @@
+use eframe::egui;
+use std::{cmp::Reverse, collections::HashMap, path::PathBuf};
+
+use crate::format;
+use crate::i18n::Texts;
+use crate::stat::{LineCounts, StatResult};
+
+/* ---------------- 按插件分组 ---------------- */
+pub struct ModuleGroup {
+    pub name: Option<String>,
+    pub lines: LineCounts,
+    // 属于该模块的文件（路径、相对路径、代码行数），按代码行数从多到少
+    pub files: Vec<(PathBuf, String, usize)>,
+}
+
+pub struct PluginGroup {
+    // None 表示游戏工程本身（不属于任何插件）
+    pub plugin: Option<String>,
+    pub lines: LineCounts,
+    pub files: usize,
+    pub modules: Vec<ModuleGroup>,
+}
+
+// 游戏工程与各插件一起按代码行数从多到少排列
+pub fn build_groups(result: &StatResult) -> Vec<PluginGroup> {
+    let mut by_module: HashMap<_, Vec<_>> = HashMap::new();
+    for file in &result.files {
+        by_module
+            .entry((file.module.as_deref(), file.plugin.as_deref()))
+            .or_default()
+            .push((file.path.clone(), file.rel_path.clone(), file.lines.code));
+    }
+
+    let mut groups: Vec<PluginGroup> = Vec::new();
+    for module in &result.modules {
+        let idx = match groups.iter().position(|g| g.plugin == module.plugin) {
+            Some(idx) => idx,
+            None => {
+                groups.push(PluginGroup {
+                    plugin: module.plugin.clone(),
+                    lines: LineCounts::default(),
+                    files: 0,
+                    modules: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        let group = &mut groups[idx];
+        group.lines += module.lines;
+        group.files += module.files;
+
+        let mut files = by_module
+            .remove(&(module.name.as_deref(), module.plugin.as_deref()))
+            .unwrap_or_default();
+        files.sort_by_key(|(_, _, code)| Reverse(*code));
+        group.modules.push(ModuleGroup {
+            name: module.name.clone(),
+            lines: module.lines,
+            files,
+        });
+    }
+    groups.sort_by_key(|g| Reverse(g.lines.code));
+    groups
+}
+
+fn subtotal(name: &str, files: usize, lines: &LineCounts, t: &Texts) -> String {
+    format!(
+        "{}    {} {} / {} {} / {} {}",
+        name,
+        t.col_code,
+        format::count(lines.code, t),
+        t.col_total,
+        format::count(lines.total, t),
+        t.files_unit,
+        format::count(files, t)
+    )
+}
+
+// 标题行后附上占全工程代码量的比例条
+fn share_bar(ui: &mut egui::Ui, code: usize, total: usize) {
+    let share = if total == 0 {
+        0.0
+    } else {
+        code as f32 / total as f32
+    };
+    ui.add(
+        egui::ProgressBar::new(share)
+            .desired_width(120.0)
+            .text(format!("{:.1}%", share * 100.0)),
+    );
+}
+
+pub fn show_groups(ui: &mut egui::Ui, groups: &[PluginGroup], t: &Texts) {
+    let total: usize = groups.iter().map(|g| g.lines.code).sum();
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                for group in groups {
+                    let name = group.plugin.as_deref().unwrap_or(t.game_project);
+                    let id = ui.make_persistent_id(("plugin_group", &group.plugin));
+                    egui::collapsing_header::CollapsingState::load_with_default_open(
+                        ui.ctx(),
+                        id,
+                        group.plugin.is_none(),
+                    )
+                    .show_header(ui, |ui| {
+                        share_bar(ui, group.lines.code, total);
+                        ui.label(subtotal(name, group.files, &group.lines, t));
+                    })
+                    .body(|ui| {
+                        for module in &group.modules {
+                            let name = module.name.as_deref().unwrap_or(t.no_module);
+                            let header = subtotal(name, module.files.len(), &module.lines, t);
+                            egui::CollapsingHeader::new(header)
+                                .id_source(("module_group", &group.plugin, &module.name))
+                                .show(ui, |ui| {
+                                    for (path, rel_path, code) in &module.files {
+                                        ui.label(format!(
+                                            "{}    {}",
+                                            rel_path,
+                                            format::count(*code, t)
+                                        ))
+                                        .on_hover_text(path.display().to_string());
+                                    }
+                                });
+                        }
+                    });
+                }
+            });
+        });
+}
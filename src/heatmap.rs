@@ -0,0 +1,65 @@
+use eframe::egui::{self, Color32};
+use serde::{Deserialize, Serialize};
+
+use crate::stat::LineCounts;
+
+const POOR_COLOR: Color32 = Color32::from_rgb(220, 70, 60);
+const FAIR_COLOR: Color32 = Color32::from_rgb(230, 190, 60);
+const GOOD_COLOR: Color32 = Color32::from_rgb(80, 180, 90);
+
+/* ---------------- 注释率热力着色 ---------------- */
+// 注释率低于 low 为红，高于 high 为绿，中间渐变
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Heatmap {
+    pub enabled: bool,
+    pub low: f32,
+    pub high: f32,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            low: 0.1,
+            high: 0.3,
+        }
+    }
+}
+
+fn lerp_color(a: Color32, b: Color32, t: f32) -> Color32 {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgb(mix(a.r(), b.r()), mix(a.g(), b.g()), mix(a.b(), b.b()))
+}
+
+impl Heatmap {
+    // 未开启或没有代码和注释时不着色
+    pub fn color(&self, lines: &LineCounts) -> Option<Color32> {
+        if !self.enabled || lines.code + lines.comment == 0 {
+            return None;
+        }
+        let ratio = lines.comment_ratio() as f32;
+        let (low, high) = (self.low.min(self.high), self.high.max(self.low));
+        let color = if ratio <= low {
+            POOR_COLOR
+        } else if ratio >= high {
+            GOOD_COLOR
+        } else {
+            let mid = (low + high) / 2.0;
+            if ratio < mid {
+                lerp_color(POOR_COLOR, FAIR_COLOR, (ratio - low) / (mid - low))
+            } else {
+                lerp_color(FAIR_COLOR, GOOD_COLOR, (ratio - mid) / (high - mid))
+            }
+        };
+        Some(color)
+    }
+
+    // 表格单元格用的淡色背景
+    pub fn paint_cell(&self, ui: &egui::Ui, lines: &LineCounts) {
+        if let Some(color) = self.color(lines) {
+            ui.painter()
+                .rect_filled(ui.max_rect(), 0.0, color.gamma_multiply(0.25));
+        }
+    }
+}
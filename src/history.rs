@@ -0,0 +1,104 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::git;
+use crate::stat::{LineCounts, ModuleStat, StatResult};
+
+/* ---------------- 历史记录 ---------------- */
+// 每次统计完成后记录一条摘要，用于绘制趋势图
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub root: PathBuf,
+    pub timestamp: DateTime<Local>,
+    // 统计时工程所在 git 仓库的 HEAD 提交
+    #[serde(default)]
+    pub revision: Option<String>,
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+#[derive(Default)]
+pub struct History {
+    // 存放历史记录的目录，None 时只保存在内存中
+    dir: Option<PathBuf>,
+    entries: Vec<HistoryEntry>,
+    #[cfg(feature = "sqlite")]
+    db: Option<rusqlite::Connection>,
+}
+
+/* ---------------- JSON 存储 ---------------- */
+#[cfg(not(feature = "sqlite"))]
+const HISTORY_FILE: &str = "history.json";
+
+#[cfg(not(feature = "sqlite"))]
+impl History {
+    // 文件不存在或损坏时从空记录开始
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let entries = dir
+            .as_ref()
+            .and_then(|d| std::fs::read(d.join(HISTORY_FILE)).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self { dir, entries }
+    }
+
+    // 整个文件重写，模块数据不保存
+    fn save(&self, _modules: &[ModuleStat]) -> io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_vec_pretty(&self.entries)?;
+        std::fs::write(dir.join(HISTORY_FILE), json)
+    }
+}
+
+/* ---------------- SQLite 存储 ---------------- */
+#[cfg(feature = "sqlite")]
+impl History {
+    // 数据库打不开时只保存在内存中，记录时再报告错误
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let db = dir.as_deref().and_then(|d| crate::history_db::open(d).ok());
+        let entries = db
+            .as_ref()
+            .and_then(|db| crate::history_db::load(db).ok())
+            .unwrap_or_default();
+        Self { dir, entries, db }
+    }
+
+    // 只插入最新的一条及其模块
+    fn save(&self, modules: &[ModuleStat]) -> io::Result<()> {
+        let (Some(db), Some(entry)) = (&self.db, self.entries.last()) else {
+            return match self.dir {
+                Some(_) => Err(io::Error::other("history database is not available")),
+                None => Ok(()),
+            };
+        };
+        crate::history_db::insert(db, entry, modules).map_err(io::Error::other)
+    }
+}
+
+impl History {
+    pub fn record(&mut self, root: &Path, result: &StatResult) -> io::Result<()> {
+        let Some(timestamp) = result.finished_at else {
+            return Ok(());
+        };
+        self.entries.push(HistoryEntry {
+            root: root.to_path_buf(),
+            timestamp,
+            revision: git::head_revision(root),
+            files: result.files.len(),
+            lines: result.totals,
+        });
+        self.save(&result.modules)
+    }
+
+    // 某个工程的全部记录，按时间先后
+    pub fn for_project<'a>(&'a self, root: &'a Path) -> impl Iterator<Item = &'a HistoryEntry> {
+        self.entries.iter().filter(move |e| e.root == root)
+    }
+}
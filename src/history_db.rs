@@ -0,0 +1,118 @@
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+use crate::history::HistoryEntry;
+use crate::stat::{LineCounts, ModuleStat};
+
+const HISTORY_DB: &str = "history.sqlite";
+
+/* ---------------- SQLite 历史记录库 ---------------- */
+// 表结构保持简单，其他团队可以直接用 SQL 查询；时间为 RFC 3339 文本
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS scans (
+    id         INTEGER PRIMARY KEY,
+    root       TEXT NOT NULL,
+    timestamp  TEXT NOT NULL,
+    revision   TEXT,
+    files      INTEGER NOT NULL,
+    total      INTEGER NOT NULL,
+    code       INTEGER NOT NULL,
+    comment    INTEGER NOT NULL,
+    blank      INTEGER NOT NULL,
+    includes   INTEGER NOT NULL,
+    reflection INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS modules (
+    scan_id INTEGER NOT NULL REFERENCES scans(id),
+    module  TEXT,
+    plugin  TEXT,
+    files   INTEGER NOT NULL,
+    total   INTEGER NOT NULL,
+    code    INTEGER NOT NULL,
+    comment INTEGER NOT NULL,
+    blank   INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS scans_root ON scans(root);
+";
+
+pub fn open(dir: &Path) -> rusqlite::Result<Connection> {
+    // 目录创建失败时交给 open 报告错误
+    let _ = std::fs::create_dir_all(dir);
+    let db = Connection::open(dir.join(HISTORY_DB))?;
+    db.execute_batch(SCHEMA)?;
+    Ok(db)
+}
+
+// 时间无法解析的行跳过
+pub fn load(db: &Connection) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut stmt = db.prepare(
+        "SELECT root, timestamp, revision, files, total, code, comment, blank, includes, reflection
+         FROM scans ORDER BY id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let timestamp: String = row.get(1)?;
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&timestamp) else {
+            return Ok(None);
+        };
+        Ok(Some(HistoryEntry {
+            root: PathBuf::from(row.get::<_, String>(0)?),
+            timestamp: timestamp.with_timezone(&Local),
+            revision: row.get(2)?,
+            files: row.get(3)?,
+            lines: LineCounts {
+                total: row.get(4)?,
+                code: row.get(5)?,
+                comment: row.get(6)?,
+                blank: row.get(7)?,
+                includes: row.get(8)?,
+                reflection: row.get(9)?,
+            },
+        }))
+    })?;
+    rows.filter_map(Result::transpose).collect()
+}
+
+// 摘要和各模块在一个事务中写入
+pub fn insert(
+    db: &Connection,
+    entry: &HistoryEntry,
+    modules: &[ModuleStat],
+) -> rusqlite::Result<()> {
+    let tx = db.unchecked_transaction()?;
+    let lines = &entry.lines;
+    tx.execute(
+        "INSERT INTO scans (root, timestamp, revision, files, total, code, comment, blank, includes, reflection)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            entry.root.display().to_string(),
+            entry.timestamp.to_rfc3339(),
+            entry.revision,
+            entry.files,
+            lines.total,
+            lines.code,
+            lines.comment,
+            lines.blank,
+            lines.includes,
+            lines.reflection
+        ],
+    )?;
+    let scan_id = tx.last_insert_rowid();
+    for m in modules {
+        tx.execute(
+            "INSERT INTO modules (scan_id, module, plugin, files, total, code, comment, blank)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                scan_id,
+                m.name,
+                m.plugin,
+                m.files,
+                m.lines.total,
+                m.lines.code,
+                m.lines.comment,
+                m.lines.blank
+            ],
+        )?;
+    }
+    tx.commit()
+}
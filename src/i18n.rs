@@ -0,0 +1,775 @@
+use serde::{Deserialize, Serialize};
+
+/* ---------------- 界面语言 ---------------- */
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    Zh,
+    En,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::Zh, Lang::En];
+
+    // 语言选择框里显示的名字，始终用该语言自身书写
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Lang::Zh => "简体中文",
+            Lang::En => "English",
+        }
+    }
+
+    pub fn texts(self) -> &'static Texts {
+        match self {
+            Lang::Zh => &ZH,
+            Lang::En => &EN,
+        }
+    }
+}
+
+/* ---------------- 字符串表 ---------------- */
+// 每种语言一份，新增字段时编译器会要求所有语言都补齐
+pub struct Texts {
+    // 标签与数值之间的冒号，中文用全角
+    pub colon: &'static str,
+    pub thousands_sep: &'static str,
+    pub heading: &'static str,
+    pub select_dir: &'static str,
+    pub no_dir: &'static str,
+    pub project_path: &'static str,
+    pub file_count: &'static str,
+    pub total_lines: &'static str,
+    pub code_lines: &'static str,
+    pub comment_lines: &'static str,
+    pub blank_lines: &'static str,
+    pub metric: &'static str,
+    pub value: &'static str,
+    pub copy_summary: &'static str,
+    pub copy_markdown: &'static str,
+    pub copy_markdown_hint: &'static str,
+    pub copied: &'static str,
+    pub drop_hint: &'static str,
+    pub drop_release: &'static str,
+    pub col_path: &'static str,
+    pub col_total: &'static str,
+    pub col_code: &'static str,
+    pub col_comment: &'static str,
+    pub col_blank: &'static str,
+    pub files_unit: &'static str,
+    pub col_comment_ratio: &'static str,
+    pub col_includes: &'static str,
+    pub col_reflection: &'static str,
+    pub columns: &'static str,
+    pub tip_path: &'static str,
+    pub tip_files: &'static str,
+    pub tip_total: &'static str,
+    pub tip_code: &'static str,
+    pub tip_comment: &'static str,
+    pub tip_blank: &'static str,
+    pub tip_comment_ratio: &'static str,
+    pub tip_includes: &'static str,
+    pub tip_reflection: &'static str,
+    pub tip_share_of_total: &'static str,
+    pub avg_lines: &'static str,
+    pub tip_avg_lines: &'static str,
+    pub view_files: &'static str,
+    pub largest_files: &'static str,
+    pub filter: &'static str,
+    pub filter_hint: &'static str,
+    pub filter_invalid: &'static str,
+    pub view_tree: &'static str,
+    pub view_modules: &'static str,
+    pub view_plugins: &'static str,
+    pub game_project: &'static str,
+    pub view_treemap: &'static str,
+    pub treemap_hint: &'static str,
+    pub treemap_loose_files: &'static str,
+    pub view_history: &'static str,
+    pub history_empty: &'static str,
+    pub history_save_failed: &'static str,
+    pub no_module: &'static str,
+    pub col_project: &'static str,
+    pub aggregate_tab: &'static str,
+    pub aggregate_skipped: &'static str,
+    pub compare_tab: &'static str,
+    pub compare_base: &'static str,
+    pub compare_target: &'static str,
+    pub compare_swap: &'static str,
+    pub col_delta: &'static str,
+    pub other: &'static str,
+    pub close_tab: &'static str,
+    pub rescan: &'static str,
+    pub rescan_hint: &'static str,
+    pub menu_file: &'static str,
+    pub menu_help: &'static str,
+    pub export: &'static str,
+    pub export_again: &'static str,
+    pub export_failed: &'static str,
+    pub save_chart: &'static str,
+    pub chart_saved: &'static str,
+    pub export_diff: &'static str,
+    pub export_warnings: &'static str,
+    pub export_warnings_hint: &'static str,
+    pub diff_added: &'static str,
+    pub diff_removed: &'static str,
+    pub diff_new_files: &'static str,
+    pub diff_deleted_files: &'static str,
+    pub export_template: &'static str,
+    pub export_template_hint: &'static str,
+    pub all_files: &'static str,
+    pub append_series: &'static str,
+    pub append_series_hint: &'static str,
+    pub snapshot: &'static str,
+    pub open_snapshot: &'static str,
+    pub save_snapshot: &'static str,
+    pub open_at_commit: &'static str,
+    pub open_at_commit_hint: &'static str,
+    pub open_at_commit_run: &'static str,
+    pub open_at_commit_failed: &'static str,
+    pub git_ref: &'static str,
+    pub git_ref_hint: &'static str,
+    pub working_tree: &'static str,
+    pub view_churn: &'static str,
+    pub col_churn: &'static str,
+    pub tip_churn: &'static str,
+    pub col_last_commit: &'static str,
+    pub tip_last_commit: &'static str,
+    pub col_last_author: &'static str,
+    pub tip_last_author: &'static str,
+    pub churn_days: &'static str,
+    pub churn_run: &'static str,
+    pub churn_hint: &'static str,
+    pub churn_empty: &'static str,
+    pub churn_ratio: &'static str,
+    pub by_author: &'static str,
+    pub ownership: &'static str,
+    pub ownership_hint: &'static str,
+    pub col_owner: &'static str,
+    pub col_authors: &'static str,
+    pub code_age: &'static str,
+    pub code_age_hint: &'static str,
+    pub age_by_year: &'static str,
+    pub age_by_quarter: &'static str,
+    pub snapshot_failed: &'static str,
+    pub focus_filter: &'static str,
+    pub shortcuts: &'static str,
+    pub mini_mode: &'static str,
+    pub batch_scan: &'static str,
+    pub batch_add: &'static str,
+    pub batch_clear: &'static str,
+    pub batch_hint: &'static str,
+    pub batch_empty: &'static str,
+    pub batch_finished: &'static str,
+    pub batch_open: &'static str,
+    pub mini_exit: &'static str,
+    pub recent_projects: &'static str,
+    pub clear_recent: &'static str,
+    pub scanned_at: &'static str,
+    pub report_generated_by: &'static str,
+    pub report_languages: &'static str,
+    pub elapsed: &'static str,
+    pub warnings: &'static str,
+    pub scanning: &'static str,
+    pub scan_finished: &'static str,
+    pub view_trend: &'static str,
+    pub trend_every: &'static str,
+    pub trend_commits: &'static str,
+    pub trend_days: &'static str,
+    pub trend_max: &'static str,
+    pub trend_run: &'static str,
+    pub trend_hint: &'static str,
+    pub trend_empty: &'static str,
+    pub trend_export: &'static str,
+    pub view_changes: &'static str,
+    pub changes_from: &'static str,
+    pub changes_to: &'static str,
+    pub changes_run: &'static str,
+    pub changes_hint: &'static str,
+    pub changes_empty: &'static str,
+    pub changes_files: &'static str,
+    pub changes_markdown_hint: &'static str,
+    pub changes_local: &'static str,
+    pub changes_local_hint: &'static str,
+    pub since_tag: &'static str,
+    pub since_tag_hint: &'static str,
+    pub view_authors: &'static str,
+    pub blame_run: &'static str,
+    pub blame_rerun: &'static str,
+    pub blame_hint: &'static str,
+    pub blame_running: &'static str,
+    pub blame_empty: &'static str,
+    pub blame_untracked: &'static str,
+    pub all_modules: &'static str,
+    pub col_author: &'static str,
+    pub col_share: &'static str,
+    pub view_submodules: &'static str,
+    pub submodules_empty: &'static str,
+    pub submodules_hint: &'static str,
+    pub submodule_count: &'static str,
+    pub col_submodule: &'static str,
+    pub submodule_excluded: &'static str,
+    pub view_diagnostics: &'static str,
+    pub diagnostics_empty: &'static str,
+    pub diagnostics_hint: &'static str,
+    pub col_reason: &'static str,
+    pub col_detail: &'static str,
+    pub warn_walk: &'static str,
+    pub warn_read: &'static str,
+    pub warn_encoding: &'static str,
+    pub warn_too_large: &'static str,
+    pub warn_binary: &'static str,
+    pub max_file_size: &'static str,
+    pub max_file_size_hint: &'static str,
+    pub tracked_only: &'static str,
+    pub tracked_only_hint: &'static str,
+    pub perforce: &'static str,
+    pub perforce_hint: &'static str,
+    pub p4_changelist: &'static str,
+    pub p4_changelist_hint: &'static str,
+    pub settings: &'static str,
+    pub appearance: &'static str,
+    pub scan_options: &'static str,
+    pub scan_options_hint: &'static str,
+    pub exclude_dirs: &'static str,
+    pub extensions: &'static str,
+    pub threads: &'static str,
+    pub threads_auto: &'static str,
+    pub restore_defaults: &'static str,
+    pub editor: &'static str,
+    pub editor_hint: &'static str,
+    pub editor_default: &'static str,
+    pub auto_export: &'static str,
+    pub auto_export_enabled: &'static str,
+    pub auto_export_path: &'static str,
+    pub auto_export_hint: &'static str,
+    pub auto_export_failed: &'static str,
+    pub open_failed: &'static str,
+    pub open_in_editor: &'static str,
+    pub reveal_in_file_manager: &'static str,
+    pub copy_path: &'static str,
+    pub file_details: &'static str,
+    pub col_module: &'static str,
+    pub col_plugin: &'static str,
+    pub longest_line: &'static str,
+    pub encoding: &'static str,
+    pub file_size: &'static str,
+    pub last_modified: &'static str,
+    pub accent_color: &'static str,
+    pub accent_custom: &'static str,
+    pub background_darkness: &'static str,
+    pub ui_zoom: &'static str,
+    pub font_size: &'static str,
+    pub heatmap: &'static str,
+    pub heatmap_enabled: &'static str,
+    pub heatmap_hint: &'static str,
+    pub heatmap_low: &'static str,
+    pub heatmap_high: &'static str,
+    pub font: &'static str,
+    pub font_auto: &'static str,
+    pub font_embedded: &'static str,
+    pub font_custom: &'static str,
+    pub font_builtin: &'static str,
+    pub font_in_use: &'static str,
+    pub browse: &'static str,
+    pub language: &'static str,
+    pub theme: &'static str,
+    pub theme_system: &'static str,
+    pub theme_dark: &'static str,
+    pub theme_light: &'static str,
+}
+
+static ZH: Texts = Texts {
+    colon: "：",
+    thousands_sep: ",",
+    heading: "UE 工程代码统计工具",
+    select_dir: "选择 UE 工程目录",
+    no_dir: "尚未选择工程目录",
+    project_path: "工程路径",
+    file_count: "文件数量",
+    total_lines: "总代码行数",
+    code_lines: "有效代码行数",
+    comment_lines: "注释行数",
+    blank_lines: "空行数",
+    metric: "指标",
+    value: "数值",
+    copy_summary: "复制摘要",
+    copy_markdown: "复制为 Markdown",
+    copy_markdown_hint: "复制按语言和按模块的 Markdown 表格，可直接粘贴到 PR 描述中",
+    copied: "已复制到剪贴板",
+    drop_hint: "也可以把工程文件夹直接拖到窗口中",
+    drop_release: "松开鼠标开始统计",
+    col_path: "文件",
+    col_total: "总行数",
+    col_code: "代码",
+    col_comment: "注释",
+    col_blank: "空行",
+    files_unit: "文件",
+    col_comment_ratio: "注释率",
+    col_includes: "#include",
+    col_reflection: "反射宏",
+    columns: "列",
+    tip_path: "相对工程根目录的路径",
+    tip_files: "后缀名在统计范围内、且不在排除目录中的文件数",
+    tip_total: "文件中的全部行，包括空行和注释行",
+    tip_code: "去掉首尾空白后非空、且不以 //、/* 或 * 开头的行；行尾带注释的代码行仍算代码行",
+    tip_comment: "去掉首尾空白后以 //、/* 或 * 开头的行；块注释中不以 * 开头的行会被算作代码行",
+    tip_blank: "为空或只含空白字符的行",
+    tip_comment_ratio: "注释行 ÷（有效代码行 + 注释行）",
+    tip_includes: "以 #include 开头的行，已计入有效代码行",
+    tip_share_of_total: "百分比为占总行数的比例",
+    avg_lines: "平均每文件行数",
+    tip_avg_lines: "总行数 ÷ 文件数",
+    tip_reflection: "以 UCLASS、USTRUCT、UENUM、UPROPERTY、UFUNCTION、GENERATED_BODY 等反射宏开头的行，已计入有效代码行",
+    view_files: "文件列表",
+    largest_files: "最大的文件",
+    filter: "过滤：",
+    filter_hint: "路径片段或通配符，如 **/AbilitySystem/**",
+    filter_invalid: "无效的通配符",
+    view_tree: "目录树",
+    view_modules: "模块排行",
+    view_plugins: "按插件分组",
+    game_project: "游戏工程",
+    view_treemap: "矩形树图",
+    treemap_hint: "方块面积为有效代码行数，点击进入子目录",
+    treemap_loose_files: "（本目录下的文件）",
+    view_history: "历史趋势",
+    history_empty: "该工程还没有历史记录",
+    history_save_failed: "保存历史记录失败",
+    no_module: "（未归属模块）",
+    col_project: "工程",
+    aggregate_tab: "Σ 汇总",
+    aggregate_skipped: "快照、历史版本和重复打开的工程标签页不计入汇总",
+    compare_tab: "⇄ 对比",
+    compare_base: "基准",
+    compare_target: "对比对象",
+    compare_swap: "交换两边",
+    col_delta: "变化",
+    other: "其他",
+    close_tab: "关闭",
+    rescan: "⟳ 重新统计",
+    rescan_hint: "按当前设置重新统计（F5）",
+    menu_file: "文件",
+    menu_help: "帮助",
+    export: "导出",
+    export_again: "按上次的格式导出",
+    export_failed: "导出失败",
+    save_chart: "图表另存为图片…",
+    chart_saved: "图表已保存",
+    export_diff: "导出变化报告",
+    export_warnings: "跳过文件清单 (CSV)",
+    export_warnings_hint: "导出被跳过、无法读取或超过大小限制的文件及原因",
+    diff_added: "增加",
+    diff_removed: "删除",
+    diff_new_files: "新增文件",
+    diff_deleted_files: "删除的文件",
+    export_template: "按模板导出…",
+    export_template_hint: "用 Handlebars 模板自定义版式，数据与 JSON 导出相同；仓库 templates 目录下有示例",
+    all_files: "所有文件",
+    append_series: "追加到 CSV 时间序列…",
+    append_series_hint: "在 CSV 末尾追加一行：时间、提交、文件数和各项行数，新文件先写表头",
+    snapshot: "统计快照",
+    open_snapshot: "打开快照…",
+    save_snapshot: "保存快照…",
+    open_at_commit: "在历史提交上统计",
+    open_at_commit_hint: "输入提交号、分支或标签，从版本库直接读取，不需要检出",
+    open_at_commit_run: "统计",
+    open_at_commit_failed: "无法读取该提交",
+    git_ref: "版本",
+    git_ref_hint: "统计工作区，或不检出、直接从对象库统计某个分支或标签",
+    working_tree: "工作区",
+    view_churn: "变动量",
+    col_churn: "变动量",
+    tip_churn: "统计窗口内 git 历史中增加与删除的行数之和（不含合并提交）",
+    col_last_commit: "最后提交",
+    tip_last_commit: "git 历史中最后一次修改该文件的日期（不含合并提交）；未提交的文件为空",
+    col_last_author: "最后作者",
+    tip_last_author: "最后一次修改该文件的提交的作者",
+    churn_days: "最近天数",
+    churn_run: "计算变动量",
+    churn_hint: "遍历窗口内的每个提交并比较改动，历史较长时可能较慢",
+    churn_empty: "尚未计算变动量；又大又常改的文件往往是需要关注的热点",
+    churn_ratio: "变动量/代码",
+    by_author: "按作者",
+    ownership: "模块归属",
+    ownership_hint: "列出每个模块中有效代码行最多的作者，方便知道该找谁",
+    col_owner: "主要作者",
+    col_authors: "作者数",
+    code_age: "代码年龄",
+    code_age_hint: "按每行最后修改的时间分段统计，找出久未改动的旧代码",
+    age_by_year: "按年",
+    age_by_quarter: "按季度",
+    snapshot_failed: "快照读写失败",
+    focus_filter: "定位到过滤框",
+    shortcuts: "快捷键",
+    mini_mode: "迷你模式：缩成置顶的小窗口",
+    batch_scan: "批量统计…",
+    batch_add: "添加目录…",
+    batch_clear: "清空队列",
+    batch_hint: "排队的目录会在后台依次统计",
+    batch_empty: "队列为空",
+    batch_finished: "已完成",
+    batch_open: "打开",
+    mini_exit: "恢复正常窗口",
+    recent_projects: "最近打开：",
+    clear_recent: "清空",
+    scanned_at: "统计于",
+    report_generated_by: "生成工具",
+    report_languages: "按语言",
+    elapsed: "耗时",
+    warnings: "警告",
+    scanning: "正在统计…",
+    scan_finished: "统计完成",
+    view_trend: "提交趋势",
+    trend_every: "每隔",
+    trend_commits: "个提交",
+    trend_days: "天",
+    trend_max: "最多取样",
+    trend_run: "遍历提交历史",
+    trend_hint: "从 HEAD 沿第一父提交往回取样，逐个从版本库统计，不需要检出",
+    trend_empty: "点击“遍历提交历史”统计各个时间点的行数",
+    trend_export: "导出 CSV",
+    view_changes: "提交间变化",
+    changes_from: "从",
+    changes_to: "到",
+    changes_run: "对比",
+    changes_hint: "对比版本库中两个提交、分支或标签，只读取有变化的文件，不需要检出",
+    changes_empty: "输入两个提交、分支或标签后点击“对比”",
+    changes_files: "个文件有变化",
+    changes_markdown_hint: "复制各模块增减的有效代码行，可直接粘贴到发布说明中",
+    changes_local: "本地改动",
+    changes_local_hint: "对比工作区（含未跟踪的文件）与 HEAD，看推送前本地新增了多少",
+    since_tag: "自上个标签以来",
+    since_tag_hint: "从“到”之前最近的标签开始对比",
+    view_authors: "作者",
+    blame_run: "运行 git blame",
+    blame_rerun: "重新归属",
+    blame_hint: "按 git blame 把每一行归到最后修改它的作者；尚未提交的改动单独列出",
+    blame_running: "正在归属…",
+    blame_empty: "点击“运行 git blame”按作者统计代码行，大工程可能需要几分钟",
+    blame_untracked: "不在 HEAD 中、未归属的文件",
+    all_modules: "全部模块",
+    col_author: "作者",
+    col_share: "占比",
+    view_submodules: "子模块",
+    submodules_empty: "工程中没有 git 子模块",
+    submodules_hint: "子模块多为第三方代码，默认不统计；勾选后重新统计并计入总数",
+    submodule_count: "统计",
+    col_submodule: "子模块",
+    submodule_excluded: "未统计",
+    view_diagnostics: "诊断",
+    diagnostics_empty: "没有跳过或读取异常的文件",
+    diagnostics_hint: "点击查看全部",
+    col_reason: "原因",
+    col_detail: "详情",
+    warn_walk: "目录无法访问",
+    warn_read: "读取失败",
+    warn_encoding: "非 UTF-8 编码（已按替换字符统计）",
+    warn_too_large: "超过大小上限",
+    warn_binary: "疑似二进制文件",
+    max_file_size: "单文件大小上限",
+    max_file_size_hint: "超过的文件跳过，0 为不限",
+    tracked_only: "只统计 git 已跟踪的文件",
+    tracked_only_hint: "未跟踪和被忽略的文件不计入；工程不在 git 仓库中时照常统计全部文件",
+    perforce: "只统计 Perforce 工作区中的文件",
+    perforce_hint: "通过 p4 fstat 取当前工作区视图映射的文件，连接参数来自 P4PORT、P4CLIENT 或 P4CONFIG",
+    p4_changelist: "变更列表",
+    p4_changelist_hint: "全部",
+    settings: "设置",
+    appearance: "外观",
+    scan_options: "扫描",
+    scan_options_hint: "修改后在下一次统计时生效",
+    exclude_dirs: "排除目录（每行一个目录名）",
+    extensions: "统计的文件后缀（逗号分隔）",
+    threads: "线程数",
+    threads_auto: "0 = 自动",
+    restore_defaults: "恢复默认",
+    editor: "外部编辑器",
+    editor_hint: "双击文件时执行，{file} 为文件路径，{line} 为行号",
+    editor_default: "系统默认程序",
+    auto_export: "自动导出",
+    auto_export_enabled: "每次统计完成后导出",
+    auto_export_path: "输出路径",
+    auto_export_hint: "{project} 为工程名，{date} {time} 为统计时间，{ext} 为扩展名；相对路径以工程根目录为准",
+    auto_export_failed: "自动导出失败",
+    open_failed: "无法打开文件",
+    open_in_editor: "在编辑器中打开",
+    reveal_in_file_manager: "在文件管理器中显示",
+    copy_path: "复制路径",
+    file_details: "详细信息",
+    col_module: "模块",
+    col_plugin: "插件",
+    longest_line: "最长一行（字符）",
+    encoding: "编码",
+    file_size: "文件大小",
+    last_modified: "修改时间",
+    accent_color: "强调色",
+    accent_custom: "自定义",
+    background_darkness: "背景加深",
+    ui_zoom: "界面缩放",
+    font_size: "字号",
+    heatmap: "注释率着色",
+    heatmap_enabled: "按注释率给文件和目录着色",
+    heatmap_hint: "红色表示注释偏少，绿色表示注释充分",
+    heatmap_low: "低于此值为红色",
+    heatmap_high: "高于此值为绿色",
+    font: "字体",
+    font_auto: "自动（优先系统字体）",
+    font_embedded: "内置字体",
+    font_custom: "自定义文件",
+    font_builtin: "egui 默认字体（无中文）",
+    font_in_use: "当前使用",
+    browse: "浏览…",
+    language: "语言",
+    theme: "主题",
+    theme_system: "跟随系统",
+    theme_dark: "深色",
+    theme_light: "浅色",
+};
+
+static EN: Texts = Texts {
+    colon: ": ",
+    thousands_sep: ",",
+    heading: "UE Project Code Statistics",
+    select_dir: "Select UE Project Folder",
+    no_dir: "No project folder selected",
+    project_path: "Project path",
+    file_count: "Files",
+    total_lines: "Total lines",
+    code_lines: "Code lines",
+    comment_lines: "Comment lines",
+    blank_lines: "Blank lines",
+    metric: "Metric",
+    value: "Value",
+    copy_summary: "Copy summary",
+    copy_markdown: "Copy as Markdown",
+    copy_markdown_hint: "Copy per-language and per-module Markdown tables, ready to paste into a PR description",
+    copied: "Copied to clipboard",
+    drop_hint: "You can also drag a project folder onto this window",
+    drop_release: "Release to scan",
+    col_path: "File",
+    col_total: "Total",
+    col_code: "Code",
+    col_comment: "Comment",
+    col_blank: "Blank",
+    files_unit: "Files",
+    col_comment_ratio: "Comment %",
+    col_includes: "#include",
+    col_reflection: "Reflection",
+    columns: "Columns",
+    tip_path: "Path relative to the project root",
+    tip_files: "Files whose extension is counted and that are not inside an excluded folder",
+    tip_total: "Every line in the file, including blank and comment lines",
+    tip_code: "Lines that are non-empty after trimming and do not start with //, /* or *; code followed by a trailing comment still counts as code",
+    tip_comment: "Lines that start with //, /* or * after trimming; lines inside a block comment that don't start with * are counted as code",
+    tip_blank: "Lines that are empty or contain only whitespace",
+    tip_comment_ratio: "Comment lines ÷ (code lines + comment lines)",
+    tip_includes: "Lines starting with #include; already included in code lines",
+    tip_share_of_total: "The percentage is the share of total lines",
+    avg_lines: "Average lines per file",
+    tip_avg_lines: "Total lines ÷ file count",
+    tip_reflection: "Lines starting with a UE reflection macro such as UCLASS, USTRUCT, UENUM, UPROPERTY, UFUNCTION or GENERATED_BODY; already included in code lines",
+    view_files: "Files",
+    largest_files: "Largest files",
+    filter: "Filter: ",
+    filter_hint: "Path fragment or glob, e.g. **/AbilitySystem/**",
+    filter_invalid: "Invalid glob pattern",
+    view_tree: "Directory tree",
+    view_modules: "Modules",
+    view_plugins: "By plugin",
+    game_project: "Game project",
+    view_treemap: "Treemap",
+    treemap_hint: "Area is code lines; click a block to zoom into it",
+    treemap_loose_files: "(files in this folder)",
+    view_history: "History",
+    history_empty: "No history recorded for this project yet",
+    history_save_failed: "Failed to save history",
+    no_module: "(no module)",
+    col_project: "Project",
+    aggregate_tab: "Σ Aggregate",
+    aggregate_skipped: "Snapshot, revision and duplicate project tabs are not included",
+    compare_tab: "⇄ Compare",
+    compare_base: "Before",
+    compare_target: "After",
+    compare_swap: "Swap sides",
+    col_delta: "Change",
+    other: "Other",
+    close_tab: "Close",
+    rescan: "⟳ Rescan",
+    rescan_hint: "Scan again with the current settings (F5)",
+    menu_file: "File",
+    menu_help: "Help",
+    export: "Export",
+    export_again: "Export in the last used format",
+    export_failed: "Export failed",
+    save_chart: "Save chart as image…",
+    chart_saved: "Chart saved",
+    export_diff: "Export diff report",
+    export_warnings: "Skipped files (CSV)",
+    export_warnings_hint: "Export the files that were skipped, unreadable or over the size limit, with reasons",
+    diff_added: "Added",
+    diff_removed: "Removed",
+    diff_new_files: "New files",
+    diff_deleted_files: "Deleted files",
+    export_template: "Export with template…",
+    export_template_hint: "Lay out the report with a Handlebars template that receives the same data as the JSON export; see the templates folder for examples",
+    all_files: "All files",
+    append_series: "Append to CSV time series…",
+    append_series_hint: "Adds one row with the time, revision, file count and line totals; new files get a header first",
+    snapshot: "Scan snapshot",
+    open_snapshot: "Open snapshot…",
+    save_snapshot: "Save snapshot…",
+    open_at_commit: "Count at commit",
+    open_at_commit_hint: "Enter a commit, branch or tag; its files are read from the repository without a checkout",
+    open_at_commit_run: "Count",
+    open_at_commit_failed: "Cannot read the commit",
+    git_ref: "Version",
+    git_ref_hint: "Count the working tree, or read a branch or tag straight from the object database without a checkout",
+    working_tree: "Working tree",
+    view_churn: "Churn",
+    col_churn: "Churn",
+    tip_churn: "Lines added plus lines deleted in git history within the window, excluding merge commits",
+    col_last_commit: "Last commit",
+    tip_last_commit: "Date of the last commit that changed the file, merge commits excluded; empty for files never committed",
+    col_last_author: "Last author",
+    tip_last_author: "Author of the last commit that changed the file",
+    churn_days: "Last days",
+    churn_run: "Compute churn",
+    churn_hint: "Walks every commit in the window and diffs it; may be slow on long histories",
+    churn_empty: "Churn not computed yet; large files that change often are hot spots worth a look",
+    churn_ratio: "Churn / code",
+    by_author: "By author",
+    ownership: "Ownership",
+    ownership_hint: "For each module, the author with the most code lines, so you know who to ask",
+    col_owner: "Owner",
+    col_authors: "Authors",
+    code_age: "Code age",
+    code_age_hint: "Lines bucketed by when they last changed, to find legacy code nobody has touched",
+    age_by_year: "By year",
+    age_by_quarter: "By quarter",
+    snapshot_failed: "Snapshot could not be read or written",
+    focus_filter: "Focus the filter box",
+    shortcuts: "Keyboard shortcuts",
+    mini_mode: "Mini mode: shrink to a small always-on-top window",
+    batch_scan: "Batch scan…",
+    batch_add: "Add folders…",
+    batch_clear: "Clear queue",
+    batch_hint: "Queued folders are scanned one after another in the background",
+    batch_empty: "The queue is empty",
+    batch_finished: "Finished",
+    batch_open: "Open",
+    mini_exit: "Back to the full window",
+    recent_projects: "Recent: ",
+    clear_recent: "Clear",
+    scanned_at: "Scanned at",
+    report_generated_by: "Generated by",
+    report_languages: "Languages",
+    elapsed: "Took",
+    warnings: "Warnings",
+    scanning: "Scanning…",
+    scan_finished: "Scan finished",
+    view_trend: "Commit trend",
+    trend_every: "Every",
+    trend_commits: "commits",
+    trend_days: "days",
+    trend_max: "At most",
+    trend_run: "Walk commit history",
+    trend_hint: "Sample commits back from HEAD along first parents and count each straight from the repository, without checking out",
+    trend_empty: "Click \"Walk commit history\" to count the lines at points in the history",
+    trend_export: "Export CSV",
+    view_changes: "Git changes",
+    changes_from: "From",
+    changes_to: "To",
+    changes_run: "Compare",
+    changes_hint: "Compare two commits, branches or tags of the repository; only the files that differ are read, nothing is checked out",
+    changes_empty: "Enter two commits, branches or tags and click Compare",
+    changes_files: "files changed",
+    changes_markdown_hint: "Copy the code lines added and removed per module, ready to paste into release notes",
+    changes_local: "Local changes",
+    changes_local_hint: "Compare the working tree, untracked files included, with HEAD to see what you added locally before pushing",
+    since_tag: "Since last tag",
+    since_tag_hint: "Compare from the most recent tag before the \"To\" ref",
+    view_authors: "Authors",
+    blame_run: "Run git blame",
+    blame_rerun: "Blame again",
+    blame_hint: "Attribute every line to the author who last changed it with git blame; uncommitted changes are listed separately",
+    blame_running: "Blaming…",
+    blame_empty: "Click \"Run git blame\" to count code lines per author; large projects may take a few minutes",
+    blame_untracked: "Files not in HEAD, left out",
+    all_modules: "All modules",
+    col_author: "Author",
+    col_share: "Share",
+    view_submodules: "Submodules",
+    submodules_empty: "The project has no git submodules",
+    submodules_hint: "Submodules are usually third-party code and are not counted by default; tick one to rescan with it included in the totals",
+    submodule_count: "Count",
+    col_submodule: "Submodule",
+    submodule_excluded: "Not counted",
+    view_diagnostics: "Diagnostics",
+    diagnostics_empty: "No files were skipped or failed to read",
+    diagnostics_hint: "Click to see all",
+    col_reason: "Reason",
+    col_detail: "Detail",
+    warn_walk: "Folder not accessible",
+    warn_read: "Read failed",
+    warn_encoding: "Not UTF-8 (counted with replacement characters)",
+    warn_too_large: "Over size limit",
+    warn_binary: "Looks like a binary file",
+    max_file_size: "Max file size",
+    max_file_size_hint: "Larger files are skipped, 0 = no limit",
+    tracked_only: "Count only git-tracked files",
+    tracked_only_hint: "Untracked and ignored files are left out; outside a git repository every file is counted",
+    perforce: "Only files in the Perforce client",
+    perforce_hint: "Lists the files mapped in the current client view with p4 fstat; the connection comes from P4PORT, P4CLIENT or P4CONFIG",
+    p4_changelist: "Changelist",
+    p4_changelist_hint: "all",
+    settings: "Settings",
+    appearance: "Appearance",
+    scan_options: "Scanning",
+    scan_options_hint: "Changes apply to the next scan",
+    exclude_dirs: "Excluded directories (one name per line)",
+    extensions: "File extensions to count (comma separated)",
+    threads: "Threads",
+    threads_auto: "0 = auto",
+    restore_defaults: "Restore defaults",
+    editor: "External editor",
+    editor_hint: "Runs on double-click; {file} is the file path, {line} the line number",
+    editor_default: "System default",
+    auto_export: "Automatic export",
+    auto_export_enabled: "Export after every scan",
+    auto_export_path: "Output path",
+    auto_export_hint: "{project} is the project name, {date} and {time} the scan time, {ext} the extension; relative paths start at the project root",
+    auto_export_failed: "Automatic export failed",
+    open_failed: "Could not open file",
+    open_in_editor: "Open in editor",
+    reveal_in_file_manager: "Reveal in file manager",
+    copy_path: "Copy path",
+    file_details: "Details",
+    col_module: "Module",
+    col_plugin: "Plugin",
+    longest_line: "Longest line (chars)",
+    encoding: "Encoding",
+    file_size: "File size",
+    last_modified: "Last modified",
+    accent_color: "Accent color",
+    accent_custom: "Custom",
+    background_darkness: "Darken background",
+    ui_zoom: "UI scale",
+    font_size: "Font size",
+    heatmap: "Comment heatmap",
+    heatmap_enabled: "Color files and folders by comment ratio",
+    heatmap_hint: "Red means sparsely commented, green means well commented",
+    heatmap_low: "Red below",
+    heatmap_high: "Green above",
+    font: "Font",
+    font_auto: "Auto (system font first)",
+    font_embedded: "Embedded font",
+    font_custom: "Custom file",
+    font_builtin: "egui default font (no CJK)",
+    font_in_use: "In use",
+    browse: "Browse…",
+    language: "Language",
+    theme: "Theme",
+    theme_system: "Follow system",
+    theme_dark: "Dark",
+    theme_light: "Light",
+};
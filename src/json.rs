@@ -0,0 +1,307 @@
+use chrono::{DateTime, Local};
+use serde::{de::Error as _, Deserialize, Serialize};
+use std::{cmp::Reverse, collections::HashMap, path::Path};
+
+use crate::stat::{
+    by_extension, largest_files, DirNode, ExtensionStat, FileStat, LineCounts, ModuleStat,
+    StatResult, SubmoduleStat,
+};
+
+/* ---------------- JSON 报告 ---------------- */
+// 机器可读输出的格式版本；新增字段不改版本，只有删改已有字段时才加一，
+// 下游工具应忽略不认识的字段
+pub const SCHEMA_VERSION: u32 = 1;
+
+// 顶层对象都带上版本号
+#[derive(Serialize)]
+struct Versioned<T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    body: T,
+}
+
+pub fn to_json<T: Serialize>(body: &T) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        body,
+    })
+}
+
+// 图形界面导出和命令行 --json 共用同一结构
+#[derive(Serialize)]
+pub struct JsonReport<'a> {
+    // 统计时给出的工程根目录
+    pub root: &'a Path,
+    // 统计完成的时间，RFC 3339
+    pub scanned_at: Option<DateTime<Local>>,
+    pub file_count: usize,
+    pub totals: &'a LineCounts,
+    // 按扩展名汇总，按有效代码行数从多到少
+    pub languages: Vec<ExtensionStat>,
+    // 按 UE 模块汇总，name 为 null 表示不属于任何模块
+    pub modules: &'a [ModuleStat],
+    // 工程中的 git 子模块，没有时不输出
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub submodules: &'a [SubmoduleStat],
+    // 逐文件结果可能很大，命令行默认不输出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<&'a [FileStat]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_files: Option<Vec<&'a FileStat>>,
+    // 按目录逐级汇总，根节点对应工程根目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<DirNode>,
+}
+
+// 报告中可选的部分
+#[derive(Clone, Copy, Default)]
+pub struct JsonOptions {
+    // 附上逐文件结果
+    pub files: bool,
+    // 附上有效代码行数最多的若干文件
+    pub top: Option<usize>,
+    // 附上目录树
+    pub tree: bool,
+}
+
+impl JsonOptions {
+    // 图形界面导出的完整报告
+    pub fn full() -> Self {
+        Self {
+            files: true,
+            top: None,
+            tree: true,
+        }
+    }
+}
+
+// 读回之前导出的报告作为对比基准，只需要整体数字和模块
+#[derive(Deserialize)]
+pub struct Baseline {
+    // 加入版本号之前导出的报告没有这个字段
+    #[serde(default)]
+    pub schema_version: Option<u32>,
+    #[serde(default)]
+    pub file_count: usize,
+    pub totals: LineCounts,
+    #[serde(default)]
+    pub modules: Vec<ModuleStat>,
+}
+
+pub fn load_baseline(text: &str) -> serde_json::Result<Baseline> {
+    let baseline: Baseline = serde_json::from_str(text)?;
+    match baseline.schema_version {
+        Some(version) if version > SCHEMA_VERSION => Err(serde_json::Error::custom(format!(
+            "schema version {} is newer than the supported version {}",
+            version, SCHEMA_VERSION
+        ))),
+        _ => Ok(baseline),
+    }
+}
+
+// 多个工程一起统计时，逐个列出并附上合计
+#[derive(Serialize)]
+pub struct MultiReport<'a> {
+    pub projects: Vec<JsonReport<'a>>,
+    pub file_count: usize,
+    pub totals: LineCounts,
+}
+
+impl<'a> JsonReport<'a> {
+    pub fn new(root: &'a Path, result: &'a StatResult, opts: JsonOptions) -> Self {
+        Self {
+            root,
+            scanned_at: result.finished_at,
+            file_count: result.files.len(),
+            totals: &result.totals,
+            languages: by_extension(&result.files),
+            modules: &result.modules,
+            submodules: &result.submodules,
+            files: opts.files.then_some(result.files.as_slice()),
+            top_files: opts.top.map(|n| largest_files(&result.files, n)),
+            tree: opts.tree.then(|| {
+                let name = root.file_name().unwrap_or(root.as_os_str());
+                DirNode::build(&name.to_string_lossy(), &result.files)
+            }),
+        }
+    }
+}
+
+pub fn result_json(
+    root: &Path,
+    result: &StatResult,
+    opts: JsonOptions,
+) -> serde_json::Result<String> {
+    to_json(&JsonReport::new(root, result, opts))
+}
+
+impl<'a> MultiReport<'a> {
+    pub fn new(results: &[(&'a Path, &'a StatResult)], opts: JsonOptions) -> Self {
+        let mut totals = LineCounts::default();
+        for (_, result) in results {
+            totals += result.totals;
+        }
+        Self {
+            projects: results
+                .iter()
+                .map(|(root, result)| JsonReport::new(root, result, opts))
+                .collect(),
+            file_count: results.iter().map(|(_, r)| r.files.len()).sum(),
+            totals,
+        }
+    }
+}
+
+pub fn results_json(
+    results: &[(&Path, &StatResult)],
+    opts: JsonOptions,
+) -> serde_json::Result<String> {
+    to_json(&MultiReport::new(results, opts))
+}
+
+/* ---------------- MessagePack ---------------- */
+// 结构与 JSON 报告相同，总是带上逐文件结果，不含可由文件推出的目录树和最大文件；
+// 字段按名称编码，下游仍可按字段名读取
+const MSGPACK_OPTIONS: JsonOptions = JsonOptions {
+    files: true,
+    top: None,
+    tree: false,
+};
+
+pub fn result_msgpack(
+    root: &Path,
+    result: &StatResult,
+) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::to_vec_named(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        body: JsonReport::new(root, result, MSGPACK_OPTIONS),
+    })
+}
+
+pub fn results_msgpack(
+    results: &[(&Path, &StatResult)],
+) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::to_vec_named(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        body: MultiReport::new(results, MSGPACK_OPTIONS),
+    })
+}
+
+/* ---------------- 目录树 JSON ---------------- */
+// 目录逐级嵌套，文件作为叶子；只有叶子带 value（有效代码行数），
+// 可以直接交给 d3.hierarchy(data).sum(d => d.value) 画矩形树图
+#[derive(Serialize)]
+pub struct TreeNode {
+    pub name: String,
+    // 相对工程根目录的路径，以 / 分隔，根节点为空
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<usize>,
+    pub files: usize,
+    pub lines: LineCounts,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn build(root: &Path, files: &[FileStat]) -> Self {
+        let name = root.file_name().unwrap_or(root.as_os_str());
+        let dirs = DirNode::build(&name.to_string_lossy(), files);
+        // 按所在目录（各级目录名）分组，挂到对应的目录节点下
+        let mut by_dir: HashMap<Vec<String>, Vec<&FileStat>> = HashMap::new();
+        for file in files {
+            let dir = Path::new(&file.rel_path)
+                .parent()
+                .into_iter()
+                .flat_map(Path::components)
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            by_dir.entry(dir).or_default().push(file);
+        }
+        Self::from_dir(dirs, &mut Vec::new(), &mut by_dir)
+    }
+
+    fn from_dir(
+        dir: DirNode,
+        path: &mut Vec<String>,
+        by_dir: &mut HashMap<Vec<String>, Vec<&FileStat>>,
+    ) -> Self {
+        let mut children: Vec<TreeNode> = dir
+            .children
+            .into_iter()
+            .map(|child| {
+                path.push(child.name.clone());
+                let node = Self::from_dir(child, path, by_dir);
+                path.pop();
+                node
+            })
+            .collect();
+        let mut leaves = by_dir.remove(path.as_slice()).unwrap_or_default();
+        leaves.sort_by_key(|f| Reverse(f.lines.code));
+        children.extend(leaves.into_iter().map(|file| {
+            TreeNode {
+                name: Path::new(&file.rel_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                path: file.rel_path.replace('\\', "/"),
+                value: Some(file.lines.code),
+                files: 1,
+                lines: file.lines,
+                children: Vec::new(),
+            }
+        }));
+        TreeNode {
+            name: dir.name,
+            path: path.join("/"),
+            value: None,
+            files: dir.files,
+            lines: dir.lines,
+            children,
+        }
+    }
+}
+
+pub fn tree_json(root: &Path, result: &StatResult) -> serde_json::Result<String> {
+    to_json(&TreeNode::build(root, &result.files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOTALS: &str = r#""totals": {"total": 3, "code": 2, "comment": 1, "blank": 0}"#;
+
+    #[test]
+    fn to_json_adds_schema_version() {
+        let json = to_json(&LineCounts::default()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        assert_eq!(value["code"], 0);
+    }
+
+    #[test]
+    fn load_baseline_accepts_current_and_unversioned_reports() {
+        let old = load_baseline(&format!("{{{}, \"file_count\": 2}}", TOTALS)).unwrap();
+        assert_eq!(old.schema_version, None);
+        assert_eq!((old.file_count, old.totals.code), (2, 2));
+        assert!(old.modules.is_empty());
+
+        let current = load_baseline(&format!(
+            "{{\"schema_version\": {}, {}, \"unknown\": true}}",
+            SCHEMA_VERSION, TOTALS
+        ))
+        .unwrap();
+        assert_eq!(current.schema_version, Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn load_baseline_rejects_newer_or_incomplete_reports() {
+        let newer = format!("{{\"schema_version\": {}, {}}}", SCHEMA_VERSION + 1, TOTALS);
+        let error = load_baseline(&newer).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("is newer than the supported version"));
+        assert!(load_baseline("{\"file_count\": 1}").is_err());
+    }
+}
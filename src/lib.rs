@@ -0,0 +1,12 @@
+// 统计核心，图形界面和命令行版本 ueloc 共用
+pub mod authors;
+pub mod badge;
+pub mod cloc;
+pub mod csv;
+pub mod diff;
+pub mod git;
+pub mod json;
+pub mod perforce;
+pub mod snapshot;
+pub mod stat;
+pub mod template;
@@ -1,195 +1,1021 @@
+mod author_table;
+mod capture;
+mod changes;
+mod charts;
+mod churn;
+mod compare;
+mod diagnostics;
+mod file_table;
+mod fonts;
+mod format;
+mod groups;
+mod heatmap;
+mod history;
+#[cfg(feature = "sqlite")]
+mod history_db;
+mod i18n;
+mod notify;
+mod pdf;
+mod pdf_font;
+mod platform;
+mod preview;
+mod project;
+mod queue;
+mod report;
+mod settings;
+mod settings_window;
+mod shortcuts;
+mod submodules;
+mod tree;
+mod treemap;
+mod trend;
+mod xlsx;
+
+use capture::ChartCapture;
+use compare::CompareView;
 use eframe::egui;
+use file_table::FileAction;
+use fonts::{FontSource, LoadedFont};
+use history::History;
+use notify::Toast;
+use project::{dir_display_name, ProjectState, ResultView};
+use queue::{QueueEntry, ScanQueue};
+use report::ExportFormat;
 use rfd::FileDialog;
+use settings::{Palette, Settings, UiScale};
+use settings_window::SettingsWindow;
+// 统计核心放在库中，与命令行版本共用
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    fs, io,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
-use walkdir::WalkDir;
+use ue_loc_gui::{authors, badge, cloc, csv, diff, git, json, snapshot, stat, template};
 
-// 全局排除目录
-const EXCLUDE_DIR: [&str; 4] = ["Intermediate", "Binaries", "Saved", ".vs"];
-// 需要统计的代码文件后缀
-const INCLUDE_EXT: [&str; 3] = ["h", "cpp", "inl"];
+// 窗口标题，同时决定 eframe 持久化数据的存放目录
+const APP_NAME: &str = "UE LOC GUI";
 
-#[derive(Default)]
-struct StatResult {
-    files: usize,
-    total_lines: usize,
-    code_lines: usize,
+// 首次启动的窗口尺寸与正常模式下的最小尺寸
+const DEFAULT_SIZE: egui::Vec2 = egui::vec2(960.0, 720.0);
+const MIN_SIZE: egui::Vec2 = egui::vec2(640.0, 480.0);
+// 迷你模式的窗口尺寸
+const MINI_SIZE: egui::Vec2 = egui::vec2(280.0, 120.0);
+// 退出迷你模式后等待窗口恢复尺寸的最长时间，窗口管理器不响应时照常关闭
+const CLOSE_RESIZE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// 当前选中的标签页
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Project(usize),
+    Aggregate,
+    Compare,
 }
 
+#[derive(Default)]
 struct UELocApp {
-    root_dir: Option<PathBuf>,
-    result: StatResult,
-    font_inited: bool,
+    // 同时打开的多个工程，每个一个标签页
+    projects: Vec<ProjectState>,
+    active: Option<Tab>,
+    // 当前已加载的字体设置，变化时才重新加载
+    applied_font: Option<(FontSource, PathBuf)>,
+    loaded_font: Option<LoadedFont>,
+    // 当前已应用的主题和配色，变化时才重新设置 visuals
+    applied_theme: Option<(eframe::Theme, Palette)>,
+    applied_scale: Option<UiScale>,
+    settings: Settings,
+    settings_window: SettingsWindow,
+    compare: CompareView,
+    capture: ChartCapture,
+    queue: ScanQueue,
+    show_shortcuts: bool,
+    // 文件菜单中输入的历史提交
+    commit_ref: String,
+    history: History,
+    toast: Option<Toast>,
+    // 迷你模式下记住进入前的窗口尺寸，None 表示正常模式
+    mini: Option<egui::Vec2>,
+    // 迷你模式下关闭窗口时先恢复原尺寸，等尺寸生效（或超时）后再关闭，
+    // eframe 保存的才是正常模式的窗口尺寸
+    closing: Option<(egui::Vec2, Instant)>,
+    // 最近一次操作失败的提示，显示在顶栏
+    error: Option<String>,
+    // 供后台统计线程完成后唤醒界面
+    ctx: egui::Context,
 }
 
-impl Default for UELocApp {
-    fn default() -> Self {
+impl UELocApp {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
         Self {
-            root_dir: None,
-            result: StatResult::default(),
-            font_inited: false,
+            settings: Settings::load(cc.storage),
+            history: History::load(eframe::storage_dir(APP_NAME)),
+            ctx: cc.egui_ctx.clone(),
+            ..Default::default()
         }
     }
-}
 
-/* ---------------- 字体初始化 ---------------- */
-fn init_fonts(ctx: &egui::Context) {
-    let mut fonts = egui::FontDefinitions::default();
+    // 已打开的工程重新统计并切过去，否则新开一个标签页
+    fn open_project(&mut self, dir: PathBuf) {
+        self.settings.push_recent(&dir);
+        let idx = match self
+            .projects
+            .iter()
+            .position(|p| p.root_dir == dir && p.is_live())
+        {
+            Some(idx) => {
+                self.projects[idx].rescan(&self.settings.scan_options(&dir), &self.ctx);
+                idx
+            }
+            None => {
+                let opts = self.settings.scan_options(&dir);
+                let state = ProjectState::scan(dir, &opts, &self.ctx);
+                self.projects.push(state);
+                self.projects.len() - 1
+            }
+        };
+        self.active = Some(Tab::Project(idx));
+    }
 
-    fonts.font_data.insert(
-        "wenkai".to_owned(),
-        egui::FontData::from_static(include_bytes!("../fonts/LXGWWenKai-Regular.ttf")),
-    );
+    // 把批量统计的结果放进标签页，已打开的工程直接替换结果
+    fn open_result(&mut self, entry: QueueEntry) {
+        self.settings.push_recent(&entry.root);
+        let idx = match self
+            .projects
+            .iter()
+            .position(|p| p.root_dir == entry.root && p.is_live())
+        {
+            Some(idx) => {
+                self.projects[idx].apply(entry.result);
+                idx
+            }
+            None => {
+                let opts = self.settings.scan_options(&entry.root);
+                let state = ProjectState::from_result(entry.root, entry.result, &opts);
+                self.projects.push(state);
+                self.projects.len() - 1
+            }
+        };
+        self.active = Some(Tab::Project(idx));
+    }
 
-    fonts
-        .families
-        .get_mut(&egui::FontFamily::Proportional)
-        .unwrap()
-        .insert(0, "wenkai".to_owned());
+    // 收取后台统计结果；窗口不在前台时发出完成提醒
+    fn poll_scans(&mut self, ctx: &egui::Context) {
+        let t = self.settings.lang.texts();
+        let focused = ctx.input(|i| i.focused);
+        for i in 0..self.projects.len() {
+            if !self.projects[i].poll() {
+                continue;
+            }
+            self.record_history(i);
+            if !focused {
+                let project = &self.projects[i];
+                let body = format!(
+                    "{}{}{} {} · {} {}",
+                    project.name(),
+                    t.colon,
+                    format::count(project.result.files.len(), t),
+                    t.files_unit,
+                    format::count(project.result.totals.code, t),
+                    t.col_code
+                );
+                notify::desktop_notify(t.scan_finished, &body);
+                self.toast = Some(Toast::new(t.scan_finished, body));
+            }
+        }
+        if let Some(entry) = self.queue.poll(&self.settings, &self.ctx) {
+            if let Some(e) = after_scan(
+                &mut self.history,
+                &self.settings,
+                &entry.root,
+                &entry.result,
+            ) {
+                self.error = Some(e);
+            }
+        }
+    }
 
-    fonts
-        .families
-        .get_mut(&egui::FontFamily::Monospace)
-        .unwrap()
-        .insert(0, "wenkai".to_owned());
+    fn pick_and_open(&mut self) {
+        if let Some(dir) = FileDialog::new().pick_folder() {
+            self.open_project(dir);
+        }
+    }
 
-    ctx.set_fonts(fonts);
-}
+    // 导出当前工程的统计结果，并记住所用格式供 Ctrl+E 使用
+    fn export_active(&mut self, format: ExportFormat) {
+        let Some(Tab::Project(i)) = self.active else {
+            return;
+        };
+        self.settings.export_format = format;
+        let t = self.settings.lang.texts();
+        let project = &self.projects[i];
+        let Some(path) = FileDialog::new()
+            .add_filter(format.name(), &[format.extension()])
+            .set_file_name(format!(
+                "{}.{}",
+                dir_display_name(&project.root_dir),
+                format.extension()
+            ))
+            .save_file()
+        else {
+            return;
+        };
+        let outcome = format
+            .render(&project.root_dir, &project.result, &self.history, t)
+            .and_then(|text| fs::write(&path, text));
+        if let Err(e) = outcome {
+            self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e));
+        }
+    }
 
-/* ---------------- 统计逻辑 ---------------- */
-fn is_comment_or_empty(line: &str) -> bool {
-    let s = line.trim();
-    s.is_empty() || s.starts_with("//") || s.starts_with("/*") || s.starts_with('*')
-}
+    // 先选 Handlebars 模板，再选输出文件；输出扩展名取模板名去掉 .hbs 后的扩展名
+    fn export_with_template(&mut self) {
+        let Some(Tab::Project(i)) = self.active else {
+            return;
+        };
+        let t = self.settings.lang.texts();
+        let mut dialog = FileDialog::new()
+            .add_filter("Handlebars", &["hbs", "handlebars"])
+            .add_filter(t.all_files, &["*"]);
+        if let Some(dir) = self
+            .settings
+            .report_template
+            .as_deref()
+            .and_then(Path::parent)
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(template_path) = dialog.pick_file() else {
+            return;
+        };
+        let name = template_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let extension = Path::new(name.strip_suffix(".hbs").unwrap_or(&name))
+            .extension()
+            .map_or_else(|| "txt".to_owned(), |e| e.to_string_lossy().into_owned());
+        let project = &self.projects[i];
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!(
+                "{}.{}",
+                dir_display_name(&project.root_dir),
+                extension
+            ))
+            .save_file()
+        else {
+            return;
+        };
+        let outcome = fs::read_to_string(&template_path).and_then(|text| {
+            let html = template::is_html(&name);
+            let report = template::render(&text, html, &project.root_dir, &project.result)
+                .map_err(io::Error::other)?;
+            fs::write(&path, report)
+        });
+        self.settings.report_template = Some(template_path);
+        if let Err(e) = outcome {
+            self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e));
+        }
+    }
 
-fn should_skip(path: &Path) -> bool {
-    // 全局排除
-    if path.components().any(|c| {
-        let name = c.as_os_str().to_string_lossy();
-        EXCLUDE_DIR.iter().any(|d| *d == name)
-    }) {
-        return true;
+    // 导出被跳过或读取有问题的文件清单，便于核对扫描范围
+    fn export_warnings(&mut self) {
+        let Some(Tab::Project(i)) = self.active else {
+            return;
+        };
+        let t = self.settings.lang.texts();
+        let project = &self.projects[i];
+        let Some(path) = FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(format!(
+                "{}-skipped.csv",
+                dir_display_name(&project.root_dir)
+            ))
+            .save_file()
+        else {
+            return;
+        };
+        let csv = csv::warnings_csv(&project.root_dir, &project.result.warnings);
+        if let Err(e) = fs::write(&path, csv) {
+            self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e));
+        }
     }
 
-    // Plugins 特殊排除
-    let components: Vec<_> = path.components().collect();
-    for (i, comp) in components.iter().enumerate() {
-        let name = comp.as_os_str().to_string_lossy();
-        if name == "Plugins" {
-            if let Some(next) = components.get(i + 1) {
-                let next_name = next.as_os_str().to_string_lossy();
-                if next_name == "Intermediate" || next_name == "ThirdParty" {
-                    return true;
-                }
+    // 在长期积累的 CSV 末尾追加当前工程的一行摘要，默认沿用上次的文件
+    fn append_series_active(&mut self) {
+        let Some(Tab::Project(i)) = self.active else {
+            return;
+        };
+        let t = self.settings.lang.texts();
+        let project = &self.projects[i];
+        let mut dialog = FileDialog::new().add_filter("CSV", &["csv"]);
+        if let Some(last) = &self.settings.series_csv {
+            if let (Some(dir), Some(name)) = (last.parent(), last.file_name()) {
+                dialog = dialog
+                    .set_directory(dir)
+                    .set_file_name(name.to_string_lossy());
             }
         }
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        let row = csv::series_row(
+            project
+                .result
+                .finished_at
+                .unwrap_or_else(chrono::Local::now),
+            // 分支名记为统计时所指的提交号
+            match &project.revision {
+                Some(revision) => git::resolve_commit(&project.root_dir, revision).ok(),
+                None => git::head_revision(&project.root_dir),
+            }
+            .as_deref(),
+            project.result.files.len(),
+            &project.result.totals,
+        );
+        match csv::append_series(&path, &row) {
+            Ok(()) => self.settings.series_csv = Some(path),
+            Err(e) => self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e)),
+        }
     }
 
-    false
-}
+    // 保存当前工程的完整结果和扫描选项，之后可以原样读回
+    fn save_snapshot(&mut self) {
+        let Some(Tab::Project(i)) = self.active else {
+            return;
+        };
+        let t = self.settings.lang.texts();
+        let project = &self.projects[i];
+        let name = format!(
+            "{} {}.{}",
+            dir_display_name(&project.root_dir),
+            chrono::Local::now().format("%Y-%m-%d"),
+            snapshot::EXTENSION
+        );
+        let Some(path) = FileDialog::new()
+            .add_filter(t.snapshot, &[snapshot::EXTENSION])
+            .set_file_name(name)
+            .save_file()
+        else {
+            return;
+        };
+        let outcome = snapshot::to_bytes(&project.root_dir, &project.options, &project.result)
+            .map_err(io::Error::from)
+            .and_then(|bytes| fs::write(&path, bytes));
+        if let Err(e) = outcome {
+            self.error = Some(format!("{}{}{}", t.snapshot_failed, t.colon, e));
+        }
+    }
 
-fn should_count(path: &Path) -> bool {
-    path.extension()
-        .and_then(|e| e.to_str())
-        .map(|e| INCLUDE_EXT.contains(&e))
-        .unwrap_or(false)
-}
+    // 当前工程在某个历史提交上的统计单独开一个标签页，可以和实时结果对比
+    fn open_at_commit(&mut self) {
+        let t = self.settings.lang.texts();
+        let Some(Tab::Project(i)) = self.active else {
+            return;
+        };
+        let root = self.projects[i].root_dir.clone();
+        match git::resolve_commit(&root, self.commit_ref.trim()) {
+            Ok(revision) => {
+                let opts = self.settings.scan_options(&root);
+                let state = ProjectState::at_revision(root, revision, &opts, &self.ctx);
+                self.projects.push(state);
+                self.active = Some(Tab::Project(self.projects.len() - 1));
+            }
+            Err(e) => {
+                self.error = Some(format!(
+                    "{}{}{}",
+                    t.open_at_commit_failed,
+                    t.colon,
+                    e.message()
+                ))
+            }
+        }
+    }
 
-fn stat_ue_code(root: &Path) -> StatResult {
-    let mut result = StatResult::default();
+    fn pick_snapshot(&mut self) {
+        let t = self.settings.lang.texts();
+        if let Some(path) = FileDialog::new()
+            .add_filter(t.snapshot, &[snapshot::EXTENSION])
+            .pick_file()
+        {
+            self.open_snapshot(path);
+        }
+    }
 
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().is_file())
-    {
-        let path = entry.path();
+    // 快照总是单独开一个标签页，可以和同一工程的实时结果对比
+    fn open_snapshot(&mut self, path: PathBuf) {
+        let t = self.settings.lang.texts();
+        let loaded =
+            fs::read(&path).and_then(|bytes| snapshot::from_slice(&bytes).map_err(io::Error::from));
+        match loaded {
+            Ok(snapshot) => {
+                self.projects
+                    .push(ProjectState::from_snapshot(path, snapshot));
+                self.active = Some(Tab::Project(self.projects.len() - 1));
+            }
+            Err(e) => self.error = Some(format!("{}{}{}", t.snapshot_failed, t.colon, e)),
+        }
+    }
+
+    fn focus_filter(&mut self) {
+        if let Some(Tab::Project(i)) = self.active {
+            self.settings.result_view = ResultView::Files;
+            self.projects[i].focus_filter();
+        }
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let pressed = |s| ctx.input_mut(|i| i.consume_shortcut(&s));
+        if pressed(shortcuts::OPEN) {
+            self.pick_and_open();
+        }
+        if pressed(shortcuts::RESCAN) {
+            self.rescan_active();
+        }
+        if pressed(shortcuts::EXPORT) {
+            self.export_active(self.settings.export_format);
+        }
+        if pressed(shortcuts::FIND) {
+            self.focus_filter();
+        }
+    }
 
-        if should_skip(path) || !should_count(path) {
-            continue;
+    // 缩成置顶的小窗口，只显示当前工程的核心数字
+    fn enter_mini(&mut self, ctx: &egui::Context) {
+        let size = ctx
+            .input(|i| i.viewport().inner_rect)
+            .map_or(DEFAULT_SIZE, |r| r.size());
+        self.mini = Some(size);
+        ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(MINI_SIZE));
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(MINI_SIZE));
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(
+            egui::WindowLevel::AlwaysOnTop,
+        ));
+    }
+
+    fn exit_mini(&mut self, ctx: &egui::Context) {
+        let Some(size) = self.mini.take() else {
+            return;
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(
+            egui::WindowLevel::Normal,
+        ));
+        ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(MIN_SIZE));
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+    }
+
+    // 迷你模式下点了关闭：取消这次关闭，先恢复窗口尺寸
+    fn handle_close(&mut self, ctx: &egui::Context) {
+        if let Some((size, since)) = self.closing {
+            let current = ctx.input(|i| i.viewport().inner_rect).map(|r| r.size());
+            let restored = current.is_some_and(|c| (c - size).length() < 1.0);
+            if restored || since.elapsed() > CLOSE_RESIZE_TIMEOUT {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            } else {
+                ctx.request_repaint();
+            }
+            return;
+        }
+        let Some(size) = self.mini else {
+            return;
+        };
+        if ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.exit_mini(ctx);
+            self.closing = Some((size, Instant::now()));
+            ctx.request_repaint();
+        }
+    }
+
+    fn show_mini(&mut self, ctx: &egui::Context, t: &i18n::Texts) {
+        let idx = match self.active {
+            Some(Tab::Project(i)) => Some(i),
+            _ => (!self.projects.is_empty()).then_some(0),
+        };
+        let mut rescan = false;
+        let mut exit = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.small_button("🗖").on_hover_text(t.mini_exit).clicked() {
+                    exit = true;
+                }
+                if let Some(i) = idx {
+                    let project = &self.projects[i];
+                    if project.is_scanning() {
+                        ui.spinner();
+                    } else if ui.small_button("⟳").on_hover_text(t.rescan).clicked() {
+                        rescan = true;
+                    }
+                    ui.strong(project.name())
+                        .on_hover_text(project.root_dir.display().to_string());
+                }
+            });
+            ui.separator();
+            let Some(i) = idx else {
+                ui.weak(t.no_dir);
+                return;
+            };
+            let result = &self.projects[i].result;
+            egui::Grid::new("mini_grid").num_columns(2).show(ui, |ui| {
+                for (label, value) in [
+                    (t.file_count, result.files.len()),
+                    (t.total_lines, result.totals.total),
+                    (t.code_lines, result.totals.code),
+                ] {
+                    ui.label(label);
+                    ui.strong(format::count(value, t));
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(i) = idx.filter(|_| rescan) {
+            let opts = self.settings.scan_options(&self.projects[i].root_dir);
+            self.projects[i].rescan(&opts, &self.ctx);
+        }
+        if exit {
+            self.exit_mini(ctx);
         }
+    }
 
-        if let Ok(file) = File::open(path) {
-            let reader = BufReader::new(file);
-            result.files += 1;
+    fn show_menu_bar(&mut self, ui: &mut egui::Ui, t: &i18n::Texts) {
+        egui::menu::bar(ui, |ui| {
+            ui.menu_button(t.menu_file, |ui| {
+                if shortcuts::menu_item(ui, t.select_dir, &shortcuts::OPEN) {
+                    self.pick_and_open();
+                }
+                if ui.button(t.batch_scan).clicked() {
+                    self.queue.open = true;
+                    ui.close_menu();
+                }
+                if ui.button(t.open_snapshot).clicked() {
+                    ui.close_menu();
+                    self.pick_snapshot();
+                }
+                let has_project = matches!(self.active, Some(Tab::Project(_)));
+                ui.add_enabled_ui(has_project, |ui| {
+                    if ui.button(t.save_snapshot).clicked() {
+                        ui.close_menu();
+                        self.save_snapshot();
+                    }
+                    ui.menu_button(t.open_at_commit, |ui| {
+                        ui.weak(t.open_at_commit_hint);
+                        let edit = ui.add(
+                            egui::TextEdit::singleline(&mut self.commit_ref)
+                                .hint_text("v1.0, HEAD~50"),
+                        );
+                        let enter =
+                            edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        let ready = !self.commit_ref.trim().is_empty();
+                        let clicked = ui
+                            .add_enabled(ready, egui::Button::new(t.open_at_commit_run))
+                            .clicked();
+                        if ready && (clicked || enter) {
+                            ui.close_menu();
+                            self.open_at_commit();
+                        }
+                    });
+                    if ui
+                        .button(t.append_series)
+                        .on_hover_text(t.append_series_hint)
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.append_series_active();
+                    }
+                    ui.menu_button(t.export, |ui| {
+                        for format in ExportFormat::ALL {
+                            // 快捷键提示标在上次使用的格式上
+                            let clicked = if format == self.settings.export_format {
+                                shortcuts::menu_item(ui, format.name(), &shortcuts::EXPORT)
+                            } else if ui.button(format.name()).clicked() {
+                                ui.close_menu();
+                                true
+                            } else {
+                                false
+                            };
+                            if clicked {
+                                self.export_active(format);
+                            }
+                        }
+                        ui.separator();
+                        if ui
+                            .button(t.export_template)
+                            .on_hover_text(t.export_template_hint)
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_with_template();
+                        }
+                        if ui
+                            .button(t.export_warnings)
+                            .on_hover_text(t.export_warnings_hint)
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_warnings();
+                        }
+                    });
+                });
+            });
+            ui.menu_button(t.menu_help, |ui| {
+                if ui.button(t.shortcuts).clicked() {
+                    self.show_shortcuts = true;
+                    ui.close_menu();
+                }
+            });
 
-            for line in reader.lines().flatten() {
-                result.total_lines += 1;
-                if !is_comment_or_empty(&line) {
-                    result.code_lines += 1;
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .selectable_label(self.settings_window.open, "⚙")
+                    .on_hover_text(t.settings)
+                    .clicked()
+                {
+                    self.settings_window.toggle(&self.settings);
                 }
+                if ui.button("🗕").on_hover_text(t.mini_mode).clicked() {
+                    self.enter_mini(ui.ctx());
+                }
+                if let Some(err) = &self.error {
+                    if ui.small_button("✖").clicked() {
+                        self.error = None;
+                    } else {
+                        ui.colored_label(ui.visuals().error_fg_color, err);
+                    }
+                }
+            });
+        });
+    }
+
+    // 历史提交上的结果不是工程的当前状态，不记入历史
+    fn record_history(&mut self, idx: usize) {
+        let project = &self.projects[idx];
+        if project.revision.is_some() {
+            return;
+        }
+        if let Some(e) = after_scan(
+            &mut self.history,
+            &self.settings,
+            &project.root_dir,
+            &project.result,
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    // 图表图片写完后提示保存位置
+    fn poll_capture(&mut self, ctx: &egui::Context) {
+        let t = self.settings.lang.texts();
+        match self.capture.poll(ctx) {
+            Some(Ok(path)) => {
+                self.toast = Some(Toast::new(t.chart_saved, path.display().to_string()));
+            }
+            Some(Err(e)) => self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e)),
+            None => {}
+        }
+    }
+
+    fn handle_file_action(&mut self, action: FileAction) {
+        let t = self.settings.lang.texts();
+        let outcome = match action {
+            FileAction::OpenInEditor(path) => {
+                platform::open_in_editor(&self.settings.editor_command, &path, 1)
+            }
+            FileAction::Reveal(path) => platform::reveal_in_file_manager(&path),
+        };
+        if let Err(e) = outcome {
+            self.error = Some(format!("{}{}{}", t.open_failed, t.colon, e));
+        }
+    }
+
+    fn apply_ui_scale(&mut self, ctx: &egui::Context) {
+        // egui 自带 Ctrl +/- 缩放快捷键，把快捷键的结果同步回设置
+        if let Some(applied) = self.applied_scale {
+            let zoom = ctx.zoom_factor();
+            if applied == self.settings.ui_scale && (zoom - applied.zoom).abs() > f32::EPSILON {
+                self.settings.ui_scale.zoom = zoom;
+                self.applied_scale = Some(self.settings.ui_scale);
+                return;
+            }
+        }
+
+        let scale = self.settings.ui_scale;
+        if self.applied_scale != Some(scale) {
+            ctx.set_zoom_factor(scale.zoom);
+            scale.apply_text_styles(ctx);
+            self.applied_scale = Some(scale);
+        }
+    }
+
+    // 汇总页和对比页下重新统计所有工程
+    fn rescan_active(&mut self) {
+        let targets: Vec<usize> = match self.active {
+            Some(Tab::Project(i)) => vec![i],
+            Some(Tab::Aggregate | Tab::Compare) => (0..self.projects.len()).collect(),
+            None => Vec::new(),
+        };
+        for i in targets {
+            let opts = self.settings.scan_options(&self.projects[i].root_dir);
+            self.projects[i].rescan(&opts, &self.ctx);
+        }
+    }
+
+    fn close_project(&mut self, idx: usize) {
+        self.projects.remove(idx);
+        self.active = match self.active {
+            _ if self.projects.is_empty() => None,
+            // 只剩一个工程时汇总页和对比页没有意义
+            Some(tab @ (Tab::Aggregate | Tab::Compare)) if self.projects.len() > 1 => Some(tab),
+            Some(Tab::Project(i)) if i < idx => Some(Tab::Project(i)),
+            Some(Tab::Project(i)) if i > idx => Some(Tab::Project(i - 1)),
+            _ => Some(Tab::Project(
+                idx.saturating_sub(1).min(self.projects.len() - 1),
+            )),
+        };
+    }
+
+    fn show_tab_bar(&mut self, ui: &mut egui::Ui, t: &i18n::Texts) {
+        let mut close = None;
+        let mut rescan = false;
+        ui.horizontal_wrapped(|ui| {
+            if ui.button(t.rescan).on_hover_text(t.rescan_hint).clicked() {
+                rescan = true;
+            }
+            ui.separator();
+
+            for (i, project) in self.projects.iter().enumerate() {
+                let selected = self.active == Some(Tab::Project(i));
+                let name = if project.is_scanning() {
+                    format!("⏳ {}", project.name())
+                } else {
+                    project.name()
+                };
+                if ui
+                    .selectable_label(selected, name)
+                    .on_hover_text(project.root_dir.display().to_string())
+                    .clicked()
+                {
+                    self.active = Some(Tab::Project(i));
+                }
+                if ui.small_button("×").on_hover_text(t.close_tab).clicked() {
+                    close = Some(i);
+                }
+                ui.add_space(8.0);
+            }
+            if self.projects.len() > 1 {
+                let selected = self.active == Some(Tab::Aggregate);
+                if ui.selectable_label(selected, t.aggregate_tab).clicked() {
+                    self.active = Some(Tab::Aggregate);
+                }
+                let selected = self.active == Some(Tab::Compare);
+                if ui.selectable_label(selected, t.compare_tab).clicked() {
+                    self.active = Some(Tab::Compare);
+                }
+            }
+        });
+        if rescan {
+            self.rescan_active();
+        }
+        if let Some(i) = close {
+            self.close_project(i);
+        }
+    }
+
+    // 拖入文件夹直接统计；拖入快照文件时读入快照，其他文件（如 .uproject）取其所在目录
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|f| f.path.clone())
+                .collect()
+        });
+
+        // 一次拖入多个文件夹时各开一个标签页
+        for path in dropped {
+            if path.extension().is_some_and(|e| e == snapshot::EXTENSION) {
+                self.open_snapshot(path);
+                continue;
+            }
+            let dir = if path.is_dir() {
+                Some(path)
+            } else {
+                path.parent().map(Path::to_path_buf)
+            };
+            if let Some(dir) = dir {
+                self.open_project(dir);
             }
         }
     }
+}
 
-    result
+// 统计完成后记入历史，再按设置自动导出；返回要显示的错误提示，两步都失败时报前一个
+fn after_scan(
+    history: &mut History,
+    settings: &Settings,
+    root: &Path,
+    result: &stat::StatResult,
+) -> Option<String> {
+    let t = settings.lang.texts();
+    let recorded = history
+        .record(root, result)
+        .map_err(|e| format!("{}{}{}", t.history_save_failed, t.colon, e));
+    let exported = report::auto_export(&settings.auto_export, root, result, history, t)
+        .map_err(|e| format!("{}{}{}", t.auto_export_failed, t.colon, e));
+    recorded.and(exported).err()
 }
 
 /* ---------------- GUI ---------------- */
 impl eframe::App for UELocApp {
-    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
-        if !self.font_inited {
-            init_fonts(ctx);
-            self.font_inited = true;
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let font = (self.settings.font_source, self.settings.font_path.clone());
+        if self.applied_font.as_ref() != Some(&font) {
+            self.loaded_font = Some(fonts::init_fonts(ctx, font.0, &font.1));
+            self.applied_font = Some(font);
+        }
+
+        let theme = self.settings.theme.resolve(frame.info().system_theme);
+        let palette = self.settings.palette;
+        if self.applied_theme != Some((theme, palette)) {
+            let mut visuals = theme.egui_visuals();
+            palette.apply(&mut visuals);
+            ctx.set_visuals(visuals);
+            self.applied_theme = Some((theme, palette));
+        }
+
+        self.apply_ui_scale(ctx);
+
+        let t = self.settings.lang.texts();
+
+        self.poll_scans(ctx);
+        self.poll_capture(ctx);
+        if let Some(toast) = &mut self.toast {
+            if !toast.show(ctx) {
+                self.toast = None;
+            }
+        }
+
+        self.handle_close(ctx);
+        if self.mini.is_some() {
+            self.show_mini(ctx, t);
+            return;
+        }
+
+        // 先处理快捷键，避免 Ctrl+F 等按键被输入框吃掉
+        self.handle_shortcuts(ctx);
+
+        egui::TopBottomPanel::top("top_bar").show(ctx, |ui| self.show_menu_bar(ui, t));
+        self.settings_window
+            .show(ctx, &mut self.settings, self.loaded_font.as_ref());
+        shortcuts::show_help_window(ctx, &mut self.show_shortcuts, t);
+        if let Some(entry) = self.queue.show(ctx, t) {
+            self.open_result(entry);
+        }
+
+        if let Some(Tab::Project(i)) = self.active {
+            let open_diagnostics = egui::TopBottomPanel::bottom("status_bar")
+                .show(ctx, |ui| project::show_status(ui, &self.projects[i], t))
+                .inner;
+            if open_diagnostics {
+                self.settings.result_view = ResultView::Diagnostics;
+            }
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             // 整体居中 + 列式布局
             ui.vertical_centered(|ui| {
                 ui.add_space(20.0);
-                ui.heading("UE 工程代码统计工具");
+                ui.heading(t.heading);
                 ui.add_space(30.0);
 
                 // 选择目录按钮
-                if ui.add_sized([200.0, 40.0], egui::Button::new("选择 UE 工程目录")).clicked() {
-                    if let Some(dir) = FileDialog::new().pick_folder() {
-                        self.result = stat_ue_code(&dir);
-                        self.root_dir = Some(dir);
+                if ui
+                    .add_sized([200.0, 40.0], egui::Button::new(t.select_dir))
+                    .clicked()
+                {
+                    self.pick_and_open();
+                }
+
+                ui.add_space(5.0);
+                ui.weak(t.drop_hint);
+
+                // 最近打开的工程，一键切换
+                if !self.settings.recent_projects.is_empty() {
+                    ui.add_space(10.0);
+                    let mut picked = None;
+                    let mut clear = false;
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(t.recent_projects);
+                        for dir in &self.settings.recent_projects {
+                            let current = self.projects.iter().any(|p| &p.root_dir == dir);
+                            if ui
+                                .selectable_label(current, dir_display_name(dir))
+                                .on_hover_text(dir.display().to_string())
+                                .clicked()
+                            {
+                                picked = Some(dir.clone());
+                            }
+                        }
+                        if ui.small_button(t.clear_recent).clicked() {
+                            clear = true;
+                        }
+                    });
+                    if let Some(dir) = picked {
+                        self.open_project(dir);
+                    }
+                    if clear {
+                        self.settings.recent_projects.clear();
                     }
                 }
 
-                ui.add_space(30.0);
+                ui.add_space(25.0);
                 ui.separator();
                 ui.add_space(20.0);
 
                 // 显示结果
-                if let Some(dir) = &self.root_dir {
-                    ui.group(|ui| {
-                        ui.vertical(|ui| {
-                            ui.add_space(10.0);
-                            ui.label(format!("工程路径：{}", dir.display()));
-                            ui.add_space(5.0);
-                            ui.label(format!("文件数量：{}", self.result.files));
-                            ui.add_space(5.0);
-                            ui.label(format!("总代码行数：{}", self.result.total_lines));
-                            ui.add_space(5.0);
-                            ui.label(format!("有效代码行数：{}", self.result.code_lines));
-                            ui.add_space(10.0);
-                        });
-                    });
-                } else {
+                if self.projects.is_empty() {
                     ui.group(|ui| {
                         ui.centered_and_justified(|ui| {
-                            ui.label("尚未选择工程目录");
+                            ui.label(t.no_dir);
                         });
                     });
+                    return;
+                }
+
+                self.show_tab_bar(ui, t);
+                ui.add_space(10.0);
+                let action = match self.active {
+                    Some(Tab::Project(i)) => {
+                        let project = &mut self.projects[i];
+                        let id = egui::Id::new(&project.root_dir);
+                        let (settings, history) = (&mut self.settings, &self.history);
+                        let capture = &mut self.capture;
+                        ui.push_id(id, |ui| project.show(ui, settings, history, capture))
+                            .inner
+                    }
+                    Some(Tab::Aggregate) => {
+                        project::show_aggregate(ui, &self.projects, &mut self.capture, t);
+                        None
+                    }
+                    Some(Tab::Compare) => {
+                        if let Err(e) = self.compare.show(ui, &self.projects, t) {
+                            self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e));
+                        }
+                        None
+                    }
+                    None => None,
+                };
+                if let Some(action) = action {
+                    self.handle_file_action(action);
                 }
             });
         });
+
+        self.handle_dropped_files(ctx);
+        paint_drop_overlay(ctx, t.drop_release);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.settings.save(storage);
     }
 }
 
+// 拖拽悬停时给整个窗口盖一层半透明提示
+fn paint_drop_overlay(ctx: &egui::Context, text: &str) {
+    if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+        return;
+    }
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("drop_overlay"),
+    ));
+    let rect = ctx.screen_rect();
+    painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(180));
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::FontId::proportional(24.0),
+        egui::Color32::WHITE,
+    );
+}
 
 /* ---------------- main ---------------- */
 fn main() -> eframe::Result<()> {
-    let options = eframe::NativeOptions::default();
+    // 从控制台启动时可用 RUST_LOG=debug 查看统计细节
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    // 窗口位置和大小由 eframe 持久化，这里只决定首次启动时的尺寸
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size(DEFAULT_SIZE)
+            .with_min_inner_size(MIN_SIZE),
+        persist_window: true,
+        ..Default::default()
+    };
 
     eframe::run_native(
-        "UE LOC GUI",
+        APP_NAME,
         options,
-        Box::new(|_cc| Box::new(UELocApp::default())),
+        Box::new(|cc| Box::new(UELocApp::new(cc))),
     )
 }
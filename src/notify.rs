@@ -0,0 +1,59 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/* ---------------- 统计完成提醒 ---------------- */
+// 提示框在窗口重新获得焦点后显示的时长
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+// 窗口右下角的提示，窗口不在前台时不开始计时
+pub struct Toast {
+    title: String,
+    body: String,
+    shown_since: Option<Instant>,
+}
+
+impl Toast {
+    pub fn new(title: &str, body: String) -> Self {
+        Self {
+            title: title.to_owned(),
+            body,
+            shown_since: None,
+        }
+    }
+
+    // 返回 false 表示提示已过期或被关闭
+    pub fn show(&mut self, ctx: &egui::Context) -> bool {
+        if ctx.input(|i| i.focused) {
+            let since = *self.shown_since.get_or_insert_with(Instant::now);
+            if since.elapsed() > TOAST_DURATION {
+                return false;
+            }
+            ctx.request_repaint_after(TOAST_DURATION);
+        }
+
+        let mut open = true;
+        egui::Area::new(egui::Id::new("scan_toast"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -40.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.strong(&self.title);
+                        if ui.small_button("✖").clicked() {
+                            open = false;
+                        }
+                    });
+                    ui.label(&self.body);
+                });
+            });
+        open
+    }
+}
+
+// 系统通知失败（如没有通知服务）时静默忽略，应用内提示仍会显示
+pub fn desktop_notify(title: &str, body: &str) {
+    let _ = notify_rust::Notification::new()
+        .summary(title)
+        .body(body)
+        .show();
+}
@@ -0,0 +1,256 @@
+use log::warn;
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+use std::{f32::consts::PI, path::Path};
+
+use crate::format;
+use crate::pdf_font::{self, CjkFont};
+use crate::project::dir_display_name;
+use crate::stat::{LineCounts, StatResult};
+
+/* ---------------- PDF 摘要 ---------------- */
+// 标签固定为英文，用 PDF 内置的 Helvetica；路径和模块名中含非 ASCII 字符时改用
+// 内置中文字体的子集。编译时没有打包字体的话这些字符显示为 ?
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const MAX_MODULES: usize = 15;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+const CJK: Name = Name(b"F3");
+
+// 代码、注释、空行的颜色，与界面中的饼图一致
+const SLICE_COLORS: [[f32; 3]; 3] = [[0.29, 0.56, 0.85], [0.36, 0.72, 0.36], [0.6, 0.6, 0.6]];
+const BAR_COLOR: [f32; 3] = [0.29, 0.56, 0.85];
+
+fn ascii(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+        .collect()
+}
+
+struct Page {
+    content: Content,
+    cjk: Option<CjkFont>,
+    // 没有中文字体可用、有字符被替换成 ? 时为 true
+    replaced: bool,
+}
+
+impl Page {
+    fn text(&mut self, x: f32, y: f32, size: f32, font: Name, text: &str) {
+        let (font, bytes) = match &mut self.cjk {
+            Some(cjk) if !text.is_ascii() => (CJK, cjk.encode(text)),
+            _ => {
+                self.replaced |= !text.is_ascii();
+                (font, ascii(text))
+            }
+        };
+        self.content
+            .begin_text()
+            .set_font(font, size)
+            .next_line(x, y)
+            .show(Str(&bytes))
+            .end_text();
+    }
+
+    // Helvetica 的数字等宽（0.556 em），按此估算右对齐的位置
+    fn text_right(&mut self, right: f32, y: f32, size: f32, text: &str) {
+        let width = text.chars().count() as f32 * 0.556 * size;
+        self.text(right - width, y, size, REGULAR, text);
+    }
+
+    fn fill(&mut self, color: [f32; 3]) {
+        self.content.set_fill_rgb(color[0], color[1], color[2]);
+    }
+
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, color: [f32; 3]) {
+        self.fill(color);
+        self.content.rect(x, y, w, h).fill_nonzero();
+        self.fill([0.0; 3]);
+    }
+
+    // 扇形用折线近似，每段不超过 5 度
+    fn slice(&mut self, center: (f32, f32), r: f32, from: f32, to: f32, color: [f32; 3]) {
+        let (cx, cy) = center;
+        let steps = ((to - from) / (PI / 36.0)).ceil().max(1.0) as usize;
+        self.fill(color);
+        self.content.move_to(cx, cy);
+        for i in 0..=steps {
+            let a = from + (to - from) * i as f32 / steps as f32;
+            self.content.line_to(cx + r * a.cos(), cy + r * a.sin());
+        }
+        self.content.close_path().fill_nonzero();
+        self.fill([0.0; 3]);
+    }
+}
+
+fn summary(page: &mut Page, result: &StatResult, top: f32) {
+    let totals = &result.totals;
+    let rows = [
+        ("Files", result.files.len(), String::new()),
+        ("Total lines", totals.total, String::new()),
+        (
+            "Code",
+            totals.code,
+            format::percent(totals.code, totals.total),
+        ),
+        (
+            "Comment",
+            totals.comment,
+            format::percent(totals.comment, totals.total),
+        ),
+        (
+            "Blank",
+            totals.blank,
+            format::percent(totals.blank, totals.total),
+        ),
+    ];
+    page.text(MARGIN, top, 13.0, BOLD, "Summary");
+    for (i, (label, value, share)) in rows.iter().enumerate() {
+        let y = top - 22.0 - i as f32 * 18.0;
+        page.text(MARGIN, y, 11.0, REGULAR, label);
+        page.text_right(MARGIN + 190.0, y, 11.0, &value.to_string());
+        page.text_right(MARGIN + 250.0, y, 11.0, share);
+    }
+    let y = top - 22.0 - rows.len() as f32 * 18.0;
+    page.text(MARGIN, y, 11.0, REGULAR, "Comment ratio");
+    page.text_right(
+        MARGIN + 250.0,
+        y,
+        11.0,
+        &format!("{:.1}%", totals.comment_ratio() * 100.0),
+    );
+}
+
+fn pie(page: &mut Page, lines: &LineCounts, top: f32) {
+    let center = (PAGE_WIDTH - MARGIN - 140.0, top - 75.0);
+    let r = 65.0;
+    let parts = [
+        ("Code", lines.code),
+        ("Comment", lines.comment),
+        ("Blank", lines.blank),
+    ];
+    let sum: usize = parts.iter().map(|(_, v)| v).sum();
+    let mut angle = PI / 2.0;
+    for (&(label, value), (i, color)) in parts.iter().zip(SLICE_COLORS.iter().enumerate()) {
+        if sum > 0 && value > 0 {
+            // 顺时针从正上方开始
+            let sweep = value as f32 / sum as f32 * 2.0 * PI;
+            page.slice(center, r, angle - sweep, angle, *color);
+            angle -= sweep;
+        }
+        let y = top - 25.0 - i as f32 * 18.0;
+        let x = center.0 + r + 20.0;
+        page.rect(x, y - 1.0, 9.0, 9.0, *color);
+        page.text(x + 14.0, y, 10.0, REGULAR, label);
+    }
+}
+
+fn modules(page: &mut Page, result: &StatResult, top: f32) {
+    if result.modules.is_empty() {
+        return;
+    }
+    page.text(MARGIN, top, 13.0, BOLD, "Top modules");
+    let header = top - 22.0;
+    page.text(MARGIN, header, 10.0, BOLD, "Module");
+    page.text(MARGIN + 250.0, header, 10.0, BOLD, "Files");
+    page.text(MARGIN + 300.0, header, 10.0, BOLD, "Code");
+    page.text(MARGIN + 360.0, header, 10.0, BOLD, "Comment%");
+
+    let max_code = result
+        .modules
+        .iter()
+        .map(|m| m.lines.code)
+        .max()
+        .unwrap_or(0);
+    for (i, m) in result.modules.iter().take(MAX_MODULES).enumerate() {
+        let y = header - 18.0 - i as f32 * 16.0;
+        let name = m.name.as_deref().unwrap_or("(no module)");
+        let name = match &m.plugin {
+            Some(plugin) => format!("{} ({})", name, plugin),
+            None => name.to_owned(),
+        };
+        page.text(MARGIN, y, 10.0, REGULAR, &name);
+        page.text_right(MARGIN + 280.0, y, 10.0, &m.files.to_string());
+        page.text_right(MARGIN + 345.0, y, 10.0, &m.lines.code.to_string());
+        page.text_right(
+            MARGIN + 405.0,
+            y,
+            10.0,
+            &format!("{:.1}%", m.lines.comment_ratio() * 100.0),
+        );
+        if max_code > 0 {
+            let width =
+                (PAGE_WIDTH - MARGIN - (MARGIN + 420.0)) * m.lines.code as f32 / max_code as f32;
+            page.rect(MARGIN + 420.0, y, width, 8.0, BAR_COLOR);
+        }
+    }
+}
+
+pub fn result_pdf(root: &Path, result: &StatResult) -> Vec<u8> {
+    let mut page = Page {
+        content: Content::new(),
+        cjk: pdf_font::load(),
+        replaced: false,
+    };
+    let mut y = PAGE_HEIGHT - MARGIN - 20.0;
+    page.text(MARGIN, y, 20.0, BOLD, &dir_display_name(root));
+    y -= 18.0;
+    let mut meta = format!(
+        "Generated by {} {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    if let Some(at) = result.finished_at {
+        meta = format!("Scanned at {}  -  {}", at.format("%Y-%m-%d %H:%M:%S"), meta);
+    }
+    page.text(MARGIN, y, 9.0, REGULAR, &meta);
+
+    y -= 40.0;
+    summary(&mut page, result, y);
+    pie(&mut page, &result.totals, y);
+    modules(&mut page, result, y - 170.0);
+
+    let (catalog, pages, page_id, content, regular, bold) = (
+        Ref::new(1),
+        Ref::new(2),
+        Ref::new(3),
+        Ref::new(4),
+        Ref::new(5),
+        Ref::new(6),
+    );
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog).pages(pages);
+    pdf.pages(pages).kids([page_id]).count(1);
+    let cjk = Ref::new(7);
+    let cjk_written = page.cjk.as_ref().is_some_and(|font| {
+        font.write(
+            &mut pdf,
+            [cjk, Ref::new(8), Ref::new(9), Ref::new(10), Ref::new(11)],
+        )
+    });
+    if page.replaced {
+        warn!(
+            "{}: characters outside ASCII are shown as ? in the PDF",
+            root.display()
+        );
+    }
+    let mut writer = pdf.page(page_id);
+    writer
+        .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+        .parent(pages)
+        .contents(content);
+    let mut resources = writer.resources();
+    let mut fonts = resources.fonts();
+    fonts.pair(REGULAR, regular).pair(BOLD, bold);
+    if cjk_written {
+        fonts.pair(CJK, cjk);
+    }
+    drop(fonts);
+    drop(resources);
+    drop(writer);
+    pdf.type1_font(regular).base_font(Name(b"Helvetica"));
+    pdf.type1_font(bold).base_font(Name(b"Helvetica-Bold"));
+    pdf.stream(content, &page.content.finish());
+    pdf.finish()
+}
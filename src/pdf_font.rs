@@ -0,0 +1,284 @@
+use pdf_writer::{
+    types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap},
+    Filter, Name, Pdf, Rect, Ref, Str,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    sync::OnceLock,
+};
+use ttf_parser::{Face, GlyphId, Tag};
+
+use crate::fonts::{EMBEDDED_FONT, SYSTEM_CJK_FONTS};
+
+/* ---------------- PDF 中文字体 ---------------- */
+// 中文字体有两万多个字形，整个嵌入会让一页的摘要有二十多 MB；
+// 只保留用到的字形，其余字形留空，字形编号不变，PDF 中直接以字形编号作为 CID。
+// 嵌入的子集字体名只需带上六个字母的前缀，阅读器不会按名字去找系统字体
+const FONT_NAME: Name = Name(b"UELOCA+CJK");
+
+// 子集中保留的表，已按标签排序；cmap 等排版用的表 PDF 用不到
+const KEPT_TABLES: [&[u8; 4]; 9] = [
+    b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep",
+];
+
+// 打包了内置字体时用内置字体，否则用系统中第一个 TrueType 轮廓的中文字体；
+// 系统字体只读入一次，之后一直保留
+pub fn load() -> Option<CjkFont> {
+    static SYSTEM: OnceLock<Option<&'static [u8]>> = OnceLock::new();
+    let data = EMBEDDED_FONT.or_else(|| {
+        *SYSTEM.get_or_init(|| {
+            SYSTEM_CJK_FONTS.iter().find_map(|path| {
+                let data = fs::read(path).ok()?;
+                has_glyf(&data).then(|| &*data.leak())
+            })
+        })
+    })?;
+    CjkFont::new(data)
+}
+
+fn has_glyf(data: &[u8]) -> bool {
+    Face::parse(data, 0).is_ok_and(|face| face.raw_face().table(Tag::from_bytes(b"glyf")).is_some())
+}
+
+pub struct CjkFont {
+    face: Face<'static>,
+    // 用到的字形及其对应的字符，用于 ToUnicode，复制出的文字才不是乱码
+    used: BTreeMap<u16, char>,
+    encoded: bool,
+}
+
+impl CjkFont {
+    // 只支持 TrueType 轮廓（glyf 表）的字体
+    pub fn new(data: &'static [u8]) -> Option<Self> {
+        let face = Face::parse(data, 0).ok()?;
+        face.raw_face().table(Tag::from_bytes(b"glyf"))?;
+        Some(Self {
+            face,
+            used: BTreeMap::new(),
+            encoded: false,
+        })
+    }
+
+    // 每个字符编码为两字节的字形编号；字体中没有的字符用 0 号字形
+    pub fn encode(&mut self, text: &str) -> Vec<u8> {
+        self.encoded = true;
+        let mut out = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            let gid = self.face.glyph_index(c).map_or(0, |g| g.0);
+            if gid != 0 {
+                self.used.entry(gid).or_insert(c);
+            }
+            out.extend(gid.to_be_bytes());
+        }
+        out
+    }
+
+    // 没有文字用到这个字体时不写入，返回 false
+    pub fn write(&self, pdf: &mut Pdf, refs: [Ref; 5]) -> bool {
+        if !self.encoded {
+            return false;
+        }
+        let Some(file) = subset(&self.face, &self.used.keys().copied().collect()) else {
+            return false;
+        };
+        let [type0, cid, descriptor, file_id, cmap_id] = refs;
+        let scale = 1000.0 / f32::from(self.face.units_per_em());
+        let system_info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+
+        pdf.type0_font(type0)
+            .base_font(FONT_NAME)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid)
+            .to_unicode(cmap_id);
+
+        let mut cid_font = pdf.cid_font(cid);
+        cid_font
+            .subtype(CidFontType::Type2)
+            .base_font(FONT_NAME)
+            .system_info(system_info)
+            .font_descriptor(descriptor)
+            .default_width(1000.0)
+            .cid_to_gid_map_predefined(Name(b"Identity"));
+        let mut widths = cid_font.widths();
+        for &gid in self.used.keys() {
+            let advance = self.face.glyph_hor_advance(GlyphId(gid)).unwrap_or(0);
+            widths.consecutive(gid, [f32::from(advance) * scale]);
+        }
+        drop(widths);
+        drop(cid_font);
+
+        let bbox = self.face.global_bounding_box();
+        let ascent = f32::from(self.face.ascender()) * scale;
+        pdf.font_descriptor(descriptor)
+            .name(FONT_NAME)
+            .flags(FontFlags::SYMBOLIC)
+            .bbox(Rect::new(
+                f32::from(bbox.x_min) * scale,
+                f32::from(bbox.y_min) * scale,
+                f32::from(bbox.x_max) * scale,
+                f32::from(bbox.y_max) * scale,
+            ))
+            .italic_angle(0.0)
+            .ascent(ascent)
+            .descent(f32::from(self.face.descender()) * scale)
+            .cap_height(
+                self.face
+                    .capital_height()
+                    .map_or(ascent, |h| f32::from(h) * scale),
+            )
+            .stem_v(80.0)
+            .font_file2(file_id);
+        // loca 和 hmtx 仍按全部字形保留，压缩后只剩几十 KB
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&file, 6);
+        pdf.stream(file_id, &compressed)
+            .filter(Filter::FlateDecode)
+            .pair(Name(b"Length1"), file.len() as i32);
+
+        let mut cmap = UnicodeCmap::new(Name(b"Custom"), system_info);
+        for (&gid, &c) in &self.used {
+            cmap.pair(gid, c);
+        }
+        pdf.cmap(cmap_id, &cmap.finish());
+        true
+    }
+}
+
+/* ---------------- 字形子集 ---------------- */
+// 重建 glyf 和 loca，未用到的字形长度为 0；loca 一律改为 4 字节的长格式
+fn subset(face: &Face, glyphs: &BTreeSet<u16>) -> Option<Vec<u8>> {
+    let raw = face.raw_face();
+    let table = |tag: &[u8; 4]| raw.table(Tag::from_bytes(tag));
+    let (glyf, loca, head) = (table(b"glyf")?, table(b"loca")?, table(b"head")?);
+    let long = head.get(50..52)? == [0, 1];
+    let count = usize::from(face.number_of_glyphs());
+    let offset = |gid: usize| -> Option<usize> {
+        if long {
+            let b = loca.get(gid * 4..gid * 4 + 4)?;
+            Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        } else {
+            let b = loca.get(gid * 2..gid * 2 + 2)?;
+            Some(usize::from(u16::from_be_bytes([b[0], b[1]])) * 2)
+        }
+    };
+    let glyph = |gid: usize| glyf.get(offset(gid)?..offset(gid + 1)?);
+
+    // 组合字形引用的部件也要保留；0 号字形是缺字时显示的方框
+    let mut keep: BTreeSet<u16> = glyphs.clone();
+    keep.insert(0);
+    let mut stack: Vec<u16> = keep.iter().copied().collect();
+    while let Some(gid) = stack.pop() {
+        for part in components(glyph(usize::from(gid)).unwrap_or_default()) {
+            if usize::from(part) < count && keep.insert(part) {
+                stack.push(part);
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((count + 1) * 4);
+    for gid in 0..count {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        if keep.contains(&(gid as u16)) {
+            new_glyf.extend_from_slice(glyph(gid).unwrap_or_default());
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+    let mut new_head = head.to_vec();
+    // 校验和调整值在整个文件写完后才能算出，阅读器不检查，清零即可
+    new_head[8..12].fill(0);
+    new_head[50..52].copy_from_slice(&[0, 1]);
+
+    let tables: Vec<(&[u8; 4], &[u8])> = KEPT_TABLES
+        .iter()
+        .filter_map(|&tag| {
+            let data: &[u8] = match tag {
+                b"glyf" => &new_glyf,
+                b"loca" => &new_loca,
+                b"head" => &new_head,
+                _ => table(tag)?,
+            };
+            Some((tag, data))
+        })
+        .collect();
+    Some(write_sfnt(&tables))
+}
+
+// 组合字形（轮廓数为负）引用的部件字形
+fn components(glyph: &[u8]) -> Vec<u16> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let word = |at: usize| {
+        glyph
+            .get(at..at + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let mut parts = Vec::new();
+    if word(0).is_none_or(|contours| (contours as i16) >= 0) {
+        return parts;
+    }
+    let mut at = 10;
+    while let (Some(flags), Some(gid)) = (word(at), word(at + 2)) {
+        parts.push(gid);
+        at += 4;
+        at += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            at += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            at += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            at += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    parts
+}
+
+// 按 TrueType 格式写出表目录和各表，每个表按 4 字节对齐
+fn write_sfnt(tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let power = 1u16 << (15 - count.leading_zeros());
+    let mut out = Vec::new();
+    out.extend(0x0001_0000u32.to_be_bytes());
+    out.extend(count.to_be_bytes());
+    out.extend((power * 16).to_be_bytes());
+    out.extend((power.trailing_zeros() as u16).to_be_bytes());
+    out.extend((count * 16 - power * 16).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in tables {
+        out.extend_from_slice(*tag);
+        out.extend(checksum(data).to_be_bytes());
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    out
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
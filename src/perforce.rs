@@ -0,0 +1,152 @@
+use log::info;
+use std::{
+    collections::HashSet,
+    io,
+    path::{self, Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/* ---------------- Perforce 工作区 ---------------- */
+// 通过 p4 命令行取文件列表，连接参数照常来自环境变量和 P4CONFIG；在 root 下运行，
+// 以便找到工作区里的 P4CONFIG 文件
+fn fstat(root: &Path, args: &[&str]) -> io::Result<Vec<PathBuf>> {
+    let output = Command::new("p4")
+        .current_dir(root)
+        .args(["-ztag", "-F", "%clientFile%", "fstat", "-T", "clientFile"])
+        .args(args)
+        .arg("...")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("cannot run p4: {}", e)))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // 目录下没有文件时 p4 也会在 stderr 报 "no such file(s)"，按空列表处理
+    if !output.status.success() && !stderr.contains("no such file") {
+        return Err(io::Error::other(format!("p4 fstat: {}", stderr.trim())));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+// clientFile 为本地绝对路径，换成相对 root 的路径以便与遍历结果对比；
+// root 经过符号链接或大小写与工作区根目录不同时，原样和解析后的路径各比一次
+fn relative(root: &Path, files: Vec<PathBuf>) -> io::Result<HashSet<PathBuf>> {
+    let returned = files.len();
+    let mut roots = vec![path::absolute(root)?];
+    if let Ok(canonical) = root.canonicalize() {
+        roots.push(strip_verbatim(canonical));
+    }
+    let relative: HashSet<PathBuf> = files
+        .into_iter()
+        .filter_map(|f| roots.iter().find_map(|r| strip_root(&f, r)))
+        .collect();
+    // 一个都对不上多半是路径没对上而不是真没有文件，按 p4 出错处理，免得统计结果为空
+    if returned > 0 && relative.is_empty() {
+        return Err(io::Error::other(format!(
+            "p4 returned {} files but none of them are under {}; \
+             check that it lies inside the client root",
+            returned,
+            root.display()
+        )));
+    }
+    Ok(relative)
+}
+
+// Windows 路径不区分大小写，盘符和目录名逐段忽略大小写比较
+fn strip_root(file: &Path, root: &Path) -> Option<PathBuf> {
+    if !cfg!(target_os = "windows") {
+        return file.strip_prefix(root).ok().map(Path::to_path_buf);
+    }
+    let mut rest = file.components();
+    for part in root.components() {
+        let next = rest.next()?;
+        let (next, part) = (
+            next.as_os_str().to_string_lossy(),
+            part.as_os_str().to_string_lossy(),
+        );
+        if !next.eq_ignore_ascii_case(&part) {
+            return None;
+        }
+    }
+    Some(rest.as_path().to_path_buf())
+}
+
+// canonicalize 在 Windows 上返回 \\?\C:\ 形式，p4 给出的是普通的 C:\ 路径
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+        Some(rest) if !rest.starts_with("UNC\\") => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+// 当前工作区视图中映射到 root 之下的文件，路径相对 root
+pub fn client_files(root: &Path) -> io::Result<HashSet<PathBuf>> {
+    let files = relative(root, fstat(root, &["-Rc"])?)?;
+    info!(
+        "{}: {} files in the Perforce client",
+        root.display(),
+        files.len()
+    );
+    Ok(files)
+}
+
+// 在待提交变更列表 change（编号或 default）中打开的、位于 root 之下的文件
+pub fn changelist_files(root: &Path, change: &str) -> io::Result<HashSet<PathBuf>> {
+    let files = relative(root, fstat(root, &["-Ro", "-e", change])?)?;
+    info!(
+        "{}: {} files open in changelist {}",
+        root.display(),
+        files.len(),
+        change
+    );
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_keeps_files_under_root() {
+        let root = std::env::temp_dir().join("ueloc-p4-missing");
+        let files = vec![
+            root.join("Source").join("A.cpp"),
+            root.with_file_name("ueloc-p4-missing-other").join("B.cpp"),
+        ];
+        assert_eq!(
+            relative(&root, files).unwrap(),
+            HashSet::from([Path::new("Source").join("A.cpp")])
+        );
+        assert!(relative(&root, Vec::new()).unwrap().is_empty());
+        // p4 返回了文件却一个都对不上时报错
+        assert!(relative(&root, vec![PathBuf::from("elsewhere/B.cpp")]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_resolves_symlinked_root() {
+        let dir = std::env::temp_dir().join(format!("ueloc-p4-{}", std::process::id()));
+        let (real, link) = (dir.join("real"), dir.join("link"));
+        std::fs::create_dir_all(&real).unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let files = vec![real.canonicalize().unwrap().join("A.cpp")];
+        let relative = relative(&link, files);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(relative.unwrap(), HashSet::from([PathBuf::from("A.cpp")]));
+    }
+
+    #[test]
+    fn strip_verbatim_keeps_unc_paths() {
+        assert_eq!(
+            strip_verbatim(PathBuf::from(r"\\?\C:\ws")),
+            PathBuf::from(r"C:\ws")
+        );
+        assert_eq!(
+            strip_verbatim(PathBuf::from(r"\\?\UNC\server\ws")),
+            PathBuf::from(r"\\?\UNC\server\ws")
+        );
+        assert_eq!(strip_verbatim(PathBuf::from("/ws")), PathBuf::from("/ws"));
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/* ---------------- 外部编辑器 ---------------- */
+// 常用编辑器的命令模板，{file} 为文件路径，{line} 为行号
+pub const EDITOR_PRESETS: [(&str, &str); 3] = [
+    ("Rider", "rider64 --line {line} \"{file}\""),
+    ("Visual Studio", "devenv /edit \"{file}\""),
+    ("VS Code", "code -g \"{file}:{line}\""),
+];
+
+// 按空白切分命令行，双引号内的空白保留
+fn split_command(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+
+    for c in template.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
+
+fn spawn_detached(program: &str, args: &[String]) -> io::Result<()> {
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+// 模板为空时交给系统默认程序打开
+pub fn open_in_editor(template: &str, file: &Path, line: usize) -> io::Result<()> {
+    let mut args = split_command(template);
+    if args.is_empty() {
+        return open_default(file);
+    }
+
+    // 先切分再替换，路径里的空格不会被拆开
+    let file_str = file.display().to_string();
+    let line_str = line.to_string();
+    let mut has_file = false;
+    for arg in &mut args {
+        if arg.contains("{file}") {
+            has_file = true;
+        }
+        *arg = arg
+            .replace("{file}", &file_str)
+            .replace("{line}", &line_str);
+    }
+    if !has_file {
+        args.push(file_str);
+    }
+
+    let program = args.remove(0);
+    spawn_detached(&program, &args)
+}
+
+/* ---------------- 系统默认打开方式 ---------------- */
+// Windows 上不经过 cmd start，免得路径里的 & ^ 等字符被当作命令解析；
+// explorer 对文件按关联程序打开，对目录直接打开文件夹
+pub fn open_default(path: &Path) -> io::Result<()> {
+    let path = path.display().to_string();
+    if cfg!(target_os = "windows") {
+        spawn_detached("explorer", &[path])
+    } else if cfg!(target_os = "macos") {
+        spawn_detached("open", &[path])
+    } else {
+        spawn_detached("xdg-open", &[path])
+    }
+}
+
+/* ---------------- 在文件管理器中显示 ---------------- */
+// Windows 和 macOS 会选中该文件；Linux 各桌面没有统一接口，只打开所在目录
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    if cfg!(target_os = "windows") {
+        spawn_detached("explorer", &[format!("/select,{}", path.display())])
+    } else if cfg!(target_os = "macos") {
+        spawn_detached("open", &["-R".into(), path.display().to_string()])
+    } else {
+        open_default(path.parent().unwrap_or(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_command_keeps_quoted_whitespace() {
+        assert_eq!(
+            split_command(r#"rider64 --line {line} "{file}""#),
+            ["rider64", "--line", "{line}", "{file}"]
+        );
+        assert_eq!(
+            split_command(r#""C:\Program Files\Editor\edit.exe"   -g  "{file}:{line}""#),
+            [r"C:\Program Files\Editor\edit.exe", "-g", "{file}:{line}"]
+        );
+        assert_eq!(
+            split_command(r#"code --profile "" "a b"c"#),
+            ["code", "--profile", "", "a bc"]
+        );
+        assert!(split_command("   ").is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use eframe::egui;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::charts::{BLANK_COLOR, CODE_COLOR, COMMENT_COLOR};
+use crate::i18n::Texts;
+use crate::stat::{classify_line, decode_text, LineKind};
+
+/* ---------------- 文件预览 ---------------- */
+// 只读显示选中文件的内容，行号按统计时的行分类着色
+pub struct Preview {
+    path: PathBuf,
+    lines: Result<Vec<(LineKind, String)>, String>,
+}
+
+fn kind_color(kind: LineKind) -> egui::Color32 {
+    match kind {
+        LineKind::Code => CODE_COLOR,
+        LineKind::Comment => COMMENT_COLOR,
+        LineKind::Blank => BLANK_COLOR,
+    }
+}
+
+impl Preview {
+    pub fn load(path: &Path) -> Self {
+        let lines = fs::read(path)
+            .map(|bytes| {
+                decode_text(bytes)
+                    .0
+                    .lines()
+                    .map(|line| (classify_line(line), line.to_owned()))
+                    .collect()
+            })
+            .map_err(|e| e.to_string());
+        Self {
+            path: path.to_path_buf(),
+            lines,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // 返回 true 表示点击了关闭
+    pub fn show(&self, ui: &mut egui::Ui, t: &Texts) -> bool {
+        let mut close = false;
+        ui.horizontal(|ui| {
+            if ui.small_button("✖").clicked() {
+                close = true;
+            }
+            let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+            ui.strong(name)
+                .on_hover_text(self.path.display().to_string());
+        });
+        ui.horizontal(|ui| {
+            for (label, color) in [
+                (t.col_code, CODE_COLOR),
+                (t.col_comment, COMMENT_COLOR),
+                (t.col_blank, BLANK_COLOR),
+            ] {
+                ui.colored_label(color, "■");
+                ui.label(label);
+            }
+        });
+        ui.separator();
+
+        let lines = match &self.lines {
+            Ok(lines) => lines,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+                return close;
+            }
+        };
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let width = lines.len().to_string().len();
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, lines.len(), |ui, range| {
+                for i in range {
+                    let (kind, text) = &lines[i];
+                    ui.horizontal(|ui| {
+                        let number = format!("{:>width$}", i + 1);
+                        ui.label(
+                            egui::RichText::new(number)
+                                .monospace()
+                                .color(kind_color(*kind)),
+                        );
+                        let mut text = egui::RichText::new(text).monospace();
+                        if *kind == LineKind::Comment {
+                            text = text.color(COMMENT_COLOR);
+                        }
+                        ui.add(egui::Label::new(text).wrap(false));
+                    });
+                }
+            });
+        close
+    }
+}
@@ -0,0 +1,790 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::author_table::AuthorView;
+use crate::capture::ChartCapture;
+use crate::changes::ChangesView;
+use crate::charts;
+use crate::churn::ChurnView;
+use crate::diagnostics;
+use crate::file_table::{FileAction, FileTable, Metric};
+use crate::format;
+use crate::git;
+use crate::groups::{self, PluginGroup};
+use crate::history::History;
+use crate::i18n::Texts;
+use crate::preview::Preview;
+use crate::report;
+use crate::settings::Settings;
+use crate::snapshot::Snapshot;
+use crate::stat::{
+    apply_last_commits, stat_ue_code, DirNode, LastCommit, LineCounts, ScanOptions, ScanProgress,
+    ScanWarning, StatResult, WarningKind,
+};
+use crate::submodules;
+use crate::tree;
+use crate::treemap::Treemap;
+use crate::trend::TrendView;
+
+// Top N 面板最多列出的文件数
+const MAX_TOP_FILES: usize = 100;
+
+// “已复制”提示的显示时长
+const COPIED_HINT_DURATION: Duration = Duration::from_secs(2);
+
+// 目录的简短显示名（最后一级目录名）
+pub fn dir_display_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string())
+}
+
+// 结果区当前显示的视图，所有标签页共用并随设置保存
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResultView {
+    #[default]
+    Files,
+    Tree,
+    Modules,
+    Plugins,
+    Treemap,
+    History,
+    Trend,
+    Changes,
+    Churn,
+    Authors,
+    Submodules,
+    Diagnostics,
+}
+
+// 在后台线程统计，完成后唤醒界面
+pub fn spawn_scan(root: PathBuf, opts: &ScanOptions, ctx: &egui::Context) -> Receiver<StatResult> {
+    let (tx, rx) = mpsc::channel();
+    let opts = opts.clone();
+    let ctx = ctx.clone();
+    thread::spawn(move || {
+        let _ = tx.send(stat_ue_code(&root, &opts));
+        ctx.request_repaint();
+    });
+    rx
+}
+
+// 从对象库统计某个提交；失败时结果为空，原因记为一条警告
+fn spawn_git_scan(
+    root: PathBuf,
+    revision: String,
+    opts: &ScanOptions,
+    ctx: &egui::Context,
+) -> Receiver<StatResult> {
+    let (tx, rx) = mpsc::channel();
+    let opts = opts.clone();
+    let ctx = ctx.clone();
+    thread::spawn(move || {
+        let result = git::stat_git_ref(&root, &revision, &opts, &ScanProgress::default())
+            .unwrap_or_else(|e| StatResult {
+                warnings: vec![ScanWarning {
+                    path: root.clone(),
+                    kind: WarningKind::Read,
+                    message: format!("{}: {}", revision, e.message()),
+                }],
+                ..Default::default()
+            });
+        let _ = tx.send(result);
+        ctx.request_repaint();
+    });
+    rx
+}
+
+// 完整的提交号只显示前 10 位，分支名和标签名原样显示
+fn revision_label(revision: &str) -> String {
+    if revision.len() == 40 && revision.bytes().all(|b| b.is_ascii_hexdigit()) {
+        revision[..10].to_owned()
+    } else {
+        revision.to_owned()
+    }
+}
+
+// 查各文件的最后一次提交，供文件表显示；查不到时只记日志，文件表不显示这两列
+fn spawn_last_commits(
+    root: PathBuf,
+    revision: String,
+    opts: &ScanOptions,
+    ctx: &egui::Context,
+) -> Receiver<HashMap<PathBuf, LastCommit>> {
+    let (tx, rx) = mpsc::channel();
+    let opts = opts.clone();
+    let ctx = ctx.clone();
+    thread::spawn(move || {
+        let commits = git::last_commits(&root, &revision, &opts).unwrap_or_else(|e| {
+            warn!("{}: {}", root.display(), e.message());
+            HashMap::new()
+        });
+        let _ = tx.send(commits);
+        ctx.request_repaint();
+    });
+    rx
+}
+
+/* ---------------- 单个工程的状态 ---------------- */
+pub struct ProjectState {
+    pub root_dir: PathBuf,
+    // 打开时解析一次的根目录，汇总时据此合并同一目录的标签页；每帧解析在网络盘上会卡住界面
+    canonical_root: PathBuf,
+    pub result: StatResult,
+    // 得出当前结果所用的扫描选项，保存快照时一并写入
+    pub options: ScanOptions,
+    // 从快照文件读入时为该文件，这样的标签页不再重新统计
+    pub snapshot: Option<PathBuf>,
+    // 在某个历史提交或分支上统计时为其提交号或引用名，重新统计仍从对象库读取
+    pub revision: Option<String>,
+    // 所在 git 仓库的分支和标签，供切换统计的版本；不在仓库中时为空
+    refs: Vec<String>,
+    file_table: FileTable,
+    dir_tree: DirNode,
+    plugin_groups: Vec<PluginGroup>,
+    treemap: Treemap,
+    // 有效代码行数最多的文件（路径、相对路径、代码行数）
+    largest: Vec<(PathBuf, String, usize)>,
+    copied_at: Option<Instant>,
+    preview: Option<Preview>,
+    authors: AuthorView,
+    trend: TrendView,
+    changes: ChangesView,
+    churn: ChurnView,
+    // 后台统计线程的结果，统计期间仍显示上一次的结果
+    pending: Option<Receiver<StatResult>>,
+    // 各文件的最后一次提交，文件表显示时才在另一个线程查询，先到的结果在换上新统计结果时写回；
+    // None 为还没有查询
+    last_commits: Option<HashMap<PathBuf, LastCommit>>,
+    // last_commits 对应的提交号和扫描选项，提交号和选项都没变时沿用，不再遍历历史
+    commits_key: Option<(String, ScanOptions)>,
+    pending_commits: Option<Receiver<HashMap<PathBuf, LastCommit>>>,
+}
+
+impl ProjectState {
+    pub fn scan(root_dir: PathBuf, opts: &ScanOptions, ctx: &egui::Context) -> Self {
+        let mut state = Self::empty(root_dir, opts.clone());
+        state.rescan(opts, ctx);
+        state
+    }
+
+    // 不检出，直接从对象库读取 revision 中的文件
+    pub fn at_revision(
+        root_dir: PathBuf,
+        revision: String,
+        opts: &ScanOptions,
+        ctx: &egui::Context,
+    ) -> Self {
+        let mut state = Self::empty(root_dir, opts.clone());
+        state.revision = Some(revision);
+        state.rescan(opts, ctx);
+        state
+    }
+
+    // 直接使用已有的统计结果，如批量统计队列里完成的结果
+    pub fn from_result(root_dir: PathBuf, result: StatResult, opts: &ScanOptions) -> Self {
+        let mut state = Self::empty(root_dir, opts.clone());
+        state.apply(result);
+        state
+    }
+
+    pub fn from_snapshot(file: PathBuf, snapshot: Snapshot) -> Self {
+        let mut state = Self::empty(snapshot.root, snapshot.options);
+        state.snapshot = Some(file);
+        state.apply(snapshot.result);
+        state
+    }
+
+    fn empty(root_dir: PathBuf, options: ScanOptions) -> Self {
+        Self {
+            canonical_root: root_dir.canonicalize().unwrap_or_else(|_| root_dir.clone()),
+            root_dir,
+            result: StatResult::default(),
+            options,
+            snapshot: None,
+            revision: None,
+            refs: Vec::new(),
+            file_table: FileTable::default(),
+            dir_tree: DirNode::default(),
+            plugin_groups: Vec::new(),
+            treemap: Treemap::default(),
+            largest: Vec::new(),
+            copied_at: None,
+            preview: None,
+            authors: AuthorView::default(),
+            trend: TrendView::default(),
+            changes: ChangesView::default(),
+            churn: ChurnView::default(),
+            pending: None,
+            last_commits: None,
+            commits_key: None,
+            pending_commits: None,
+        }
+    }
+
+    // 在后台线程重新统计，完成后唤醒界面；正在统计时以新的请求为准
+    pub fn rescan(&mut self, opts: &ScanOptions, ctx: &egui::Context) {
+        if self.snapshot.is_some() {
+            return;
+        }
+        self.options = opts.clone();
+        // 列出引用很快，顺便刷新，新建的分支也能选到
+        self.refs = git::list_refs(&self.root_dir).unwrap_or_default();
+        let root = self.root_dir.clone();
+        if !self.refs.is_empty() {
+            let revision = self.revision.as_deref().unwrap_or("HEAD");
+            let key = git::resolve_commit(&root, revision)
+                .ok()
+                .map(|commit| (commit, opts.clone()));
+            // 换了提交或选项，旧结果和正在进行的查询都作废
+            if key != self.commits_key {
+                self.commits_key = key;
+                self.last_commits = None;
+                self.pending_commits = None;
+            }
+        }
+        self.pending = Some(match &self.revision {
+            Some(revision) => spawn_git_scan(root, revision.clone(), opts, ctx),
+            None => spawn_scan(root, opts, ctx),
+        });
+    }
+
+    // 文件表显示时才查询，在历史很长的仓库里遍历要很久
+    fn request_last_commits(&mut self, ctx: &egui::Context) {
+        if self.last_commits.is_some() || self.pending_commits.is_some() {
+            return;
+        }
+        if let Some((commit, opts)) = &self.commits_key {
+            let root = self.root_dir.clone();
+            self.pending_commits = Some(spawn_last_commits(root, commit.clone(), opts, ctx));
+        }
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    // 每帧调用，统计完成时返回 true
+    pub fn poll(&mut self) -> bool {
+        if let Some(rx) = &self.pending_commits {
+            match rx.try_recv() {
+                Ok(commits) => {
+                    self.pending_commits = None;
+                    apply_last_commits(&mut self.result, &commits);
+                    self.last_commits = Some(commits);
+                    self.file_table.sort(&mut self.result.files);
+                }
+                Err(TryRecvError::Empty) => {}
+                // 查询线程异常退出，同一提交不再重试
+                Err(TryRecvError::Disconnected) => {
+                    self.pending_commits = None;
+                    self.last_commits = Some(HashMap::new());
+                }
+            }
+        }
+        let Some(rx) = &self.pending else {
+            return false;
+        };
+        match rx.try_recv() {
+            Ok(result) => {
+                self.pending = None;
+                self.apply(result);
+                true
+            }
+            Err(TryRecvError::Empty) => false,
+            // 统计线程异常退出，保留原来的结果
+            Err(TryRecvError::Disconnected) => {
+                self.pending = None;
+                false
+            }
+        }
+    }
+
+    // 换上新结果，保留当前视图、排序和过滤条件
+    pub fn apply(&mut self, result: StatResult) {
+        self.result = result;
+        self.preview = None;
+        self.authors = AuthorView::default();
+        self.churn.assign(&mut self.result);
+        if let Some(commits) = &self.last_commits {
+            apply_last_commits(&mut self.result, commits);
+        }
+        self.file_table.sort(&mut self.result.files);
+        self.dir_tree = DirNode::build(&dir_display_name(&self.root_dir), &self.result.files);
+        self.plugin_groups = groups::build_groups(&self.result);
+
+        let mut by_code: Vec<_> = self.result.files.iter().collect();
+        by_code.sort_by_key(|f| Reverse(f.lines.code));
+        self.largest = by_code
+            .into_iter()
+            .take(MAX_TOP_FILES)
+            .map(|f| (f.path.clone(), f.rel_path.clone(), f.lines.code))
+            .collect();
+    }
+
+    fn show_largest(
+        &mut self,
+        ui: &mut egui::Ui,
+        t: &Texts,
+        top_n: &mut usize,
+        view: &mut ResultView,
+    ) {
+        ui.horizontal(|ui| {
+            ui.strong(t.largest_files);
+            ui.add(egui::DragValue::new(top_n).clamp_range(1..=MAX_TOP_FILES));
+        });
+        ui.separator();
+
+        let mut jump = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for (rank, (path, rel_path, code)) in self.largest.iter().take(*top_n).enumerate() {
+                    let name = Path::new(rel_path)
+                        .file_name()
+                        .map_or_else(|| rel_path.clone(), |n| n.to_string_lossy().into_owned());
+                    let text = format!("{}. {}  {}", rank + 1, name, format::count(*code, t));
+                    if ui
+                        .add(
+                            egui::Label::new(text)
+                                .sense(egui::Sense::click())
+                                .truncate(true),
+                        )
+                        .on_hover_text(rel_path)
+                        .clicked()
+                    {
+                        jump = Some(path.clone());
+                    }
+                }
+            });
+
+        // 点击后切到文件列表并定位到该行
+        if let Some(path) = jump {
+            *view = ResultView::Files;
+            self.file_table.reveal(&path, &self.result.files);
+        }
+    }
+
+    // 选中文件时在右侧显示预览，选中项变化时重新读取
+    fn show_preview(&mut self, ui: &mut egui::Ui, t: &Texts) {
+        let Some(selected) = self.file_table.selected() else {
+            self.preview = None;
+            return;
+        };
+        if self.preview.as_ref().map(Preview::path) != Some(selected) {
+            self.preview = Some(Preview::load(selected));
+        }
+        let Some(preview) = &self.preview else {
+            return;
+        };
+
+        let close = egui::SidePanel::right("file_preview")
+            .resizable(true)
+            .default_width(360.0)
+            .show_inside(ui, |ui| preview.show(ui, t))
+            .inner;
+        if close {
+            self.file_table.clear_selection();
+        }
+    }
+
+    pub fn focus_filter(&mut self) {
+        self.file_table.focus_filter();
+    }
+
+    // 快照和历史提交的标签页附上快照文件名或短提交号，与同一工程的实时结果区分
+    pub fn name(&self) -> String {
+        let name = dir_display_name(&self.root_dir);
+        match (&self.snapshot, &self.revision) {
+            (Some(file), _) => format!(
+                "{} 📷 {}",
+                name,
+                file.file_stem().unwrap_or_default().to_string_lossy()
+            ),
+            (None, Some(revision)) => format!("{} @ {}", name, revision_label(revision)),
+            (None, None) => name,
+        }
+    }
+
+    // 切换统计的版本：工作区、HEAD 或任意分支和标签，在当前标签页重新统计
+    fn show_ref_selector(&mut self, ui: &mut egui::Ui, t: &Texts) {
+        let selected = self
+            .revision
+            .as_deref()
+            .map_or(t.working_tree.to_owned(), revision_label);
+        let mut chosen = None;
+        ui.horizontal(|ui| {
+            ui.label(t.git_ref);
+            egui::ComboBox::from_id_source("git_ref")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(self.revision.is_none(), t.working_tree)
+                        .clicked()
+                    {
+                        chosen = Some(None);
+                    }
+                    for name in std::iter::once("HEAD").chain(self.refs.iter().map(String::as_str))
+                    {
+                        if ui
+                            .selectable_label(self.revision.as_deref() == Some(name), name)
+                            .clicked()
+                        {
+                            chosen = Some(Some(name.to_owned()));
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(t.git_ref_hint);
+        });
+        if let Some(revision) = chosen.filter(|r| *r != self.revision) {
+            self.revision = revision;
+            let opts = self.options.clone();
+            self.rescan(&opts, ui.ctx());
+        }
+    }
+
+    // 工作区的实时结果，批量统计和重新打开时只替换这样的标签页
+    pub fn is_live(&self) -> bool {
+        self.snapshot.is_none() && self.revision.is_none()
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        settings: &mut Settings,
+        history: &History,
+        capture: &mut ChartCapture,
+    ) -> Option<FileAction> {
+        let t = settings.lang.texts();
+        // 图表图片的默认文件名，如 MyGame-modules.png
+        let project_name = dir_display_name(&self.root_dir);
+        let image_name = |chart: &str| format!("{}-{}", project_name, chart);
+        let view = &mut settings.result_view;
+        let columns = &mut settings.file_columns;
+        let heatmap = &settings.heatmap;
+        egui::SidePanel::right("largest_files")
+            .resizable(true)
+            .default_width(220.0)
+            .show_inside(ui, |ui| self.show_largest(ui, t, &mut settings.top_n, view));
+
+        if self.snapshot.is_none() && !self.refs.is_empty() {
+            self.show_ref_selector(ui, t);
+        }
+
+        // 数字与环形图并排
+        ui.horizontal(|ui| {
+            show_summary(
+                ui,
+                t,
+                Some(&self.root_dir),
+                self.result.files.len(),
+                &self.result.totals,
+            );
+
+            let donut = charts::line_category_donut(ui, &self.result.totals, t, 180.0);
+            capture.menu(donut, &image_name("lines"), t);
+            let donut = charts::extension_donut(ui, &self.result.files, t, 180.0);
+            capture.menu(donut, &image_name("languages"), t);
+
+            ui.vertical(|ui| {
+                if ui.button(t.copy_summary).clicked() {
+                    let text = report::summary_markdown(&self.root_dir, &self.result, t);
+                    ui.output_mut(|o| o.copied_text = text);
+                    self.copied_at = Some(Instant::now());
+                }
+                if ui
+                    .button(t.copy_markdown)
+                    .on_hover_text(t.copy_markdown_hint)
+                    .clicked()
+                {
+                    let text = report::tables_markdown(&self.result, t);
+                    ui.output_mut(|o| o.copied_text = text);
+                    self.copied_at = Some(Instant::now());
+                }
+                if let Some(at) = self.copied_at {
+                    if at.elapsed() < COPIED_HINT_DURATION {
+                        ui.weak(t.copied);
+                        ui.ctx().request_repaint_after(COPIED_HINT_DURATION);
+                    }
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.selectable_value(view, ResultView::Files, t.view_files);
+            ui.selectable_value(view, ResultView::Tree, t.view_tree);
+            ui.selectable_value(view, ResultView::Modules, t.view_modules);
+            ui.selectable_value(view, ResultView::Plugins, t.view_plugins);
+            ui.selectable_value(view, ResultView::Treemap, t.view_treemap);
+            ui.selectable_value(view, ResultView::History, t.view_history);
+            ui.selectable_value(view, ResultView::Trend, t.view_trend);
+            ui.selectable_value(view, ResultView::Changes, t.view_changes);
+            ui.selectable_value(view, ResultView::Churn, t.view_churn);
+            ui.selectable_value(view, ResultView::Authors, t.view_authors);
+            // 只有含子模块的工程才显示
+            if !self.result.submodules.is_empty() {
+                ui.selectable_value(view, ResultView::Submodules, t.view_submodules);
+            }
+            ui.selectable_value(view, ResultView::Diagnostics, t.view_diagnostics);
+        });
+        ui.add_space(5.0);
+        match view {
+            ResultView::Files => {
+                self.request_last_commits(ui.ctx());
+                self.show_preview(ui, t);
+                return self
+                    .file_table
+                    .show(ui, &mut self.result.files, columns, heatmap, t);
+            }
+            ResultView::Tree => tree::show_tree(ui, &self.dir_tree, t, heatmap),
+            ResultView::Modules => {
+                let chart = charts::module_bar_chart(ui, &self.result.modules, t);
+                capture.menu(chart, &image_name("modules"), t);
+            }
+            ResultView::Plugins => groups::show_groups(ui, &self.plugin_groups, t),
+            ResultView::Treemap => {
+                let chart = self.treemap.show(ui, &self.dir_tree, t);
+                capture.menu(chart, &image_name("treemap"), t);
+            }
+            ResultView::History => {
+                let entries: Vec<_> = history.for_project(&self.root_dir).collect();
+                let chart = charts::history_chart(ui, &entries, t);
+                capture.menu(chart, &image_name("history"), t);
+            }
+            ResultView::Trend => {
+                let chart = self.trend.show(ui, &self.root_dir, &self.options, t);
+                capture.menu(chart, &image_name("trend"), t);
+            }
+            ResultView::Changes => {
+                let files = self.is_live().then_some(self.result.files.as_slice());
+                self.changes
+                    .show(ui, &self.root_dir, files, &self.options, t);
+            }
+            ResultView::Churn => {
+                let (chart, updated) = self.churn.show(ui, &self.root_dir, &mut self.result, t);
+                if updated {
+                    self.file_table.sort(&mut self.result.files);
+                }
+                capture.menu(chart, &image_name("churn"), t);
+            }
+            ResultView::Authors => {
+                let workers = self.options.worker_count();
+                let chart = self
+                    .authors
+                    .show(ui, &self.root_dir, &self.result, workers, t);
+                capture.menu(chart, &image_name("code-age"), t);
+            }
+            ResultView::Submodules => {
+                let include = settings
+                    .submodules
+                    .entry(self.root_dir.clone())
+                    .or_default();
+                if submodules::show_submodules(ui, &self.result.submodules, include, t) {
+                    if include.is_empty() {
+                        settings.submodules.remove(&self.root_dir);
+                    }
+                    self.rescan(&settings.scan_options(&self.root_dir), ui.ctx());
+                }
+            }
+            ResultView::Diagnostics => {
+                diagnostics::show_diagnostics(ui, &self.root_dir, &self.result.warnings, t)
+            }
+        }
+        None
+    }
+}
+
+fn show_summary(
+    ui: &mut egui::Ui,
+    t: &Texts,
+    dir: Option<&Path>,
+    files: usize,
+    totals: &LineCounts,
+) {
+    ui.group(|ui| {
+        ui.vertical(|ui| {
+            ui.add_space(10.0);
+            if let Some(dir) = dir {
+                ui.label(format!("{}{}{}", t.project_path, t.colon, dir.display()));
+                ui.add_space(5.0);
+            }
+            ui.label(format!(
+                "{}{}{}",
+                t.file_count,
+                t.colon,
+                format::count(files, t)
+            ))
+            .on_hover_text(t.tip_files);
+            ui.add_space(5.0);
+            ui.label(format!(
+                "{}{}{}",
+                t.total_lines,
+                t.colon,
+                format::count(totals.total, t)
+            ))
+            .on_hover_text(t.tip_total);
+            // 各类行数后附上占总行数的比例
+            for (label, value, hint) in [
+                (t.code_lines, totals.code, t.tip_code),
+                (t.comment_lines, totals.comment, t.tip_comment),
+                (t.blank_lines, totals.blank, t.tip_blank),
+            ] {
+                ui.add_space(5.0);
+                ui.label(format!(
+                    "{}{}{}    {}",
+                    label,
+                    t.colon,
+                    format::count(value, t),
+                    format::percent(value, totals.total)
+                ))
+                .on_hover_text(format!("{}\n{}", hint, t.tip_share_of_total));
+            }
+            ui.add_space(5.0);
+            ui.label(format!(
+                "{}{}{}",
+                t.avg_lines,
+                t.colon,
+                format::average(totals.total, files)
+            ))
+            .on_hover_text(t.tip_avg_lines);
+            ui.add_space(10.0);
+        });
+    });
+}
+
+/* ---------------- 状态栏 ---------------- */
+// 返回 true 表示点击了警告数，需要切到诊断视图
+pub fn show_status(ui: &mut egui::Ui, project: &ProjectState, t: &Texts) -> bool {
+    let result = &project.result;
+    ui.horizontal(|ui| {
+        if project.is_scanning() {
+            ui.spinner();
+            ui.label(t.scanning);
+            ui.separator();
+        }
+        if let Some(at) = result.finished_at {
+            ui.label(format!(
+                "{}{}{}",
+                t.scanned_at,
+                t.colon,
+                at.format("%Y-%m-%d %H:%M:%S")
+            ));
+            ui.separator();
+        }
+        ui.label(format!(
+            "{}{}{:.2} s",
+            t.elapsed,
+            t.colon,
+            result.elapsed.as_secs_f64()
+        ));
+        ui.separator();
+
+        let warnings = format!("{}{}{}", t.warnings, t.colon, result.warnings.len());
+        if result.warnings.is_empty() {
+            ui.label(warnings);
+            return false;
+        }
+        let text = egui::RichText::new(warnings).color(ui.visuals().warn_fg_color);
+        ui.add(egui::Label::new(text).sense(egui::Sense::click()))
+            .on_hover_ui(|ui| {
+                for w in result.warnings.iter().take(20) {
+                    ui.label(format!(
+                        "{}{}{}",
+                        w.path.display(),
+                        t.colon,
+                        diagnostics::kind_label(w.kind, t)
+                    ));
+                }
+                ui.weak(t.diagnostics_hint);
+            })
+            .clicked()
+    })
+    .inner
+}
+
+/* ---------------- 汇总页 ---------------- */
+pub fn show_aggregate(
+    ui: &mut egui::Ui,
+    projects: &[ProjectState],
+    capture: &mut ChartCapture,
+    t: &Texts,
+) {
+    // 快照和历史版本是同一工程的旧结果，同一目录也可能开了多个标签页，只汇总各目录的当前统计
+    let mut roots = HashSet::new();
+    let counted: Vec<&ProjectState> = projects
+        .iter()
+        .filter(|p| p.is_live())
+        .filter(|p| roots.insert(&p.canonical_root))
+        .collect();
+    let mut totals = LineCounts::default();
+    let mut files = 0;
+    for p in &counted {
+        totals += p.result.totals;
+        files += p.result.files.len();
+    }
+
+    ui.horizontal(|ui| {
+        show_summary(ui, t, None, files, &totals);
+        let donut = charts::line_category_donut(ui, &totals, t, 180.0);
+        capture.menu(donut, "aggregate-lines", t);
+    });
+    if counted.len() < projects.len() {
+        ui.weak(t.aggregate_skipped);
+    }
+
+    ui.add_space(10.0);
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::remainder().at_least(200.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 5)
+        .header(row_height + 6.0, |mut header| {
+            header.col(|ui| {
+                ui.strong(t.col_project);
+            });
+            header.col(|ui| {
+                ui.strong(t.files_unit).on_hover_text(t.tip_files);
+            });
+            for metric in Metric::DEFAULT {
+                header.col(|ui| {
+                    ui.strong(metric.label(t)).on_hover_text(metric.tooltip(t));
+                });
+            }
+        })
+        .body(|mut body| {
+            for p in &counted {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| {
+                        ui.label(p.name())
+                            .on_hover_text(p.root_dir.display().to_string());
+                    });
+                    let lines = &p.result.totals;
+                    for value in [
+                        p.result.files.len(),
+                        lines.total,
+                        lines.code,
+                        lines.comment,
+                        lines.blank,
+                    ] {
+                        row.col(|ui| {
+                            ui.label(format::count(value, t));
+                        });
+                    }
+                });
+            }
+        });
+}
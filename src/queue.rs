@@ -0,0 +1,165 @@
+use eframe::egui;
+use rfd::FileDialog;
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::mpsc::{Receiver, TryRecvError},
+};
+
+use crate::format;
+use crate::i18n::Texts;
+use crate::project::{dir_display_name, spawn_scan};
+use crate::settings::Settings;
+use crate::stat::StatResult;
+
+/* ---------------- 批量统计队列 ---------------- */
+pub struct QueueEntry {
+    pub root: PathBuf,
+    pub result: StatResult,
+}
+
+// 依次在后台统计排队的目录，同一时间只统计一个
+#[derive(Default)]
+pub struct ScanQueue {
+    pub open: bool,
+    waiting: VecDeque<PathBuf>,
+    running: Option<(PathBuf, Receiver<StatResult>)>,
+    // 已完成的结果，最新的在最后
+    finished: Vec<QueueEntry>,
+}
+
+impl ScanQueue {
+    pub fn add(&mut self, dirs: impl IntoIterator<Item = PathBuf>) {
+        for dir in dirs {
+            let queued = self.waiting.contains(&dir)
+                || self.running.as_ref().is_some_and(|(d, _)| *d == dir);
+            if !queued {
+                self.waiting.push_back(dir);
+            }
+        }
+    }
+
+    fn is_busy(&self) -> bool {
+        self.running.is_some() || !self.waiting.is_empty()
+    }
+
+    // 每帧调用：收取当前结果并启动下一个，有目录完成时返回它
+    pub fn poll(&mut self, settings: &Settings, ctx: &egui::Context) -> Option<&QueueEntry> {
+        let mut done = false;
+        if let Some((root, rx)) = &self.running {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.finished.push(QueueEntry {
+                        root: root.clone(),
+                        result,
+                    });
+                    self.running = None;
+                    done = true;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.running = None,
+            }
+        }
+        if self.running.is_none() {
+            if let Some(root) = self.waiting.pop_front() {
+                let rx = spawn_scan(root.clone(), &settings.scan_options(&root), ctx);
+                self.running = Some((root, rx));
+            }
+        }
+        if done {
+            self.finished.last()
+        } else {
+            None
+        }
+    }
+
+    // 返回用户选择在标签页中打开的结果
+    pub fn show(&mut self, ctx: &egui::Context, t: &Texts) -> Option<QueueEntry> {
+        let mut open = self.open;
+        let mut picked = None;
+        egui::Window::new(t.batch_scan)
+            .open(&mut open)
+            .resizable(true)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(t.batch_add).clicked() {
+                        if let Some(dirs) = FileDialog::new().pick_folders() {
+                            self.add(dirs);
+                        }
+                    }
+                    if !self.waiting.is_empty() && ui.button(t.batch_clear).clicked() {
+                        self.waiting.clear();
+                    }
+                });
+                ui.weak(t.batch_hint);
+                ui.separator();
+
+                if let Some((root, _)) = &self.running {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(dir_display_name(root))
+                            .on_hover_text(root.display().to_string());
+                    });
+                }
+                let mut remove = None;
+                for (i, dir) in self.waiting.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("×").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.weak(dir_display_name(dir))
+                            .on_hover_text(dir.display().to_string());
+                    });
+                }
+                if let Some(i) = remove {
+                    self.waiting.remove(i);
+                }
+                if !self.is_busy() && self.finished.is_empty() {
+                    ui.weak(t.batch_empty);
+                }
+
+                if !self.finished.is_empty() {
+                    ui.separator();
+                    ui.strong(t.batch_finished);
+                    picked = self.finished_list(ui, t);
+                }
+            });
+        self.open = open;
+        picked.map(|i| self.finished.remove(i))
+    }
+
+    fn finished_list(&self, ui: &mut egui::Ui, t: &Texts) -> Option<usize> {
+        let mut picked = None;
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("batch_finished")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, entry) in self.finished.iter().enumerate().rev() {
+                            let result = &entry.result;
+                            ui.label(dir_display_name(&entry.root))
+                                .on_hover_text(entry.root.display().to_string());
+                            ui.label(format!(
+                                "{} {}",
+                                t.col_code,
+                                format::count(result.totals.code, t)
+                            ));
+                            if let Some(at) = result.finished_at {
+                                ui.weak(at.format("%m-%d %H:%M").to_string());
+                            } else {
+                                ui.label("");
+                            }
+                            if ui.button(t.batch_open).clicked() {
+                                picked = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        picked
+    }
+}
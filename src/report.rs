@@ -0,0 +1,540 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, fs, io, path::Path};
+
+use crate::badge;
+use crate::charts;
+use crate::cloc;
+use crate::csv;
+use crate::diff::{file_deltas, module_churn, FileDelta};
+use crate::file_table::Metric;
+use crate::format;
+use crate::history::History;
+use crate::i18n::Texts;
+use crate::json::{self, JsonOptions};
+use crate::pdf;
+use crate::project::dir_display_name;
+use crate::settings::AutoExport;
+use crate::stat::{by_extension, largest_files, LineCounts, StatResult};
+use crate::xlsx;
+
+// 导出报告中列出的最大文件数
+const REPORT_TOP_FILES: usize = 10;
+
+/* ---------------- 导出格式 ---------------- */
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    #[default]
+    Markdown,
+    Html,
+    // 与 cloc --xml 相同的结构，供现有的 cloc 解析脚本使用
+    ClocXml,
+    // 只含有效代码行数的 SVG 徽章
+    Badge,
+    // 可打印的单页摘要
+    Pdf,
+    // 汇总、模块、文件、历史记录各一个工作表
+    Xlsx,
+    // 按目录嵌套、文件为叶子的 JSON，供 d3 等外部可视化工具使用
+    TreeJson,
+    // 与 JSON 报告结构相同的二进制格式，总是含逐文件结果
+    MessagePack,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 10] = [
+        ExportFormat::Csv,
+        ExportFormat::Json,
+        ExportFormat::Markdown,
+        ExportFormat::Html,
+        ExportFormat::ClocXml,
+        ExportFormat::Badge,
+        ExportFormat::Pdf,
+        ExportFormat::Xlsx,
+        ExportFormat::TreeJson,
+        ExportFormat::MessagePack,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+            ExportFormat::ClocXml => "cloc XML",
+            ExportFormat::Badge => "SVG badge",
+            ExportFormat::Pdf => "PDF",
+            ExportFormat::Xlsx => "Excel",
+            ExportFormat::TreeJson => "Directory tree JSON",
+            ExportFormat::MessagePack => "MessagePack",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::ClocXml => "xml",
+            ExportFormat::Badge => "svg",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::TreeJson => "json",
+            ExportFormat::MessagePack => "msgpack",
+        }
+    }
+
+    // PDF、Excel 和 MessagePack 是二进制格式，其余格式都是 UTF-8 文本
+    pub fn render(
+        self,
+        root: &Path,
+        result: &StatResult,
+        history: &History,
+        t: &Texts,
+    ) -> io::Result<Vec<u8>> {
+        let text = match self {
+            ExportFormat::Csv => csv::files_csv(result),
+            ExportFormat::Json => json::result_json(root, result, JsonOptions::full())?,
+            ExportFormat::Markdown => result_markdown(root, result, t),
+            ExportFormat::Html => result_html(root, result, t)?,
+            ExportFormat::ClocXml => cloc::xml(&[result]),
+            ExportFormat::Badge => badge::svg(result.totals.code),
+            ExportFormat::TreeJson => json::tree_json(root, result)?,
+            ExportFormat::Pdf => return Ok(pdf::result_pdf(root, result)),
+            ExportFormat::Xlsx => {
+                let history: Vec<_> = history.for_project(root).collect();
+                return xlsx::result_xlsx(root, result, &history);
+            }
+            ExportFormat::MessagePack => {
+                return json::result_msgpack(root, result).map_err(io::Error::other);
+            }
+        };
+        Ok(text.into_bytes())
+    }
+}
+
+// 按自动导出设置写出报告，未启用时什么也不做
+pub fn auto_export(
+    settings: &AutoExport,
+    root: &Path,
+    result: &StatResult,
+    history: &History,
+    t: &Texts,
+) -> io::Result<()> {
+    let at = result.finished_at.unwrap_or_else(Local::now);
+    let Some(path) = settings.target(root, at) else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let bytes = settings.format.render(root, result, history, t)?;
+    fs::write(path, bytes)
+}
+
+/* ---------------- 文本摘要 ---------------- */
+// 生成可直接粘贴到周报里的 Markdown 摘要
+pub fn summary_markdown(root: &Path, result: &StatResult, t: &Texts) -> String {
+    let totals = &result.totals;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "**{}**{}{}", t.project_path, t.colon, root.display());
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| {} | {} |", t.metric, t.value);
+    let _ = writeln!(out, "| --- | ---: |");
+    for (label, value) in [
+        (t.file_count, result.files.len()),
+        (t.total_lines, totals.total),
+    ] {
+        let _ = writeln!(out, "| {} | {} |", label, value);
+    }
+    for (label, value) in [
+        (t.code_lines, totals.code),
+        (t.comment_lines, totals.comment),
+        (t.blank_lines, totals.blank),
+    ] {
+        let share = format::percent(value, totals.total);
+        let _ = writeln!(out, "| {} | {} ({}) |", label, value, share);
+    }
+    let avg = format::average(totals.total, result.files.len());
+    let _ = writeln!(out, "| {} | {} |", t.avg_lines, avg);
+
+    out
+}
+
+// 统计时间和生成工具的版本
+fn generation_info(result: &StatResult, t: &Texts) -> Vec<String> {
+    let mut info = Vec::new();
+    if let Some(at) = result.finished_at {
+        info.push(format!(
+            "{}{}{}",
+            t.scanned_at,
+            t.colon,
+            at.format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+    info.push(format!(
+        "{}{}{} {}",
+        t.report_generated_by,
+        t.colon,
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    ));
+    info
+}
+
+// 完整报告：标题和生成信息、摘要、模块排行和最大的文件
+pub fn result_markdown(root: &Path, result: &StatResult, t: &Texts) -> String {
+    let mut out = format!("# {}\n\n", dir_display_name(root));
+    // 行尾两个空格在 Markdown 中表示换行
+    let _ = writeln!(out, "{}", generation_info(result, t).join("  \n"));
+    let _ = writeln!(out);
+    out += &summary_markdown(root, result, t);
+    modules_markdown(&mut out, result, t);
+    top_files_markdown(&mut out, result, t);
+    out
+}
+
+// 按语言和按模块的表格，直接粘贴到 PR 描述中
+pub fn tables_markdown(result: &StatResult, t: &Texts) -> String {
+    let mut out = String::new();
+    languages_markdown(&mut out, result, t);
+    modules_markdown(&mut out, result, t);
+    out.trim_start().to_owned()
+}
+
+fn languages_markdown(out: &mut String, result: &StatResult, t: &Texts) {
+    let languages = by_extension(&result.files);
+    if languages.is_empty() {
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## {}", t.language);
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} | {} | {} |",
+        t.language, t.files_unit, t.col_total, t.col_code, t.col_comment, t.col_blank
+    );
+    let _ = writeln!(out, "| --- | ---: | ---: | ---: | ---: | ---: |");
+    for language in &languages {
+        let lines = &language.lines;
+        let _ = writeln!(
+            out,
+            "| {} (.{}) | {} | {} | {} | {} | {} |",
+            cloc::language(&language.extension),
+            language.extension,
+            language.files,
+            lines.total,
+            lines.code,
+            lines.comment,
+            lines.blank
+        );
+    }
+}
+
+fn modules_markdown(out: &mut String, result: &StatResult, t: &Texts) {
+    if result.modules.is_empty() {
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## {}", t.view_modules);
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} | {} | {} |",
+        t.view_modules, t.files_unit, t.col_total, t.col_code, t.col_comment, t.col_blank
+    );
+    let _ = writeln!(out, "| --- | ---: | ---: | ---: | ---: | ---: |");
+    for module in &result.modules {
+        let lines = &module.lines;
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} |",
+            charts::module_label(module, t),
+            module.files,
+            lines.total,
+            lines.code,
+            lines.comment,
+            lines.blank
+        );
+    }
+}
+
+// 路径中的竖线会截断表格，需要转义
+fn top_files_markdown(out: &mut String, result: &StatResult, t: &Texts) {
+    let files = largest_files(&result.files, REPORT_TOP_FILES);
+    if files.is_empty() {
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## {}", t.largest_files);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| {} | {} | {} |", t.col_path, t.col_code, t.col_total);
+    let _ = writeln!(out, "| --- | ---: | ---: |");
+    for file in files {
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} |",
+            file.rel_path.replace('|', "\\|"),
+            file.lines.code,
+            file.lines.total
+        );
+    }
+}
+
+/* ---------------- HTML 报告 ---------------- */
+// 单个文件，数据以 JSON 嵌入页面，图表由页面内的脚本绘制，不依赖网络
+const HTML_TEMPLATE: &str = include_str!("../templates/report.html");
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn result_html(root: &Path, result: &StatResult, t: &Texts) -> serde_json::Result<String> {
+    let title = dir_display_name(root);
+    let report = json::JsonReport::new(
+        root,
+        result,
+        JsonOptions {
+            files: false,
+            top: Some(REPORT_TOP_FILES),
+            tree: true,
+        },
+    );
+    let labels = serde_json::json!({
+        "file_count": t.file_count,
+        "total_lines": t.total_lines,
+        "code_lines": t.code_lines,
+        "comment_lines": t.comment_lines,
+        "blank_lines": t.blank_lines,
+        "languages": t.report_languages,
+        "other": t.other,
+        "modules": t.view_modules,
+        "no_module": t.no_module,
+        "files": t.files_unit,
+        "code": t.col_code,
+        "comment": t.col_comment,
+        "total": t.col_total,
+        "path": t.col_path,
+        "treemap": t.view_treemap,
+        "largest_files": t.largest_files,
+    });
+    let data = serde_json::to_string(&serde_json::json!({
+        "title": title,
+        "meta": generation_info(result, t).join(" · "),
+        "labels": labels,
+        "report": report,
+    }))?;
+    // 路径里的 </script> 会提前结束脚本
+    let data = data.replace("</", "<\\/");
+    Ok(HTML_TEMPLATE
+        .replace("{{TITLE}}", &html_escape(&title))
+        .replace("{{DATA}}", &data))
+}
+
+/* ---------------- 对比报告 ---------------- */
+// 对比视图导出的变化报告：整体指标、各模块增减、新增和删除的文件
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    Markdown,
+    Html,
+}
+
+impl DiffFormat {
+    pub const ALL: [DiffFormat; 2] = [DiffFormat::Markdown, DiffFormat::Html];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DiffFormat::Markdown => "Markdown",
+            DiffFormat::Html => "HTML",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            DiffFormat::Markdown => "md",
+            DiffFormat::Html => "html",
+        }
+    }
+}
+
+struct Table {
+    title: String,
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+// 与 summary_markdown 一样写原始数字，千位分隔符只用于界面显示
+fn metric_text(metric: Metric, lines: &LineCounts) -> String {
+    match metric {
+        Metric::CommentRatio => format!("{:.1}%", lines.comment_ratio() * 100.0),
+        _ => (metric.value(lines) as usize).to_string(),
+    }
+}
+
+// 注释率的变化按百分点写出
+fn metric_delta(metric: Metric, before: &LineCounts, after: &LineCounts) -> String {
+    let delta = metric.value(after) - metric.value(before);
+    match metric {
+        Metric::CommentRatio => format!("{:+.1}%", delta * 100.0),
+        _ => format!("{:+}", delta as i64),
+    }
+}
+
+fn diff_tables(before: &StatResult, after: &StatResult, t: &Texts) -> Vec<Table> {
+    let mut summary = vec![vec![
+        t.files_unit.to_owned(),
+        before.files.len().to_string(),
+        after.files.len().to_string(),
+        format!("{:+}", after.files.len() as i64 - before.files.len() as i64),
+    ]];
+    for metric in Metric::ALL {
+        let (a, b) = (&before.totals, &after.totals);
+        summary.push(vec![
+            metric.label(t).to_owned(),
+            metric_text(metric, a),
+            metric_text(metric, b),
+            metric_delta(metric, a, b),
+        ]);
+    }
+
+    let files = file_deltas(&before.files, &after.files);
+    let modules = module_churn(&files)
+        .iter()
+        .map(|m| {
+            vec![
+                charts::module_name(m.name.as_deref(), m.plugin.as_deref(), t),
+                format!("{:+}", m.added as i64),
+                format!("{:+}", -(m.removed as i64)),
+                format!("{:+}", m.added as i64 - m.removed as i64),
+                m.new_files.to_string(),
+                m.deleted_files.to_string(),
+            ]
+        })
+        .collect();
+    let listed = |side: fn(&FileDelta) -> Option<LineCounts>| {
+        files
+            .iter()
+            .filter_map(|f| side(f).map(|lines| vec![f.rel_path.clone(), lines.code.to_string()]))
+            .collect()
+    };
+    let new_files = listed(|f| f.after.filter(|_| f.before.is_none()));
+    let deleted_files = listed(|f| f.before.filter(|_| f.after.is_none()));
+
+    vec![
+        Table {
+            title: t.metric.to_owned(),
+            headers: vec![t.metric, t.compare_base, t.compare_target, t.col_delta],
+            rows: summary,
+        },
+        Table {
+            title: t.view_modules.to_owned(),
+            headers: vec![
+                t.view_modules,
+                t.diff_added,
+                t.diff_removed,
+                t.col_delta,
+                t.diff_new_files,
+                t.diff_deleted_files,
+            ],
+            rows: modules,
+        },
+        Table {
+            title: t.diff_new_files.to_owned(),
+            headers: vec![t.col_path, t.col_code],
+            rows: new_files,
+        },
+        Table {
+            title: t.diff_deleted_files.to_owned(),
+            headers: vec![t.col_path, t.col_code],
+            rows: deleted_files,
+        },
+    ]
+}
+
+// 模块的增减按文件计：行数增加的文件记入增加，减少的记入删除
+pub fn diff_report(
+    format: DiffFormat,
+    (before_name, before): (&str, &StatResult),
+    (after_name, after): (&str, &StatResult),
+    t: &Texts,
+) -> String {
+    let title = format!("{} → {}", before_name, after_name);
+    let info = generation_info(after, t);
+    let tables = diff_tables(before, after, t);
+    match format {
+        DiffFormat::Markdown => {
+            let mut out = format!("# {}\n\n{}\n", title, info.join("  \n"));
+            for table in tables.iter().filter(|t| !t.rows.is_empty()) {
+                let _ = writeln!(out, "\n## {}\n", table.title);
+                let _ = writeln!(out, "| {} |", table.headers.join(" | "));
+                let align: Vec<&str> = (0..table.headers.len())
+                    .map(|i| if i == 0 { "---" } else { "---:" })
+                    .collect();
+                let _ = writeln!(out, "| {} |", align.join(" | "));
+                for row in &table.rows {
+                    let cells: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+                    let _ = writeln!(out, "| {} |", cells.join(" | "));
+                }
+            }
+            out
+        }
+        DiffFormat::Html => {
+            let mut out = format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+                 <style>body {{ font-family: system-ui, sans-serif; margin: 24px; }} \
+                 table {{ border-collapse: collapse; margin-bottom: 20px; }} \
+                 th, td {{ padding: 4px 10px; border-bottom: 1px solid #ddd; text-align: right; }} \
+                 th:first-child, td:first-child {{ text-align: left; }} \
+                 .meta {{ color: #777; font-size: 13px; }}</style>\n</head>\n<body>\n\
+                 <h1>{0}</h1>\n<p class=\"meta\">{1}</p>\n",
+                html_escape(&title),
+                html_escape(&info.join(" · "))
+            );
+            for table in tables.iter().filter(|t| !t.rows.is_empty()) {
+                let _ = writeln!(out, "<h2>{}</h2>\n<table>", html_escape(&table.title));
+                let headers: Vec<String> = table
+                    .headers
+                    .iter()
+                    .map(|h| format!("<th>{}</th>", html_escape(h)))
+                    .collect();
+                let _ = writeln!(out, "<tr>{}</tr>", headers.concat());
+                for row in &table.rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|c| format!("<td>{}</td>", html_escape(c)))
+                        .collect();
+                    let _ = writeln!(out, "<tr>{}</tr>", cells.concat());
+                }
+                out += "</table>\n";
+            }
+            out + "</body>\n</html>\n"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_numbers_are_not_grouped() {
+        let lines = |code| LineCounts {
+            total: code,
+            code,
+            ..Default::default()
+        };
+        assert_eq!(metric_text(Metric::Code, &lines(12_345)), "12345");
+        assert_eq!(
+            metric_delta(Metric::Code, &lines(12_345), &lines(10_000)),
+            "-2345"
+        );
+        assert_eq!(metric_delta(Metric::Total, &lines(5), &lines(5)), "+0");
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::{DateTime, Local};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::file_table::Metric;
+use crate::fonts::FontSource;
+use crate::heatmap::Heatmap;
+use crate::i18n::{Lang, Texts};
+use crate::project::{dir_display_name, ResultView};
+use crate::report::ExportFormat;
+use crate::stat::ScanOptions;
+
+// eframe 持久化存储中的键名
+pub const STORAGE_KEY: &str = "settings";
+// 最近打开的工程最多保留的条数
+const MAX_RECENT: usize = 8;
+
+/* ---------------- 主题 ---------------- */
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemePref {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl ThemePref {
+    pub const ALL: [ThemePref; 3] = [ThemePref::System, ThemePref::Dark, ThemePref::Light];
+
+    pub fn label(self, t: &Texts) -> &'static str {
+        match self {
+            ThemePref::System => t.theme_system,
+            ThemePref::Dark => t.theme_dark,
+            ThemePref::Light => t.theme_light,
+        }
+    }
+
+    // 跟随系统时，拿不到系统主题就按深色处理（与 egui 默认一致）
+    pub fn resolve(self, system: Option<eframe::Theme>) -> eframe::Theme {
+        match self {
+            ThemePref::System => system.unwrap_or(eframe::Theme::Dark),
+            ThemePref::Dark => eframe::Theme::Dark,
+            ThemePref::Light => eframe::Theme::Light,
+        }
+    }
+}
+
+/* ---------------- 自定义配色 ---------------- */
+// 在主题的基础上替换强调色并调整背景深浅，便于截图按工程区分
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    // None 时使用主题自带的强调色
+    pub accent: Option<[u8; 3]>,
+    // 背景加深的比例，0 为不调整
+    pub darkness: f32,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            accent: None,
+            darkness: 0.0,
+        }
+    }
+}
+
+fn darken(color: egui::Color32, amount: f32) -> egui::Color32 {
+    let scale = |c: u8| (c as f32 * (1.0 - amount)).round() as u8;
+    egui::Color32::from_rgba_unmultiplied(
+        scale(color.r()),
+        scale(color.g()),
+        scale(color.b()),
+        color.a(),
+    )
+}
+
+impl Palette {
+    pub const DARKNESS_RANGE: std::ops::RangeInclusive<f32> = 0.0..=0.8;
+
+    pub fn apply(&self, visuals: &mut egui::Visuals) {
+        if let Some([r, g, b]) = self.accent {
+            let accent = egui::Color32::from_rgb(r, g, b);
+            visuals.selection.bg_fill = accent;
+            visuals.hyperlink_color = accent;
+            visuals.widgets.hovered.bg_stroke.color = accent;
+            visuals.widgets.active.bg_fill = accent;
+        }
+        let amount = self.darkness.clamp(0.0, 1.0);
+        if amount > 0.0 {
+            visuals.panel_fill = darken(visuals.panel_fill, amount);
+            visuals.window_fill = darken(visuals.window_fill, amount);
+            visuals.extreme_bg_color = darken(visuals.extreme_bg_color, amount);
+            visuals.faint_bg_color = darken(visuals.faint_bg_color, amount);
+        }
+    }
+}
+
+/* ---------------- 缩放与字号 ---------------- */
+// egui 默认正文字号，其余文字样式按同样比例缩放
+const DEFAULT_BODY_SIZE: f32 = 12.5;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiScale {
+    // 整体缩放倍数，对应 egui 的 zoom factor
+    pub zoom: f32,
+    // 正文字号（缩放前的点数）
+    pub font_size: f32,
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            font_size: DEFAULT_BODY_SIZE,
+        }
+    }
+}
+
+impl UiScale {
+    pub const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+    pub const FONT_RANGE: std::ops::RangeInclusive<f32> = 8.0..=32.0;
+
+    // 以 egui 默认样式为基准按字号缩放，反复调整不会累积误差
+    pub fn apply_text_styles(&self, ctx: &egui::Context) {
+        let ratio = self.font_size / DEFAULT_BODY_SIZE;
+        let defaults = egui::Style::default().text_styles;
+        ctx.style_mut(|style| {
+            for (text_style, font_id) in style.text_styles.iter_mut() {
+                if let Some(base) = defaults.get(text_style) {
+                    font_id.size = base.size * ratio;
+                }
+            }
+        });
+    }
+}
+
+/* ---------------- 统计后自动导出 ---------------- */
+// 每次统计完成后按模板写一份报告，如放到共享目录里积累
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoExport {
+    pub enabled: bool,
+    pub format: ExportFormat,
+    // 输出路径模板，{project} {date} {time} {ext} 会被替换；相对路径以工程根目录为准
+    pub path: String,
+}
+
+impl Default for AutoExport {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: ExportFormat::Json,
+            path: String::new(),
+        }
+    }
+}
+
+impl AutoExport {
+    // 未启用或模板为空时为 None
+    pub fn target(&self, root: &Path, at: DateTime<Local>) -> Option<PathBuf> {
+        let pattern = self.path.trim();
+        if !self.enabled || pattern.is_empty() {
+            return None;
+        }
+        let path = pattern
+            .replace("{project}", &dir_display_name(root))
+            .replace("{date}", &at.format("%Y-%m-%d").to_string())
+            .replace("{time}", &at.format("%H%M%S").to_string())
+            .replace("{ext}", self.format.extension());
+        Some(root.join(path))
+    }
+}
+
+/* ---------------- 用户设置 ---------------- */
+// 缺失的字段回退到默认值，老版本的存档也能正常读取
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub lang: Lang,
+    pub theme: ThemePref,
+    pub palette: Palette,
+    pub font_source: FontSource,
+    // 自定义字体文件，仅在 font_source 为 Custom 时使用
+    pub font_path: PathBuf,
+    pub ui_scale: UiScale,
+    // 最近统计过的工程目录，最新的在最前
+    pub recent_projects: Vec<PathBuf>,
+    pub scan: ScanOptions,
+    // 各工程要统计的子模块，按工程目录分别记住；scan 中的 include_submodules 不使用
+    pub submodules: HashMap<PathBuf, Vec<String>>,
+    // 双击文件时执行的命令模板，为空则用系统默认程序打开
+    pub editor_command: String,
+    pub result_view: ResultView,
+    // Top N 面板显示的文件数
+    pub top_n: usize,
+    // 逐文件结果表中显示的统计列
+    pub file_columns: Vec<Metric>,
+    // 上次导出使用的格式，Ctrl+E 直接沿用
+    pub export_format: ExportFormat,
+    pub heatmap: Heatmap,
+    pub auto_export: AutoExport,
+    // 上次追加时间序列的 CSV 文件
+    pub series_csv: Option<PathBuf>,
+    // 上次使用的报告模板
+    pub report_template: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            lang: Lang::default(),
+            theme: ThemePref::default(),
+            palette: Palette::default(),
+            font_source: FontSource::default(),
+            font_path: PathBuf::new(),
+            ui_scale: UiScale::default(),
+            recent_projects: Vec::new(),
+            scan: ScanOptions::default(),
+            submodules: HashMap::new(),
+            editor_command: String::new(),
+            result_view: ResultView::default(),
+            top_n: 10,
+            file_columns: Metric::DEFAULT.to_vec(),
+            export_format: ExportFormat::default(),
+            heatmap: Heatmap::default(),
+            auto_export: AutoExport::default(),
+            series_csv: None,
+            report_template: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, STORAGE_KEY, self);
+    }
+
+    // 统计 root 时使用的选项：全局的扫描选项加上该工程勾选的子模块
+    pub fn scan_options(&self, root: &Path) -> ScanOptions {
+        ScanOptions {
+            include_submodules: self.submodules.get(root).cloned().unwrap_or_default(),
+            ..self.scan.clone()
+        }
+    }
+
+    pub fn push_recent(&mut self, dir: &Path) {
+        self.recent_projects.retain(|p| p != dir);
+        self.recent_projects.insert(0, dir.to_path_buf());
+        self.recent_projects.truncate(MAX_RECENT);
+    }
+}
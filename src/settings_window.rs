@@ -0,0 +1,339 @@
+use eframe::egui;
+use rfd::FileDialog;
+
+use crate::fonts::{FontSource, LoadedFont};
+use crate::i18n::{Lang, Texts};
+use crate::platform::EDITOR_PRESETS;
+use crate::report::ExportFormat;
+use crate::settings::{AutoExport, Palette, Settings, ThemePref, UiScale};
+use crate::stat::ScanOptions;
+
+/* ---------------- 设置窗口 ---------------- */
+// 列表类选项用文本框编辑，打开窗口时从设置同步一次
+#[derive(Default)]
+pub struct SettingsWindow {
+    pub open: bool,
+    exclude_text: String,
+    ext_text: String,
+}
+
+fn join_lines(items: &[String]) -> String {
+    items.join("\n")
+}
+
+fn join_commas(items: &[String]) -> String {
+    items.join(", ")
+}
+
+fn split_items(text: &str, sep: &[char]) -> Vec<String> {
+    text.split(sep)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+impl SettingsWindow {
+    pub fn toggle(&mut self, settings: &Settings) {
+        self.open = !self.open;
+        if self.open {
+            self.sync_from(&settings.scan);
+        }
+    }
+
+    fn sync_from(&mut self, scan: &ScanOptions) {
+        self.exclude_text = join_lines(&scan.exclude_dirs);
+        self.ext_text = join_commas(&scan.extensions);
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        settings: &mut Settings,
+        loaded_font: Option<&LoadedFont>,
+    ) {
+        let t = settings.lang.texts();
+        let mut open = self.open;
+
+        egui::Window::new(t.settings)
+            .open(&mut open)
+            .resizable(true)
+            .collapsible(false)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                self.appearance_section(ui, settings, t);
+                font_section(ui, settings, loaded_font, t);
+                ui.separator();
+                self.scan_section(ui, &mut settings.scan, t);
+                ui.separator();
+                editor_section(ui, &mut settings.editor_command, t);
+                ui.separator();
+                auto_export_section(ui, &mut settings.auto_export, t);
+            });
+
+        self.open = open;
+    }
+
+    fn appearance_section(&mut self, ui: &mut egui::Ui, settings: &mut Settings, t: &Texts) {
+        ui.heading(t.appearance);
+        egui::Grid::new("appearance_grid")
+            .num_columns(2)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                ui.label(t.language);
+                egui::ComboBox::from_id_source("lang")
+                    .selected_text(settings.lang.native_name())
+                    .show_ui(ui, |ui| {
+                        for lang in Lang::ALL {
+                            ui.selectable_value(&mut settings.lang, lang, lang.native_name());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(t.theme);
+                egui::ComboBox::from_id_source("theme")
+                    .selected_text(settings.theme.label(t))
+                    .show_ui(ui, |ui| {
+                        for theme in ThemePref::ALL {
+                            ui.selectable_value(&mut settings.theme, theme, theme.label(t));
+                        }
+                    });
+                ui.end_row();
+
+                let palette = &mut settings.palette;
+                ui.label(t.accent_color);
+                ui.horizontal(|ui| {
+                    let mut custom = palette.accent.is_some();
+                    if ui.checkbox(&mut custom, t.accent_custom).changed() {
+                        palette.accent = custom.then(|| {
+                            let c = ui.visuals().selection.bg_fill;
+                            [c.r(), c.g(), c.b()]
+                        });
+                    }
+                    if let Some(accent) = &mut palette.accent {
+                        ui.color_edit_button_srgb(accent);
+                    }
+                });
+                ui.end_row();
+
+                ui.label(t.background_darkness);
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::Slider::new(&mut palette.darkness, Palette::DARKNESS_RANGE)
+                            .step_by(0.05)
+                            .custom_formatter(|v, _| format!("{:.0}%", v * 100.0)),
+                    );
+                    if *palette != Palette::default()
+                        && ui
+                            .small_button("↺")
+                            .on_hover_text(t.restore_defaults)
+                            .clicked()
+                    {
+                        *palette = Palette::default();
+                    }
+                });
+                ui.end_row();
+
+                ui.label(t.ui_zoom);
+                ui.add(
+                    egui::Slider::new(&mut settings.ui_scale.zoom, UiScale::ZOOM_RANGE)
+                        .step_by(0.05)
+                        .custom_formatter(|v, _| format!("{:.0}%", v * 100.0)),
+                );
+                ui.end_row();
+
+                ui.label(t.font_size);
+                ui.add(
+                    egui::Slider::new(&mut settings.ui_scale.font_size, UiScale::FONT_RANGE)
+                        .step_by(0.5),
+                );
+                ui.end_row();
+
+                let heatmap = &mut settings.heatmap;
+                ui.label(t.heatmap);
+                ui.checkbox(&mut heatmap.enabled, t.heatmap_enabled)
+                    .on_hover_text(t.heatmap_hint);
+                ui.end_row();
+
+                if heatmap.enabled {
+                    let percent = |v: f64, _| format!("{:.0}%", v * 100.0);
+                    ui.label(t.heatmap_low);
+                    ui.add(
+                        egui::Slider::new(&mut heatmap.low, 0.0..=1.0)
+                            .step_by(0.01)
+                            .custom_formatter(percent),
+                    );
+                    ui.end_row();
+
+                    ui.label(t.heatmap_high);
+                    ui.add(
+                        egui::Slider::new(&mut heatmap.high, 0.0..=1.0)
+                            .step_by(0.01)
+                            .custom_formatter(percent),
+                    );
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn scan_section(&mut self, ui: &mut egui::Ui, scan: &mut ScanOptions, t: &Texts) {
+        ui.heading(t.scan_options);
+        ui.weak(t.scan_options_hint);
+        ui.add_space(4.0);
+
+        ui.label(t.exclude_dirs);
+        if ui
+            .add(
+                egui::TextEdit::multiline(&mut self.exclude_text)
+                    .desired_rows(4)
+                    .desired_width(f32::INFINITY),
+            )
+            .changed()
+        {
+            scan.exclude_dirs = split_items(&self.exclude_text, &['\n']);
+        }
+
+        ui.add_space(4.0);
+        ui.label(t.extensions);
+        if ui
+            .add(egui::TextEdit::singleline(&mut self.ext_text).desired_width(f32::INFINITY))
+            .changed()
+        {
+            // 允许写成 .cpp 的形式
+            scan.extensions = split_items(&self.ext_text, &[',', ' ', ';'])
+                .into_iter()
+                .map(|e| e.trim_start_matches('.').to_owned())
+                .filter(|e| !e.is_empty())
+                .collect();
+        }
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(t.threads);
+            ui.add(egui::DragValue::new(&mut scan.threads).clamp_range(0..=256));
+            ui.weak(t.threads_auto);
+        });
+        ui.horizontal(|ui| {
+            ui.label(t.max_file_size);
+            ui.add(
+                egui::DragValue::new(&mut scan.max_file_kb)
+                    .clamp_range(0..=1024 * 1024)
+                    .suffix(" KB"),
+            );
+            ui.weak(t.max_file_size_hint);
+        });
+        ui.checkbox(&mut scan.tracked_only, t.tracked_only)
+            .on_hover_text(t.tracked_only_hint);
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut scan.perforce, t.perforce)
+                .on_hover_text(t.perforce_hint);
+            ui.add_enabled_ui(scan.perforce, |ui| {
+                ui.label(t.p4_changelist);
+                ui.add(
+                    egui::TextEdit::singleline(&mut scan.p4_changelist)
+                        .hint_text(t.p4_changelist_hint)
+                        .desired_width(100.0),
+                );
+            });
+        });
+
+        ui.add_space(8.0);
+        if ui.button(t.restore_defaults).clicked() {
+            *scan = ScanOptions::default();
+            self.sync_from(scan);
+        }
+    }
+}
+
+fn editor_section(ui: &mut egui::Ui, command: &mut String, t: &Texts) {
+    ui.heading(t.editor);
+    ui.weak(t.editor_hint);
+    ui.add_space(4.0);
+    ui.add(
+        egui::TextEdit::singleline(command)
+            .hint_text(t.editor_default)
+            .desired_width(f32::INFINITY),
+    );
+    ui.horizontal_wrapped(|ui| {
+        for (name, template) in EDITOR_PRESETS {
+            if ui.small_button(name).on_hover_text(template).clicked() {
+                *command = template.to_owned();
+            }
+        }
+        if ui.small_button(t.editor_default).clicked() {
+            command.clear();
+        }
+    });
+}
+
+fn auto_export_section(ui: &mut egui::Ui, auto: &mut AutoExport, t: &Texts) {
+    ui.heading(t.auto_export);
+    ui.checkbox(&mut auto.enabled, t.auto_export_enabled);
+    ui.add_enabled_ui(auto.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(t.export);
+            egui::ComboBox::from_id_source("auto_export_format")
+                .selected_text(auto.format.name())
+                .show_ui(ui, |ui| {
+                    for format in ExportFormat::ALL {
+                        ui.selectable_value(&mut auto.format, format, format.name());
+                    }
+                });
+        });
+        ui.label(t.auto_export_path);
+        ui.add(
+            egui::TextEdit::singleline(&mut auto.path)
+                .hint_text("D:/Reports/{project}/{date}_{time}.{ext}")
+                .desired_width(f32::INFINITY),
+        );
+        ui.weak(t.auto_export_hint);
+    });
+}
+
+fn font_section(
+    ui: &mut egui::Ui,
+    settings: &mut Settings,
+    loaded_font: Option<&LoadedFont>,
+    t: &Texts,
+) {
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.label(t.font);
+        egui::ComboBox::from_id_source("font_source")
+            .selected_text(settings.font_source.label(t))
+            .show_ui(ui, |ui| {
+                for source in FontSource::all() {
+                    ui.selectable_value(&mut settings.font_source, source, source.label(t));
+                }
+            });
+    });
+
+    if settings.font_source == FontSource::Custom {
+        ui.horizontal(|ui| {
+            let mut text = settings.font_path.display().to_string();
+            if ui
+                .add(egui::TextEdit::singleline(&mut text).desired_width(260.0))
+                .changed()
+            {
+                settings.font_path = text.into();
+            }
+            if ui.button(t.browse).clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("Font", &["ttf", "ttc", "otf"])
+                    .pick_file()
+                {
+                    settings.font_path = path;
+                }
+            }
+        });
+    }
+
+    if let Some(loaded) = loaded_font {
+        ui.weak(format!(
+            "{}{}{}",
+            t.font_in_use,
+            t.colon,
+            loaded.describe(t)
+        ));
+    }
+}
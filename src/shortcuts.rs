@@ -0,0 +1,52 @@
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+use crate::i18n::Texts;
+
+/* ---------------- 快捷键 ---------------- */
+// Modifiers::COMMAND 在 macOS 上对应 ⌘，其他平台对应 Ctrl
+pub const OPEN: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+pub const RESCAN: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F5);
+pub const EXPORT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::E);
+pub const FIND: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+
+// 快捷键一览，用于帮助窗口
+fn all(t: &Texts) -> [(KeyboardShortcut, &'static str); 4] {
+    [
+        (OPEN, t.select_dir),
+        (RESCAN, t.rescan),
+        (EXPORT, t.export_again),
+        (FIND, t.focus_filter),
+    ]
+}
+
+// 菜单项右侧带快捷键提示
+pub fn menu_item(ui: &mut egui::Ui, label: &str, shortcut: &KeyboardShortcut) -> bool {
+    let text = ui.ctx().format_shortcut(shortcut);
+    let clicked = ui
+        .add(egui::Button::new(label).shortcut_text(text))
+        .clicked();
+    if clicked {
+        ui.close_menu();
+    }
+    clicked
+}
+
+pub fn show_help_window(ctx: &egui::Context, open: &mut bool, t: &Texts) {
+    egui::Window::new(t.shortcuts)
+        .open(open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts_grid")
+                .num_columns(2)
+                .spacing([24.0, 6.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for (shortcut, label) in all(t) {
+                        ui.strong(ctx.format_shortcut(&shortcut));
+                        ui.label(label);
+                        ui.end_row();
+                    }
+                });
+        });
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Local};
+use serde::{de::Error as _, Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::stat::{ScanOptions, StatResult};
+
+/* ---------------- 统计快照 ---------------- */
+// 完整的统计结果连同当时的扫描选项，保存后可以原样读回界面查看或对比；
+// 内容是 JSON，扩展名区分于普通报告
+pub const EXTENSION: &str = "uelocsnap";
+
+// 快照结构的版本，只有删改已有字段时才加一
+const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    // 统计时的工程根目录
+    pub root: PathBuf,
+    pub saved_at: DateTime<Local>,
+    pub options: ScanOptions,
+    pub result: StatResult,
+}
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    root: &'a Path,
+    saved_at: DateTime<Local>,
+    options: &'a ScanOptions,
+    result: &'a StatResult,
+}
+
+pub fn to_bytes(
+    root: &Path,
+    options: &ScanOptions,
+    result: &StatResult,
+) -> serde_json::Result<Vec<u8>> {
+    serde_json::to_vec(&SnapshotRef {
+        version: SNAPSHOT_VERSION,
+        root,
+        saved_at: Local::now(),
+        options,
+        result,
+    })
+}
+
+// 文件的绝对路径不保存，按快照中的根目录重建
+pub fn from_slice(bytes: &[u8]) -> serde_json::Result<Snapshot> {
+    let mut snapshot: Snapshot = serde_json::from_slice(bytes)?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        return Err(serde_json::Error::custom(format!(
+            "snapshot version {} is newer than the supported version {}",
+            snapshot.version, SNAPSHOT_VERSION
+        )));
+    }
+    for file in &mut snapshot.result.files {
+        file.path = snapshot.root.join(&file.rel_path);
+    }
+    Ok(snapshot)
+}
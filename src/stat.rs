@@ -0,0 +1,1118 @@
+use chrono::{DateTime, Local};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::{debug, info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fs, io,
+    num::NonZeroUsize,
+    ops::AddAssign,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+use walkdir::WalkDir;
+
+use crate::git;
+use crate::perforce;
+
+// 默认全局排除目录
+const EXCLUDE_DIR: [&str; 4] = ["Intermediate", "Binaries", "Saved", ".vs"];
+// 默认需要统计的代码文件后缀
+const INCLUDE_EXT: [&str; 3] = ["h", "cpp", "inl"];
+// 默认的单文件大小上限，生成的巨型源码一般超过这个大小
+const DEFAULT_MAX_FILE_KB: u64 = 8 * 1024;
+// 判断是否为二进制文件时检查的字节数
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/* ---------------- 扫描选项 ---------------- */
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    // 路径中任意一级目录名命中即跳过
+    pub exclude_dirs: Vec<String>,
+    // 不带点的后缀名，不区分大小写
+    pub extensions: Vec<String>,
+    // 读取文件的线程数，0 表示按 CPU 核数自动选择
+    pub threads: usize,
+    // 单个文件的大小上限（KB），超过的跳过，0 表示不限
+    pub max_file_kb: u64,
+    // 按相对路径匹配的 glob，命中即跳过
+    pub exclude_globs: Vec<String>,
+    // 非空时只统计命中的文件，代替按后缀名筛选
+    pub include_globs: Vec<String>,
+    // 只统计 git 索引中已跟踪的文件，未跟踪和被忽略的文件不计
+    pub tracked_only: bool,
+    // 要统计的 git 子模块，相对根目录；其余子模块整个跳过
+    pub include_submodules: Vec<String>,
+    // 只统计当前 Perforce 工作区映射的文件
+    pub perforce: bool,
+    // 与 perforce 同时使用：非空时只统计在该待提交变更列表（编号或 default）中打开的文件
+    pub p4_changelist: String,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            exclude_dirs: EXCLUDE_DIR.iter().map(|s| s.to_string()).collect(),
+            extensions: INCLUDE_EXT.iter().map(|s| s.to_string()).collect(),
+            threads: 0,
+            max_file_kb: DEFAULT_MAX_FILE_KB,
+            exclude_globs: Vec::new(),
+            include_globs: Vec::new(),
+            tracked_only: false,
+            include_submodules: Vec::new(),
+            perforce: false,
+            p4_changelist: String::new(),
+        }
+    }
+}
+
+impl ScanOptions {
+    pub fn worker_count(&self) -> usize {
+        if self.threads > 0 {
+            self.threads
+        } else {
+            thread::available_parallelism().map_or(1, NonZeroUsize::get)
+        }
+    }
+
+    // 单个文件大小上限，None 表示不限
+    pub(crate) fn max_bytes(&self) -> Option<u64> {
+        (self.max_file_kb > 0).then(|| self.max_file_kb * 1024)
+    }
+}
+
+/* ---------------- 统计结果 ---------------- */
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LineCounts {
+    pub total: usize,
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+    // 以下两项是代码行中的细分
+    pub includes: usize,
+    pub reflection: usize,
+}
+
+impl LineCounts {
+    // 注释行占代码与注释之和的比例
+    pub fn comment_ratio(&self) -> f64 {
+        let denom = self.code + self.comment;
+        if denom == 0 {
+            0.0
+        } else {
+            self.comment as f64 / denom as f64
+        }
+    }
+}
+
+impl AddAssign for LineCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        self.total += rhs.total;
+        self.code += rhs.code;
+        self.comment += rhs.comment;
+        self.blank += rhs.blank;
+        self.includes += rhs.includes;
+        self.reflection += rhs.reflection;
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileStat {
+    // 不写入报告；读回快照时由根目录和 rel_path 重建
+    #[serde(skip)]
+    pub path: PathBuf,
+    // 相对工程根目录的路径，用于显示
+    pub rel_path: String,
+    // 所属 UE 模块（最近的含 *.Build.cs 的上级目录）
+    pub module: Option<String>,
+    // 所属插件（最近的含 *.uplugin 的上级目录）
+    pub plugin: Option<String>,
+    pub lines: LineCounts,
+    pub details: FileDetails,
+    // 统计窗口内 git 历史中增加与删除的行数之和，算过才有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<usize>,
+    // git 历史中最后一次修改该文件的提交，查过且文件已提交才有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_commit: Option<LastCommit>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LastCommit {
+    pub time: DateTime<Local>,
+    pub author: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    // 不是合法的 UTF-8，无法解码的字节按替换字符处理
+    Lossy,
+}
+
+impl TextEncoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 BOM",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Lossy => "?",
+        }
+    }
+}
+
+// 行数之外的单文件信息，用于详情弹窗
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FileDetails {
+    pub size: u64,
+    pub modified: Option<DateTime<Local>>,
+    pub encoding: TextEncoding,
+    // 最长一行的字符数
+    pub longest_line: usize,
+}
+
+impl FileStat {
+    // 小写、不带点的扩展名，没有扩展名时为空
+    pub fn extension(&self) -> String {
+        Path::new(&self.rel_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModuleStat {
+    // None 表示不属于任何模块的文件
+    pub name: Option<String>,
+    pub plugin: Option<String>,
+    pub files: usize,
+    pub lines: LineCounts,
+    // 模块内各文件变动量之和，算过才有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<usize>,
+}
+
+// 警告的类别；Encoding 仍会计入统计，其余类别的文件被跳过
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarningKind {
+    Walk,
+    Read,
+    Encoding,
+    TooLarge,
+    Binary,
+}
+
+impl WarningKind {
+    // 导出时使用的英文标识
+    pub fn name(self) -> &'static str {
+        match self {
+            WarningKind::Walk => "walk",
+            WarningKind::Read => "read",
+            WarningKind::Encoding => "encoding",
+            WarningKind::TooLarge => "too_large",
+            WarningKind::Binary => "binary",
+        }
+    }
+
+    pub fn skipped(self) -> bool {
+        self != WarningKind::Encoding
+    }
+}
+
+// 扫描过程中遇到、但不影响整体结果的问题
+#[derive(Serialize, Deserialize)]
+pub struct ScanWarning {
+    pub path: PathBuf,
+    pub kind: WarningKind,
+    // 补充说明，如系统错误信息或文件大小
+    pub message: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct StatResult {
+    pub files: Vec<FileStat>,
+    // 按有效代码行数从多到少
+    pub modules: Vec<ModuleStat>,
+    pub totals: LineCounts,
+    pub warnings: Vec<ScanWarning>,
+    // 工程中的 git 子模块，与工程自身的代码分开列出
+    #[serde(default)]
+    pub submodules: Vec<SubmoduleStat>,
+    // 扫描完成的时间与耗时
+    pub finished_at: Option<DateTime<Local>>,
+    pub elapsed: Duration,
+}
+
+// 未纳入统计的子模块只有路径，行数为 0
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SubmoduleStat {
+    // 相对工程根目录，以 / 分隔
+    pub path: String,
+    pub included: bool,
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+fn submodule_stats(
+    root: &Path,
+    paths: Vec<String>,
+    files: &[FileStat],
+    opts: &ScanOptions,
+) -> Vec<SubmoduleStat> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let mut stat = SubmoduleStat {
+                included: opts.include_submodules.contains(&path),
+                ..Default::default()
+            };
+            if stat.included {
+                let dir = root.join(&path);
+                for file in files.iter().filter(|f| f.path.starts_with(&dir)) {
+                    stat.files += 1;
+                    stat.lines += file.lines;
+                }
+            }
+            stat.path = path;
+            stat
+        })
+        .collect()
+}
+
+// 把按路径算出的变动量写入各文件和模块，历史中没有改动的文件为 0
+pub fn apply_churn(result: &mut StatResult, churn: &HashMap<PathBuf, usize>) {
+    let mut by_module: HashMap<(Option<&str>, Option<&str>), usize> = HashMap::new();
+    for file in &mut result.files {
+        let n = churn.get(&file.path).copied().unwrap_or(0);
+        file.churn = Some(n);
+        *by_module
+            .entry((file.module.as_deref(), file.plugin.as_deref()))
+            .or_default() += n;
+    }
+    for module in &mut result.modules {
+        let key = (module.name.as_deref(), module.plugin.as_deref());
+        module.churn = Some(by_module.get(&key).copied().unwrap_or(0));
+    }
+}
+
+// 把按路径查到的最后一次提交写入各文件，不在历史中的文件为 None
+pub fn apply_last_commits(result: &mut StatResult, commits: &HashMap<PathBuf, LastCommit>) {
+    for file in &mut result.files {
+        file.last_commit = commits.get(&file.path).cloned();
+    }
+}
+
+// 按扩展名汇总，近似于按语言分类
+#[derive(Serialize)]
+pub struct ExtensionStat {
+    pub extension: String,
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+// 按有效代码行数从多到少
+pub fn by_extension(files: &[FileStat]) -> Vec<ExtensionStat> {
+    let mut map: HashMap<String, ExtensionStat> = HashMap::new();
+    for file in files {
+        let extension = file.extension();
+        let entry = map
+            .entry(extension.clone())
+            .or_insert_with(|| ExtensionStat {
+                extension,
+                files: 0,
+                lines: LineCounts::default(),
+            });
+        entry.files += 1;
+        entry.lines += file.lines;
+    }
+    let mut stats: Vec<_> = map.into_values().collect();
+    stats.sort_by(|a, b| {
+        b.lines
+            .code
+            .cmp(&a.lines.code)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+    stats
+}
+
+// 有效代码行数最多的 n 个文件，行数相同按路径排列
+pub fn largest_files(files: &[FileStat], n: usize) -> Vec<&FileStat> {
+    let mut sorted: Vec<&FileStat> = files.iter().collect();
+    sorted.sort_by(|a, b| {
+        b.lines
+            .code
+            .cmp(&a.lines.code)
+            .then_with(|| a.rel_path.cmp(&b.rel_path))
+    });
+    sorted.truncate(n);
+    sorted
+}
+
+/* ---------------- 目录树聚合 ---------------- */
+#[derive(Default, Serialize)]
+pub struct DirNode {
+    pub name: String,
+    // 相对工程根目录的路径，根节点为空
+    pub rel_path: String,
+    // 子树内所有文件的合计
+    pub files: usize,
+    pub lines: LineCounts,
+    pub children: Vec<DirNode>,
+}
+
+impl DirNode {
+    pub fn build(root_name: &str, files: &[FileStat]) -> Self {
+        let mut root = DirNode {
+            name: root_name.to_owned(),
+            ..Default::default()
+        };
+
+        for file in files {
+            let mut node = &mut root;
+            node.add(file);
+
+            let parent = Path::new(&file.rel_path).parent();
+            for comp in parent.into_iter().flat_map(Path::components) {
+                let name = comp.as_os_str().to_string_lossy();
+                let idx = match node.children.iter().position(|c| c.name == name) {
+                    Some(idx) => idx,
+                    None => {
+                        let rel_path = Path::new(&node.rel_path).join(name.as_ref());
+                        node.children.push(DirNode {
+                            name: name.into_owned(),
+                            rel_path: rel_path.display().to_string(),
+                            ..Default::default()
+                        });
+                        node.children.len() - 1
+                    }
+                };
+                node = &mut node.children[idx];
+                node.add(file);
+            }
+        }
+
+        root.sort_children();
+        root
+    }
+
+    fn add(&mut self, file: &FileStat) {
+        self.files += 1;
+        self.lines += file.lines;
+    }
+
+    // 代码量大的目录排在前面
+    fn sort_children(&mut self) {
+        self.children.sort_by_key(|c| Reverse(c.lines.code));
+        for child in &mut self.children {
+            child.sort_children();
+        }
+    }
+}
+
+/* ---------------- 行分类 ---------------- */
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Blank,
+    Comment,
+    Code,
+}
+
+pub fn classify_line(line: &str) -> LineKind {
+    let s = line.trim();
+    if s.is_empty() {
+        LineKind::Blank
+    } else if s.starts_with("//") || s.starts_with("/*") || s.starts_with('*') {
+        LineKind::Comment
+    } else {
+        LineKind::Code
+    }
+}
+
+// UE 反射系统使用的宏
+const REFLECTION_MACROS: [&str; 11] = [
+    "UCLASS",
+    "USTRUCT",
+    "UENUM",
+    "UINTERFACE",
+    "UPROPERTY",
+    "UFUNCTION",
+    "UDELEGATE",
+    "GENERATED_BODY",
+    "GENERATED_UCLASS_BODY",
+    "GENERATED_USTRUCT_BODY",
+    "GENERATED_IINTERFACE_BODY",
+];
+
+fn is_include(code: &str) -> bool {
+    code.strip_prefix('#')
+        .is_some_and(|rest| rest.trim_start().starts_with("include"))
+}
+
+// 宏名后必须紧跟括号，避免把 UCLASS_API 之类的标识符算进去
+fn is_reflection(code: &str) -> bool {
+    REFLECTION_MACROS.iter().any(|m| {
+        code.strip_prefix(m)
+            .is_some_and(|rest| rest.trim_start().starts_with('('))
+    })
+}
+
+// 把一行计入 counts，按作者归属时逐行调用
+pub fn count_line(counts: &mut LineCounts, line: &str) {
+    counts.total += 1;
+    match classify_line(line) {
+        LineKind::Blank => counts.blank += 1,
+        LineKind::Comment => counts.comment += 1,
+        LineKind::Code => {
+            counts.code += 1;
+            let code = line.trim();
+            if is_include(code) {
+                counts.includes += 1;
+            } else if is_reflection(code) {
+                counts.reflection += 1;
+            }
+        }
+    }
+}
+
+fn count_lines(text: &str) -> LineCounts {
+    let mut counts = LineCounts::default();
+    for line in text.lines() {
+        count_line(&mut counts, line);
+    }
+    counts
+}
+
+/* ---------------- 目录过滤 ---------------- */
+// 不区分大小写，* 可以跨越目录
+pub fn build_glob(pattern: &str) -> Result<globset::Glob, globset::Error> {
+    GlobBuilder::new(pattern).case_insensitive(true).build()
+}
+
+// 无效的模式直接忽略，命令行在解析参数时已经报过错
+fn build_globset(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for glob in patterns.iter().filter_map(|p| build_glob(p).ok()) {
+        builder.add(glob);
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+// 按相对工程根目录的路径过滤，统一成正斜杠
+pub struct ScanFilter<'a> {
+    opts: &'a ScanOptions,
+    exclude: GlobSet,
+    include: Option<GlobSet>,
+}
+
+impl<'a> ScanFilter<'a> {
+    pub fn new(opts: &'a ScanOptions) -> Self {
+        Self {
+            opts,
+            exclude: build_globset(&opts.exclude_globs),
+            include: (!opts.include_globs.is_empty()).then(|| build_globset(&opts.include_globs)),
+        }
+    }
+
+    fn rel_path(root: &Path, path: &Path) -> String {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    pub(crate) fn skip(&self, root: &Path, path: &Path) -> bool {
+        should_skip(path, self.opts) || self.exclude.is_match(Self::rel_path(root, path))
+    }
+
+    pub(crate) fn count(&self, root: &Path, path: &Path) -> bool {
+        match &self.include {
+            Some(include) => include.is_match(Self::rel_path(root, path)),
+            None => should_count(path, self.opts),
+        }
+    }
+
+    // 该路径的变化是否会影响统计结果，供命令行监视模式过滤文件事件
+    pub fn affects_result(&self, root: &Path, path: &Path) -> bool {
+        !self.skip(root, path) && (self.count(root, path) || module_marker(path).is_some())
+    }
+}
+
+fn should_skip(path: &Path, opts: &ScanOptions) -> bool {
+    // 全局排除
+    if path.components().any(|c| {
+        let name = c.as_os_str().to_string_lossy();
+        opts.exclude_dirs.iter().any(|d| *d == name)
+    }) {
+        return true;
+    }
+
+    // Plugins 特殊排除
+    let components: Vec<_> = path.components().collect();
+    for (i, comp) in components.iter().enumerate() {
+        let name = comp.as_os_str().to_string_lossy();
+        if name == "Plugins" {
+            if let Some(next) = components.get(i + 1) {
+                let next_name = next.as_os_str().to_string_lossy();
+                if next_name == "Intermediate" || next_name == "ThirdParty" {
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+fn should_count(path: &Path, opts: &ScanOptions) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| opts.extensions.iter().any(|x| x.eq_ignore_ascii_case(e)))
+        .unwrap_or(false)
+}
+
+/* ---------------- 模块识别 ---------------- */
+// 模块根目录放 Foo.Build.cs，插件根目录放 Foo.uplugin，返回对应的名字
+fn module_marker(path: &Path) -> Option<(&str, bool)> {
+    let name = path.file_name()?.to_str()?;
+    if let Some(module) = name.strip_suffix(".Build.cs") {
+        Some((module, false))
+    } else {
+        name.strip_suffix(".uplugin").map(|plugin| (plugin, true))
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ModuleRoots {
+    modules: HashMap<PathBuf, String>,
+    plugins: HashMap<PathBuf, String>,
+}
+
+impl ModuleRoots {
+    pub(crate) fn record(&mut self, path: &Path) {
+        if let (Some((name, is_plugin)), Some(dir)) = (module_marker(path), path.parent()) {
+            let map = if is_plugin {
+                &mut self.plugins
+            } else {
+                &mut self.modules
+            };
+            map.insert(dir.to_path_buf(), name.to_owned());
+        }
+    }
+
+    fn nearest(map: &HashMap<PathBuf, String>, root: &Path, file: &Path) -> Option<String> {
+        file.ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| map.get(dir).cloned())
+    }
+
+    fn assign(&self, root: &Path, file: &mut FileStat) {
+        file.module = Self::nearest(&self.modules, root, &file.path);
+        file.plugin = Self::nearest(&self.plugins, root, &file.path);
+    }
+}
+
+fn aggregate_modules(files: &[FileStat]) -> Vec<ModuleStat> {
+    // 同名模块可能出现在不同插件里，按 (模块, 插件) 区分
+    let mut map: HashMap<(Option<&str>, Option<&str>), ModuleStat> = HashMap::new();
+    for file in files {
+        let entry = map
+            .entry((file.module.as_deref(), file.plugin.as_deref()))
+            .or_insert_with(|| ModuleStat {
+                name: file.module.clone(),
+                plugin: file.plugin.clone(),
+                files: 0,
+                lines: LineCounts::default(),
+                churn: None,
+            });
+        entry.files += 1;
+        entry.lines += file.lines;
+    }
+
+    let mut modules: Vec<_> = map.into_values().collect();
+    modules.sort_by_key(|m| Reverse(m.lines.code));
+    modules
+}
+
+/* ---------------- 扫描 ---------------- */
+// 单个文件的统计结果：stat 为 None 表示被跳过
+pub(crate) struct FileOutcome {
+    stat: Option<(LineCounts, FileDetails)>,
+    warning: Option<(WarningKind, String)>,
+}
+
+impl FileOutcome {
+    pub(crate) fn skipped(kind: WarningKind, message: String) -> Self {
+        Self {
+            stat: None,
+            warning: Some((kind, message)),
+        }
+    }
+
+    // 超过大小上限时直接跳过，不必读出内容
+    pub(crate) fn too_large(size: u64, max_bytes: Option<u64>) -> Option<Self> {
+        max_bytes
+            .is_some_and(|max| size > max)
+            .then(|| Self::skipped(WarningKind::TooLarge, format!("{} KB", size / 1024)))
+    }
+
+    // 文件内容可能来自磁盘，也可能来自版本库
+    pub(crate) fn from_bytes(bytes: Vec<u8>, modified: Option<DateTime<Local>>) -> Self {
+        if looks_binary(&bytes) {
+            return Self::skipped(WarningKind::Binary, String::new());
+        }
+        let size = bytes.len() as u64;
+        let (text, encoding) = decode_text(bytes);
+        let details = FileDetails {
+            size,
+            modified,
+            encoding,
+            longest_line: text.lines().map(|l| l.chars().count()).max().unwrap_or(0),
+        };
+        Self {
+            stat: Some((count_lines(&text), details)),
+            warning: (encoding == TextEncoding::Lossy)
+                .then(|| (WarningKind::Encoding, String::new())),
+        }
+    }
+}
+
+// 选出待统计的文件，以及沿途找到的模块、插件和遍历警告
+#[derive(Default)]
+pub(crate) struct Selection {
+    pub paths: Vec<PathBuf>,
+    pub roots: ModuleRoots,
+    pub warnings: Vec<ScanWarning>,
+}
+
+// 带 BOM 的 UTF-16 按 UTF-16 解码
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let units = bytes.chunks_exact(2).map(|c| {
+        let pair = [c[0], c[1]];
+        if little_endian {
+            u16::from_le_bytes(pair)
+        } else {
+            u16::from_be_bytes(pair)
+        }
+    });
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn read_file(path: &Path, max_bytes: Option<u64>) -> io::Result<FileOutcome> {
+    let meta = fs::metadata(path)?;
+    if let Some(skipped) = FileOutcome::too_large(meta.len(), max_bytes) {
+        return Ok(skipped);
+    }
+    let bytes = fs::read(path)?;
+    Ok(FileOutcome::from_bytes(
+        bytes,
+        meta.modified().ok().map(DateTime::from),
+    ))
+}
+
+// 带 UTF-16 BOM 的文件本身含大量 0 字节，不能算作二进制
+fn looks_binary(bytes: &[u8]) -> bool {
+    !matches!(bytes, [0xFF, 0xFE, ..] | [0xFE, 0xFF, ..])
+        && bytes.iter().take(BINARY_SNIFF_LEN).any(|&b| b == 0)
+}
+
+// 返回解码后的文本及识别出的编码
+// 非 UTF-8 的行（如 GBK 注释）按替换字符照常计入
+pub fn decode_text(bytes: Vec<u8>) -> (String, TextEncoding) {
+    match bytes.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => (decode_utf16(rest, true), TextEncoding::Utf16Le),
+        [0xFE, 0xFF, rest @ ..] => (decode_utf16(rest, false), TextEncoding::Utf16Be),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => match std::str::from_utf8(rest) {
+            Ok(text) => (text.to_owned(), TextEncoding::Utf8Bom),
+            Err(_) => (
+                String::from_utf8_lossy(rest).into_owned(),
+                TextEncoding::Lossy,
+            ),
+        },
+        _ => match String::from_utf8(bytes) {
+            Ok(text) => (text, TextEncoding::Utf8),
+            Err(e) => (
+                String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                TextEncoding::Lossy,
+            ),
+        },
+    }
+}
+
+fn stat_file(path: &Path, max_bytes: Option<u64>) -> FileOutcome {
+    read_file(path, max_bytes)
+        .unwrap_or_else(|e| FileOutcome::skipped(WarningKind::Read, e.to_string()))
+}
+
+/* ---------------- 统计进度 ---------------- */
+// 统计线程写入，其他线程可随时读取
+#[derive(Default)]
+pub struct ScanProgress {
+    // 目录遍历中找到的待统计文件数
+    found: AtomicUsize,
+    // 遍历结束后 found 即为总数
+    walked: AtomicBool,
+    counted: AtomicUsize,
+}
+
+impl ScanProgress {
+    // 已统计的文件数，以及总数（遍历结束前未知）
+    pub fn snapshot(&self) -> (usize, Option<usize>) {
+        let counted = self.counted.load(Ordering::Relaxed);
+        let total = self
+            .walked
+            .load(Ordering::Acquire)
+            .then(|| self.found.load(Ordering::Relaxed));
+        (counted, total)
+    }
+
+    pub fn found(&self) -> usize {
+        self.found.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_total(&self, total: usize) {
+        self.found.store(total, Ordering::Relaxed);
+        self.walked.store(true, Ordering::Release);
+    }
+
+    pub(crate) fn advance(&self) {
+        self.counted.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// 读取一个文件并统计，磁盘和版本库各有实现
+pub(crate) type ReadFile<'a> = dyn Fn(&Path) -> FileOutcome + Sync + 'a;
+
+// 把待统计文件均分给若干线程，结果保持原有顺序
+fn stat_files(
+    paths: &[PathBuf],
+    workers: usize,
+    read: &ReadFile,
+    progress: &ScanProgress,
+) -> Vec<FileOutcome> {
+    let stat = |p: &PathBuf| {
+        let outcome = read(p);
+        if let Some((lines, _)) = &outcome.stat {
+            debug!("{}: {} lines", p.display(), lines.total);
+        }
+        progress.advance();
+        outcome
+    };
+    if workers <= 1 || paths.len() < 2 {
+        return paths.iter().map(stat).collect();
+    }
+
+    let chunk = paths.len().div_ceil(workers);
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk)
+            .map(|part| scope.spawn(move || part.iter().map(stat).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("统计线程异常退出"))
+            .collect()
+    })
+}
+
+pub fn stat_ue_code(root: &Path, opts: &ScanOptions) -> StatResult {
+    stat_ue_code_with_progress(root, opts, &ScanProgress::default())
+}
+
+pub fn stat_ue_code_with_progress(
+    root: &Path,
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> StatResult {
+    let started = Instant::now();
+    let mut selection = Selection::default();
+    let filter = ScanFilter::new(opts);
+    info!("scanning {}", root.display());
+
+    // 不在版本库中时记为警告，照常统计全部文件
+    let mut tracked = None;
+    if opts.tracked_only {
+        match git::tracked_files(root) {
+            Ok(files) => tracked = Some(files),
+            Err(e) => {
+                warn!("{}: {}", root.display(), e.message());
+                selection.warnings.push(ScanWarning {
+                    path: root.to_path_buf(),
+                    kind: WarningKind::Walk,
+                    message: format!("tracked files only: {}", e.message()),
+                });
+            }
+        }
+    }
+    // 与 git 相同，取不到 Perforce 的文件列表时记为警告
+    let mut mapped = None;
+    if opts.perforce {
+        let change = opts.p4_changelist.trim();
+        let files = if change.is_empty() {
+            perforce::client_files(root)
+        } else {
+            perforce::changelist_files(root, change)
+        };
+        match files {
+            Ok(files) => mapped = Some(files),
+            Err(e) => {
+                warn!("{}: {}", root.display(), e);
+                selection.warnings.push(ScanWarning {
+                    path: root.to_path_buf(),
+                    kind: WarningKind::Walk,
+                    message: format!("perforce: {}", e),
+                });
+            }
+        }
+    }
+    let is_tracked = |path: &Path| {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        [&tracked, &mapped].into_iter().all(|set| {
+            set.as_ref()
+                .is_none_or(|s: &HashSet<PathBuf>| s.contains(rel))
+        })
+    };
+
+    // 子模块多是第三方代码，没有列入设置的整个目录都不进入
+    let submodules = git::submodules(root).unwrap_or_default();
+    let excluded: Vec<PathBuf> = submodules
+        .iter()
+        .filter(|p| !opts.include_submodules.contains(p))
+        .map(|p| root.join(p))
+        .collect();
+
+    // 目录遍历在当前线程完成，读文件和数行交给工作线程
+    let walk = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !excluded.iter().any(|p| p == e.path()));
+    for entry in walk {
+        // 无权限等原因进不去的目录记为警告，继续遍历其余部分
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("{}", e);
+                selection.warnings.push(ScanWarning {
+                    path: e.path().map(Path::to_path_buf).unwrap_or_default(),
+                    kind: WarningKind::Walk,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+
+        if filter.skip(root, path) {
+            trace!("excluded {}", path.display());
+            continue;
+        }
+        selection.roots.record(path);
+        if filter.count(root, path) && is_tracked(path) {
+            selection.paths.push(entry.into_path());
+            progress.found.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let max_bytes = opts.max_bytes();
+    let read = |path: &Path| stat_file(path, max_bytes);
+    let mut result = count_paths(root, selection, opts, started, progress, &read);
+    result.submodules = submodule_stats(root, submodules, &result.files, opts);
+    result
+}
+
+// 统计调用方给出的文件列表，不遍历目录也不按规则过滤；相对路径以 root 为基准
+pub fn stat_file_list(
+    root: &Path,
+    files: &[PathBuf],
+    opts: &ScanOptions,
+    progress: &ScanProgress,
+) -> StatResult {
+    let started = Instant::now();
+    let mut selection = Selection {
+        paths: files.iter().map(|f| root.join(f)).collect(),
+        ..Default::default()
+    };
+    let mut seen_dirs = HashSet::new();
+    info!(
+        "counting {} listed files under {}",
+        selection.paths.len(),
+        root.display()
+    );
+
+    // 没有遍历目录，只在各文件的上级目录中查找 Build.cs 和 uplugin
+    for path in &selection.paths {
+        for dir in path.ancestors().skip(1).take_while(|d| d.starts_with(root)) {
+            if !seen_dirs.insert(dir.to_path_buf()) {
+                break;
+            }
+            for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+                selection.roots.record(&entry.path());
+            }
+        }
+    }
+
+    let max_bytes = opts.max_bytes();
+    let read = |path: &Path| stat_file(path, max_bytes);
+    count_paths(root, selection, opts, started, progress, &read)
+}
+
+// 读取并统计选出的文件，再归属模块
+pub(crate) fn count_paths(
+    root: &Path,
+    selection: Selection,
+    opts: &ScanOptions,
+    started: Instant,
+    progress: &ScanProgress,
+    read: &ReadFile,
+) -> StatResult {
+    let Selection {
+        paths,
+        roots,
+        warnings,
+    } = selection;
+    let mut result = StatResult {
+        warnings,
+        ..Default::default()
+    };
+    progress.set_total(paths.len());
+    let outcomes = stat_files(&paths, opts.worker_count(), read, progress);
+    for (path, outcome) in paths.into_iter().zip(outcomes) {
+        if let Some((kind, message)) = outcome.warning {
+            warn!("{}: {}", path.display(), message);
+            result.warnings.push(ScanWarning {
+                path: path.clone(),
+                kind,
+                message,
+            });
+        }
+        let Some((lines, details)) = outcome.stat else {
+            continue;
+        };
+        result.totals += lines;
+        result.files.push(FileStat {
+            rel_path: path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .display()
+                .to_string(),
+            path,
+            module: None,
+            plugin: None,
+            lines,
+            details,
+            churn: None,
+            last_commit: None,
+        });
+    }
+
+    // 文件和 Build.cs 的遍历先后不确定，全部走完后再归属模块
+    for file in &mut result.files {
+        roots.assign(root, file);
+    }
+    result.modules = aggregate_modules(&result.files);
+
+    result.finished_at = Some(Local::now());
+    result.elapsed = started.elapsed();
+    info!(
+        "{}: {} files, {} lines in {:.2?}",
+        root.display(),
+        result.files.len(),
+        result.totals.total,
+        result.elapsed
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_line_by_leading_token() {
+        for (line, kind) in [
+            ("", LineKind::Blank),
+            ("  \t", LineKind::Blank),
+            ("// comment", LineKind::Comment),
+            ("  /* block", LineKind::Comment),
+            ("   * continued", LineKind::Comment),
+            ("int a; // trailing comment", LineKind::Code),
+            ("}", LineKind::Code),
+        ] {
+            assert!(classify_line(line) == kind, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn count_lines_splits_includes_and_reflection() {
+        let counts = count_lines(concat!(
+            "#include \"A.h\"\n",
+            "#  include <B.h>\n",
+            "UCLASS()\n",
+            "UPROPERTY (EditAnywhere)\n",
+            "class GAME_API UCLASS_API;\n",
+            "\n",
+            "// c\n",
+        ));
+        assert_eq!(
+            (
+                counts.total,
+                counts.code,
+                counts.comment,
+                counts.blank,
+                counts.includes,
+                counts.reflection
+            ),
+            (7, 5, 1, 1, 2, 2)
+        );
+    }
+
+    #[test]
+    fn decode_text_detects_encoding() {
+        let utf16 = |little_endian: bool| -> Vec<u8> {
+            let bom: [u8; 2] = if little_endian {
+                [0xFF, 0xFE]
+            } else {
+                [0xFE, 0xFF]
+            };
+            let units = "a\n中".encode_utf16().flat_map(|u| {
+                if little_endian {
+                    u.to_le_bytes()
+                } else {
+                    u.to_be_bytes()
+                }
+            });
+            bom.into_iter().chain(units).collect()
+        };
+        let cases = [
+            ("a\n中".as_bytes().to_vec(), "a\n中", TextEncoding::Utf8),
+            (
+                [&[0xEF, 0xBB, 0xBF][..], "a\n中".as_bytes()].concat(),
+                "a\n中",
+                TextEncoding::Utf8Bom,
+            ),
+            (utf16(true), "a\n中", TextEncoding::Utf16Le),
+            (utf16(false), "a\n中", TextEncoding::Utf16Be),
+            (vec![b'a', 0xFF, b'b'], "a\u{FFFD}b", TextEncoding::Lossy),
+        ];
+        for (bytes, text, encoding) in cases {
+            let (decoded, detected) = decode_text(bytes);
+            assert_eq!(decoded, text);
+            assert!(detected == encoding, "{}", encoding.name());
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+use crate::format;
+use crate::i18n::Texts;
+use crate::stat::SubmoduleStat;
+
+/* ---------------- git 子模块 ---------------- */
+// 勾选要统计的子模块，改动写入 include 后返回 true，由调用方重新统计
+pub fn show_submodules(
+    ui: &mut egui::Ui,
+    submodules: &[SubmoduleStat],
+    include: &mut Vec<String>,
+    t: &Texts,
+) -> bool {
+    if submodules.is_empty() {
+        ui.weak(t.submodules_empty);
+        return false;
+    }
+    ui.weak(t.submodules_hint);
+    ui.add_space(4.0);
+    let mut changed = false;
+    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+    TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(60.0))
+        .column(Column::remainder().at_least(160.0).clip(true))
+        .columns(Column::auto().at_least(70.0), 3)
+        .header(row_height + 6.0, |mut header| {
+            for label in [
+                t.submodule_count,
+                t.col_submodule,
+                t.files_unit,
+                t.col_code,
+                t.col_comment,
+            ] {
+                header.col(|ui| {
+                    ui.strong(label);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, submodules.len(), |mut row| {
+                let s = &submodules[row.index()];
+                row.col(|ui| {
+                    let mut included = s.included;
+                    if ui.checkbox(&mut included, "").changed() {
+                        include.retain(|p| *p != s.path);
+                        if included {
+                            include.push(s.path.clone());
+                        }
+                        changed = true;
+                    }
+                });
+                row.col(|ui| {
+                    ui.label(&s.path);
+                });
+                if !s.included {
+                    row.col(|ui| {
+                        ui.weak(t.submodule_excluded);
+                    });
+                    return;
+                }
+                row.col(|ui| {
+                    ui.label(format::count(s.files, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(s.lines.code, t));
+                });
+                row.col(|ui| {
+                    ui.label(format::count(s.lines.comment, t));
+                });
+            });
+        });
+    changed
+}
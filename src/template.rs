@@ -0,0 +1,87 @@
+use chrono::SecondsFormat;
+use handlebars::{handlebars_helper, no_escape, Handlebars, RenderError, RenderErrorReason};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::json::{JsonOptions, JsonReport};
+use crate::stat::StatResult;
+
+/* ---------------- 自定义报告模板 ---------------- */
+// 用 Handlebars 模板自定义报告版式；上下文与 JSON 报告相同，另加标题和生成信息
+const TOP_FILES: usize = 20;
+
+// 内置模板，名称供命令行 --template 使用
+pub const BUILTIN: [(&str, &str); 2] = [
+    ("markdown", include_str!("../templates/summary.md.hbs")),
+    ("html", include_str!("../templates/summary.html.hbs")),
+];
+
+pub fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN.iter().find(|(n, _)| *n == name).map(|(_, t)| *t)
+}
+
+// 按模板文件名判断是否输出 HTML，如 report.html.hbs；只有 HTML 模板转义 {{ }} 中的内容
+pub fn is_html(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    let name = name.strip_suffix(".hbs").unwrap_or(&name);
+    name == "html" || name.ends_with(".html") || name.ends_with(".htm")
+}
+
+#[derive(Serialize)]
+struct Context<'a> {
+    title: String,
+    generator: String,
+    #[serde(flatten)]
+    report: JsonReport<'a>,
+}
+
+// {{percent part whole}}，whole 为 0 时为 0.0%
+handlebars_helper!(percent: |part: u64, whole: u64| {
+    let share = if whole == 0 { 0.0 } else { part as f64 / whole as f64 };
+    format!("{:.1}%", share * 100.0)
+});
+
+// {{comment_ratio lines}}，与界面中的注释率相同：注释行占代码与注释之和的比例
+handlebars_helper!(comment_ratio: |lines: Value| {
+    let get = |key: &str| lines.get(key).and_then(Value::as_u64).unwrap_or(0);
+    let (code, comment) = (get("code"), get("comment"));
+    let ratio = if code + comment == 0 { 0.0 } else { comment as f64 / (code + comment) as f64 };
+    format!("{:.1}%", ratio * 100.0)
+});
+
+pub fn render(
+    template: &str,
+    html: bool,
+    root: &Path,
+    result: &StatResult,
+) -> Result<String, RenderError> {
+    let mut registry = Handlebars::new();
+    if !html {
+        registry.register_escape_fn(no_escape);
+    }
+    registry.register_helper("percent", Box::new(percent));
+    registry.register_helper("comment_ratio", Box::new(comment_ratio));
+
+    let opts = JsonOptions {
+        files: true,
+        top: Some(TOP_FILES),
+        tree: true,
+    };
+    let scanned_at = result
+        .finished_at
+        .map(|at| at.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let context = Context {
+        title: root
+            .file_name()
+            .unwrap_or(root.as_os_str())
+            .to_string_lossy()
+            .into_owned(),
+        generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        report: JsonReport::new(root, result, opts),
+    };
+    // JSON 报告中的时间带小数秒，放进报告里太长，去掉小数秒后再交给模板
+    let mut data = serde_json::to_value(&context).map_err(RenderErrorReason::from)?;
+    data["scanned_at"] = scanned_at.into();
+    registry.render_template(template, &data)
+}
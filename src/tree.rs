@@ -0,0 +1,54 @@
+use eframe::egui;
+
+use crate::format;
+use crate::heatmap::Heatmap;
+use crate::i18n::Texts;
+use crate::stat::DirNode;
+
+/* ---------------- 树视图 ---------------- */
+fn node_label(node: &DirNode, t: &Texts, heatmap: &Heatmap) -> egui::RichText {
+    let text = format!(
+        "{}    {} {} / {} {} / {} {}",
+        node.name,
+        t.col_code,
+        format::count(node.lines.code, t),
+        t.col_total,
+        format::count(node.lines.total, t),
+        t.files_unit,
+        format::count(node.files, t)
+    );
+    match heatmap.color(&node.lines) {
+        Some(color) => egui::RichText::new(text).color(color),
+        None => egui::RichText::new(text),
+    }
+}
+
+fn show_node(ui: &mut egui::Ui, node: &DirNode, t: &Texts, heatmap: &Heatmap, default_open: bool) {
+    if node.children.is_empty() {
+        // 叶子目录没有可展开的内容，缩进对齐后直接显示
+        ui.horizontal(|ui| {
+            ui.add_space(ui.spacing().indent);
+            ui.label(node_label(node, t, heatmap));
+        });
+        return;
+    }
+
+    egui::CollapsingHeader::new(node_label(node, t, heatmap))
+        .id_source(("dir_node", &node.rel_path))
+        .default_open(default_open)
+        .show(ui, |ui| {
+            for child in &node.children {
+                show_node(ui, child, t, heatmap, false);
+            }
+        });
+}
+
+pub fn show_tree(ui: &mut egui::Ui, root: &DirNode, t: &Texts, heatmap: &Heatmap) {
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                show_node(ui, root, t, heatmap, true);
+            });
+        });
+}
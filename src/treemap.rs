@@ -0,0 +1,223 @@
+use eframe::egui::{self, ecolor::Hsva, Color32, Rect, Vec2};
+
+use crate::format;
+use crate::i18n::Texts;
+use crate::stat::DirNode;
+
+/* ---------------- 矩形树图布局 ---------------- */
+// 一行内最差的长宽比，越接近 1 越好
+fn worst_ratio(row: &[f32], sum: f32, short: f32, scale: f32) -> f32 {
+    let thickness = sum * scale / short;
+    row.iter()
+        .map(|v| {
+            let len = v * scale / thickness;
+            (len / thickness).max(thickness / len)
+        })
+        .fold(0.0, f32::max)
+}
+
+// Squarified 算法：values 需从大到小排列且都大于 0
+fn squarify(values: &[f32], rect: Rect) -> Vec<Rect> {
+    let mut out = Vec::with_capacity(values.len());
+    let mut rect = rect;
+    let mut remaining: f32 = values.iter().sum();
+    let mut start = 0;
+
+    while start < values.len() {
+        let short = rect.width().min(rect.height());
+        let scale = rect.area() / remaining;
+
+        // 往当前行里加方块，直到长宽比开始变差
+        let mut end = start + 1;
+        let mut sum = values[start];
+        let mut best = worst_ratio(&values[start..end], sum, short, scale);
+        while end < values.len() {
+            let ratio = worst_ratio(&values[start..=end], sum + values[end], short, scale);
+            if ratio > best {
+                break;
+            }
+            best = ratio;
+            sum += values[end];
+            end += 1;
+        }
+
+        // 沿短边排成一条，剩下的区域继续布局
+        let thickness = sum * scale / short;
+        let mut offset = 0.0;
+        for v in &values[start..end] {
+            let len = v * scale / thickness;
+            let cell = if rect.width() >= rect.height() {
+                Rect::from_min_size(rect.min + Vec2::new(0.0, offset), Vec2::new(thickness, len))
+            } else {
+                Rect::from_min_size(rect.min + Vec2::new(offset, 0.0), Vec2::new(len, thickness))
+            };
+            out.push(cell);
+            offset += len;
+        }
+        if rect.width() >= rect.height() {
+            rect.min.x += thickness;
+        } else {
+            rect.min.y += thickness;
+        }
+
+        remaining -= sum;
+        start = end;
+    }
+    out
+}
+
+/* ---------------- 矩形树图视图 ---------------- */
+// 面积为有效代码行数，点击方块进入子目录
+#[derive(Default)]
+pub struct Treemap {
+    // 当前所在目录，从根节点往下的目录名
+    path: Vec<String>,
+}
+
+fn cell_color(index: usize, total: usize) -> Color32 {
+    let hue = index as f32 / total.max(1) as f32;
+    Hsva::new(hue, 0.45, 0.8, 1.0).into()
+}
+
+impl Treemap {
+    // 沿保存的路径往下找，重新统计后找不到的部分丢弃
+    fn current<'a>(&mut self, root: &'a DirNode) -> &'a DirNode {
+        let mut node = root;
+        for (depth, name) in self.path.iter().enumerate() {
+            match node.children.iter().find(|c| &c.name == name) {
+                Some(child) => node = child,
+                None => {
+                    self.path.truncate(depth);
+                    break;
+                }
+            }
+        }
+        node
+    }
+
+    fn show_breadcrumb(&mut self, ui: &mut egui::Ui, root: &DirNode) {
+        let mut jump = None;
+        ui.horizontal_wrapped(|ui| {
+            if ui
+                .selectable_label(self.path.is_empty(), &root.name)
+                .clicked()
+            {
+                jump = Some(0);
+            }
+            for (depth, name) in self.path.iter().enumerate() {
+                ui.label("›");
+                let last = depth + 1 == self.path.len();
+                if ui.selectable_label(last, name).clicked() {
+                    jump = Some(depth + 1);
+                }
+            }
+        });
+        if let Some(depth) = jump {
+            self.path.truncate(depth);
+        }
+    }
+
+    // 返回整个方块区域的响应，供另存为图片使用
+    pub fn show(&mut self, ui: &mut egui::Ui, root: &DirNode, t: &Texts) -> Option<egui::Response> {
+        self.show_breadcrumb(ui, root);
+        ui.weak(t.treemap_hint);
+        ui.add_space(4.0);
+
+        let node = self.current(root);
+
+        // 子目录之外，直接放在本目录下的文件合成一块
+        let mut cells: Vec<(Option<&DirNode>, usize)> = node
+            .children
+            .iter()
+            .filter(|c| c.lines.code > 0)
+            .map(|c| (Some(c), c.lines.code))
+            .collect();
+        let in_children: usize = node.children.iter().map(|c| c.lines.code).sum();
+        let loose = node.lines.code.saturating_sub(in_children);
+        if loose > 0 {
+            cells.push((None, loose));
+        }
+        cells.sort_by_key(|&(_, code)| std::cmp::Reverse(code));
+
+        let (rect, mut area) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click());
+        if cells.is_empty() || rect.area() <= 0.0 {
+            return None;
+        }
+
+        let values: Vec<f32> = cells.iter().map(|&(_, code)| code as f32).collect();
+        let painter = ui.painter_at(rect);
+        let mut enter = None;
+        for (i, (cell, &(child, code))) in squarify(&values, rect).iter().zip(&cells).enumerate() {
+            let cell = cell.shrink(1.0);
+            let name = child.map_or(t.treemap_loose_files, |c| c.name.as_str());
+            let fill = match child {
+                Some(_) => cell_color(i, cells.len()),
+                None => Color32::GRAY,
+            };
+            painter.rect_filled(cell, 2.0, fill);
+
+            // 方块太小时不写字，悬停仍可看到详情
+            if cell.width() > 40.0 && cell.height() > 18.0 {
+                painter.with_clip_rect(cell.intersect(rect)).text(
+                    cell.min + Vec2::splat(4.0),
+                    egui::Align2::LEFT_TOP,
+                    format!("{}\n{}", name, format::count(code, t)),
+                    egui::FontId::proportional(12.0),
+                    Color32::from_gray(20),
+                );
+            }
+
+            let response = ui
+                .interact(
+                    cell,
+                    ui.id().with(("treemap_cell", i)),
+                    egui::Sense::click(),
+                )
+                .on_hover_text(format!(
+                    "{}\n{}{}{}",
+                    child.map_or(name, |c| c.rel_path.as_str()),
+                    t.col_code,
+                    t.colon,
+                    format::count(code, t)
+                ));
+            if response.hovered() {
+                painter.rect_stroke(cell, 2.0, ui.visuals().widgets.hovered.fg_stroke);
+            }
+            // 方块上的右键也算在整个图上
+            area = area.union(response.clone());
+            if let Some(child) = child {
+                if response.clicked() && !child.children.is_empty() {
+                    enter = Some(child.name.clone());
+                }
+            }
+        }
+        if let Some(name) = enter {
+            self.path.push(name);
+        }
+        Some(area)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squarify_fills_rect_in_proportion() {
+        let rect = Rect::from_min_size(egui::pos2(10.0, 20.0), Vec2::new(6.0, 4.0));
+        let values = [6.0, 6.0, 4.0, 3.0, 2.0, 2.0, 1.0];
+        let cells = squarify(&values, rect);
+        assert_eq!(cells.len(), values.len());
+        for (cell, value) in cells.iter().zip(values) {
+            assert!((cell.area() - value).abs() < 1e-3, "{:?}", cell);
+            assert!(rect.expand(1e-3).contains_rect(*cell), "{:?}", cell);
+        }
+        // 方块互不重叠
+        for (i, a) in cells.iter().enumerate() {
+            for b in &cells[i + 1..] {
+                let overlap = a.intersect(*b);
+                assert!(overlap.width() < 1e-3 || overlap.height() < 1e-3);
+            }
+        }
+    }
+}
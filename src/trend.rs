@@ -0,0 +1,180 @@
+use eframe::egui;
+use rfd::FileDialog;
+use std::{
+    fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use crate::charts;
+use crate::csv;
+use crate::git::{self, Sampling, TrendPoint};
+use crate::history::HistoryEntry;
+use crate::i18n::Texts;
+use crate::project::dir_display_name;
+use crate::stat::{ScanOptions, ScanProgress};
+
+// 沿提交历史取样的上限
+const MAX_POINTS: usize = 500;
+
+/* ---------------- 提交历史上的行数趋势 ---------------- */
+// 每个取样点都要从对象库完整统计一次，只在点击后于后台线程进行
+pub struct TrendView {
+    by_days: bool,
+    every: usize,
+    max_points: usize,
+    // 已完成的取样点数和总数
+    done: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>,
+    pending: Option<Receiver<Result<Vec<TrendPoint>, String>>>,
+    points: Vec<TrendPoint>,
+    // 同样的数据，供趋势图使用
+    entries: Vec<HistoryEntry>,
+    error: Option<String>,
+}
+
+impl Default for TrendView {
+    fn default() -> Self {
+        Self {
+            by_days: false,
+            every: 10,
+            max_points: 100,
+            done: Arc::default(),
+            total: Arc::default(),
+            pending: None,
+            points: Vec::new(),
+            entries: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+// 借用历史记录的结构，与历史视图共用同一张图
+fn to_entry(root: &Path, point: &TrendPoint) -> HistoryEntry {
+    HistoryEntry {
+        root: root.to_path_buf(),
+        timestamp: point.time,
+        revision: Some(point.revision.clone()),
+        files: point.files,
+        lines: point.lines,
+    }
+}
+
+impl TrendView {
+    fn start(&mut self, root: &Path, opts: &ScanOptions, ctx: &egui::Context) {
+        let sampling = if self.by_days {
+            Sampling::Days(self.every as u32)
+        } else {
+            Sampling::Commits(self.every)
+        };
+        let (tx, rx) = mpsc::channel();
+        let (root, opts, max_points) = (root.to_path_buf(), opts.clone(), self.max_points);
+        let (done, total) = (self.done.clone(), self.total.clone());
+        done.store(0, Ordering::Relaxed);
+        total.store(0, Ordering::Relaxed);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let walk = || -> Result<Vec<TrendPoint>, git2::Error> {
+                let samples = git::sample_commits(&root, "HEAD", sampling, max_points)?;
+                total.store(samples.len(), Ordering::Relaxed);
+                let mut points = Vec::with_capacity(samples.len());
+                for sample in samples {
+                    let point = git::trend_point(&root, sample, &opts, &ScanProgress::default())?;
+                    points.push(point);
+                    done.fetch_add(1, Ordering::Relaxed);
+                    ctx.request_repaint();
+                }
+                Ok(points)
+            };
+            let _ = tx.send(walk().map_err(|e| e.message().to_owned()));
+            ctx.request_repaint();
+        });
+        self.pending = Some(rx);
+        self.error = None;
+    }
+
+    fn poll(&mut self, root: &Path) {
+        let Some(rx) = &self.pending else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(points)) => {
+                self.pending = None;
+                self.entries = points.iter().map(|p| to_entry(root, p)).collect();
+                self.points = points;
+            }
+            Ok(Err(e)) => {
+                self.pending = None;
+                self.error = Some(e);
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.pending = None,
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: &Path,
+        opts: &ScanOptions,
+        t: &Texts,
+    ) -> Option<egui::Response> {
+        self.poll(root);
+        let running = self.pending.is_some();
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!running, |ui| {
+                ui.label(t.trend_every);
+                ui.add(egui::DragValue::new(&mut self.every).clamp_range(1..=10_000));
+                ui.selectable_value(&mut self.by_days, false, t.trend_commits);
+                ui.selectable_value(&mut self.by_days, true, t.trend_days);
+                ui.separator();
+                ui.label(t.trend_max);
+                ui.add(egui::DragValue::new(&mut self.max_points).clamp_range(2..=MAX_POINTS));
+                if ui.button(t.trend_run).on_hover_text(t.trend_hint).clicked() {
+                    self.start(root, opts, ui.ctx());
+                }
+            });
+            if running {
+                ui.spinner();
+                ui.weak(format!(
+                    "{} / {}",
+                    self.done.load(Ordering::Relaxed),
+                    self.total.load(Ordering::Relaxed)
+                ));
+            }
+            if !self.points.is_empty() && ui.button(t.trend_export).clicked() {
+                if let Err(e) = self.export(root) {
+                    self.error = Some(format!("{}{}{}", t.export_failed, t.colon, e));
+                }
+            }
+        });
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        ui.add_space(4.0);
+        if self.points.is_empty() {
+            if !running {
+                ui.weak(t.trend_empty);
+            }
+            return None;
+        }
+        let entries: Vec<&HistoryEntry> = self.entries.iter().collect();
+        charts::history_chart(ui, &entries, t)
+    }
+
+    fn export(&self, root: &Path) -> io::Result<()> {
+        let Some(path) = FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(format!("{}-trend.csv", dir_display_name(root)))
+            .save_file()
+        else {
+            return Ok(());
+        };
+        fs::write(path, csv::trend_csv(&self.points))
+    }
+}
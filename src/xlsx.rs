@@ -0,0 +1,201 @@
+use chrono::SecondsFormat;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::{io, path::Path};
+
+use crate::cloc;
+use crate::history::HistoryEntry;
+use crate::stat::{LineCounts, StatResult};
+
+/* ---------------- Excel 工作簿 ---------------- */
+// 表头与 CSV 导出一致，固定为英文；数值写成数字单元格，方便直接做透视表
+const LINE_HEADERS: [&str; 7] = [
+    "total",
+    "code",
+    "comment",
+    "blank",
+    "comment_ratio",
+    "includes",
+    "reflection",
+];
+
+struct Sheet<'a> {
+    sheet: &'a mut Worksheet,
+    bold: &'a Format,
+    percent: &'a Format,
+    row: u32,
+}
+
+impl<'a> Sheet<'a> {
+    fn new(
+        workbook: &'a mut Workbook,
+        name: &str,
+        bold: &'a Format,
+        percent: &'a Format,
+    ) -> Result<Self, XlsxError> {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(name)?;
+        Ok(Self {
+            sheet,
+            bold,
+            percent,
+            row: 0,
+        })
+    }
+
+    // 表头行加粗并冻结
+    fn header(&mut self, columns: &[&str]) -> Result<(), XlsxError> {
+        for (col, name) in columns.iter().enumerate() {
+            self.sheet
+                .write_string_with_format(self.row, col as u16, *name, self.bold)?;
+        }
+        self.sheet.set_freeze_panes(self.row + 1, 0)?;
+        self.row += 1;
+        Ok(())
+    }
+
+    fn text(&mut self, col: u16, text: &str) -> Result<(), XlsxError> {
+        self.sheet.write_string(self.row, col, text)?;
+        Ok(())
+    }
+
+    fn number(&mut self, col: u16, value: usize) -> Result<(), XlsxError> {
+        self.sheet.write_number(self.row, col, value as f64)?;
+        Ok(())
+    }
+
+    // 从 col 开始写 LINE_HEADERS 对应的各列
+    fn lines(&mut self, col: u16, lines: &LineCounts) -> Result<(), XlsxError> {
+        self.number(col, lines.total)?;
+        self.number(col + 1, lines.code)?;
+        self.number(col + 2, lines.comment)?;
+        self.number(col + 3, lines.blank)?;
+        self.sheet.write_number_with_format(
+            self.row,
+            col + 4,
+            lines.comment_ratio(),
+            self.percent,
+        )?;
+        self.number(col + 5, lines.includes)?;
+        self.number(col + 6, lines.reflection)
+    }
+
+    fn next_row(&mut self) {
+        self.row += 1;
+    }
+
+    fn finish(self) {
+        self.sheet.autofit();
+    }
+}
+
+fn with_lines<'a>(columns: &[&'a str]) -> Vec<&'a str> {
+    columns.iter().copied().chain(LINE_HEADERS).collect()
+}
+
+fn summary_sheet(s: &mut Sheet, root: &Path, result: &StatResult) -> Result<(), XlsxError> {
+    s.header(&["metric", "value"])?;
+    s.text(0, "root")?;
+    s.text(1, &root.display().to_string())?;
+    s.next_row();
+    if let Some(at) = result.finished_at {
+        s.text(0, "scanned_at")?;
+        s.text(1, &at.to_rfc3339_opts(SecondsFormat::Secs, false))?;
+        s.next_row();
+    }
+    let totals = &result.totals;
+    for (name, value) in [
+        ("files", result.files.len()),
+        ("total", totals.total),
+        ("code", totals.code),
+        ("comment", totals.comment),
+        ("blank", totals.blank),
+        ("includes", totals.includes),
+        ("reflection", totals.reflection),
+    ] {
+        s.text(0, name)?;
+        s.number(1, value)?;
+        s.next_row();
+    }
+    s.text(0, "comment_ratio")?;
+    s.sheet
+        .write_number_with_format(s.row, 1, totals.comment_ratio(), s.percent)?;
+    Ok(())
+}
+
+fn modules_sheet(s: &mut Sheet, result: &StatResult) -> Result<(), XlsxError> {
+    s.header(&with_lines(&["module", "plugin", "files"]))?;
+    for module in &result.modules {
+        s.text(0, module.name.as_deref().unwrap_or_default())?;
+        s.text(1, module.plugin.as_deref().unwrap_or_default())?;
+        s.number(2, module.files)?;
+        s.lines(3, &module.lines)?;
+        s.next_row();
+    }
+    Ok(())
+}
+
+fn files_sheet(s: &mut Sheet, result: &StatResult) -> Result<(), XlsxError> {
+    let mut columns = with_lines(&["path", "language", "module", "plugin"]);
+    columns.extend(["size", "longest_line", "encoding", "modified"]);
+    s.header(&columns)?;
+    for file in &result.files {
+        let details = &file.details;
+        s.text(0, &file.rel_path)?;
+        s.text(1, &cloc::language(&file.extension()))?;
+        s.text(2, file.module.as_deref().unwrap_or_default())?;
+        s.text(3, file.plugin.as_deref().unwrap_or_default())?;
+        s.lines(4, &file.lines)?;
+        s.sheet.write_number(s.row, 11, details.size as f64)?;
+        s.number(12, details.longest_line)?;
+        s.text(13, details.encoding.name())?;
+        if let Some(modified) = details.modified {
+            s.text(14, &modified.to_rfc3339_opts(SecondsFormat::Secs, false))?;
+        }
+        s.next_row();
+    }
+    Ok(())
+}
+
+fn history_sheet(s: &mut Sheet, history: &[&HistoryEntry]) -> Result<(), XlsxError> {
+    s.header(&with_lines(&["timestamp", "revision", "files"]))?;
+    for entry in history {
+        s.text(
+            0,
+            &entry.timestamp.to_rfc3339_opts(SecondsFormat::Secs, false),
+        )?;
+        s.text(1, entry.revision.as_deref().unwrap_or_default())?;
+        s.number(2, entry.files)?;
+        s.lines(3, &entry.lines)?;
+        s.next_row();
+    }
+    Ok(())
+}
+
+// history 为该工程的历史记录，按时间先后
+pub fn result_xlsx(
+    root: &Path,
+    result: &StatResult,
+    history: &[&HistoryEntry],
+) -> io::Result<Vec<u8>> {
+    let build = || -> Result<Vec<u8>, XlsxError> {
+        let mut workbook = Workbook::new();
+        let bold = Format::new().set_bold();
+        let percent = Format::new().set_num_format("0.0%");
+
+        let mut s = Sheet::new(&mut workbook, "Summary", &bold, &percent)?;
+        summary_sheet(&mut s, root, result)?;
+        s.finish();
+        let mut s = Sheet::new(&mut workbook, "Per-Module", &bold, &percent)?;
+        modules_sheet(&mut s, result)?;
+        s.finish();
+        let mut s = Sheet::new(&mut workbook, "Per-File", &bold, &percent)?;
+        files_sheet(&mut s, result)?;
+        s.finish();
+        let mut s = Sheet::new(&mut workbook, "History", &bold, &percent)?;
+        history_sheet(&mut s, history)?;
+        s.finish();
+
+        workbook.save_to_buffer()
+    };
+    build().map_err(io::Error::other)
+}